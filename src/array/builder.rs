@@ -0,0 +1,717 @@
+//! Builders that append in bulk and from borrowed values.
+//!
+//! Unlike [`Array::push`], which takes an owned item, builders copy from slices and item
+//! references, so no temporary allocation is needed per row. Nullable builders also append
+//! runs of nulls at once.
+
+use std::hash::{BuildHasher, Hash};
+
+use ahash::RandomState;
+
+use super::{
+    id::IdArray,
+    idvec::IdVec,
+    list::{ConstSizeListArray, ListArray, OptionListArray},
+    option::OptionArray,
+    primitive::PrimitiveArray,
+    slotmap::SlotMap,
+    Array,
+};
+use crate::{bitvec::BitVec, primitive::Primitive, scalar::list::OptionSlice};
+
+pub trait ArrayBuilder {
+    type Array: Array;
+
+    /// Appends the row [`Array::push_zero`] would, used as the placeholder for nulls.
+    fn append_zero(&mut self);
+
+    fn append_zeros(&mut self, count: usize) {
+        for _ in 0..count {
+            self.append_zero();
+        }
+    }
+
+    /// Appends every row of `array`.
+    fn append_array(&mut self, array: &Self::Array);
+
+    /// Reserves room for `additional` more rows.
+    fn reserve(&mut self, additional: usize);
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn finish(self) -> Self::Array;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PrimitiveBuilder<P> {
+    data: Vec<P>,
+}
+
+impl<P: Primitive> PrimitiveBuilder<P> {
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+        }
+    }
+
+    #[inline]
+    pub fn append_value(&mut self, value: P) {
+        self.data.push(value);
+    }
+
+    #[inline]
+    pub fn append_slice(&mut self, values: &[P]) {
+        self.data.extend_from_slice(values);
+    }
+}
+
+impl<P: Primitive> ArrayBuilder for PrimitiveBuilder<P> {
+    type Array = PrimitiveArray<P>;
+
+    #[inline]
+    fn append_zero(&mut self) {
+        self.data.push(P::default());
+    }
+
+    #[inline]
+    fn append_zeros(&mut self, count: usize) {
+        self.data.resize(self.data.len() + count, P::default());
+    }
+
+    #[inline]
+    fn append_array(&mut self, array: &Self::Array) {
+        self.append_slice(&array.data);
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline]
+    fn finish(self) -> Self::Array {
+        PrimitiveArray { data: self.data }
+    }
+}
+
+/// Lists are either appended whole or element by element, closing each with
+/// [`finish_list`](Self::finish_list).
+#[derive(Debug, Clone, Default)]
+pub struct ListBuilder<P> {
+    data: Vec<P>,
+    offsets: Vec<usize>,
+    open: bool,
+}
+
+impl<P: Primitive> ListBuilder<P> {
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Room for `lists` lists holding `values` elements in total.
+    #[inline]
+    pub fn with_capacity(lists: usize, values: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(values),
+            offsets: Vec::with_capacity(lists),
+            open: false,
+        }
+    }
+
+    /// Appends `values` as one list, after any elements of the current list.
+    #[inline]
+    pub fn append_value(&mut self, values: &[P]) {
+        self.data.extend_from_slice(values);
+        self.finish_list();
+    }
+
+    /// Appends each of `lists` as one list.
+    pub fn append_slice<L: AsRef<[P]>>(&mut self, lists: &[L]) {
+        self.reserve(lists.len());
+        for list in lists {
+            self.append_value(list.as_ref());
+        }
+    }
+
+    #[inline]
+    pub fn append_value_to_current_list(&mut self, value: P) {
+        self.data.push(value);
+        self.open = true;
+    }
+
+    #[inline]
+    pub fn append_values_to_current_list(&mut self, values: &[P]) {
+        self.data.extend_from_slice(values);
+        self.open = true;
+    }
+
+    /// Closes the current list, which is empty if nothing was appended to it.
+    #[inline]
+    pub fn finish_list(&mut self) {
+        self.offsets.push(self.data.len());
+        self.open = false;
+    }
+
+    #[inline]
+    pub fn reserve_values(&mut self, additional: usize) {
+        self.data.reserve(additional);
+    }
+}
+
+impl<P: Primitive> ArrayBuilder for ListBuilder<P> {
+    type Array = ListArray<P>;
+
+    #[inline]
+    fn append_zero(&mut self) {
+        self.finish_list();
+    }
+
+    fn append_array(&mut self, array: &Self::Array) {
+        if self.open {
+            self.finish_list();
+        }
+        let base = self.data.len();
+        self.data.extend_from_slice(&array.data);
+        self.offsets
+            .extend(array.offsets.iter().map(|offset| base + offset));
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.offsets.reserve(additional);
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// A list still open is closed first.
+    #[inline]
+    fn finish(mut self) -> Self::Array {
+        if self.open {
+            self.finish_list();
+        }
+        ListArray {
+            data: self.data,
+            offsets: self.offsets,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConstSizeListBuilder<P, const SIZE: usize> {
+    data: Vec<P>,
+}
+
+impl<P: Primitive, const SIZE: usize> ConstSizeListBuilder<P, SIZE> {
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity * SIZE),
+        }
+    }
+
+    #[inline]
+    pub fn append_value(&mut self, value: &[P; SIZE]) {
+        self.data.extend_from_slice(value);
+    }
+
+    /// Appends `values.len() / SIZE` lists; the length must be a multiple of `SIZE`.
+    #[inline]
+    pub fn append_slice(&mut self, values: &[P]) {
+        assert!(
+            values.len().is_multiple_of(SIZE),
+            "{} values do not fill lists of {SIZE}",
+            values.len()
+        );
+        self.data.extend_from_slice(values);
+    }
+}
+
+impl<P: Primitive, const SIZE: usize> ArrayBuilder for ConstSizeListBuilder<P, SIZE> {
+    type Array = ConstSizeListArray<P, SIZE>;
+
+    #[inline]
+    fn append_zero(&mut self) {
+        self.append_zeros(1);
+    }
+
+    #[inline]
+    fn append_zeros(&mut self, count: usize) {
+        self.data
+            .resize(self.data.len() + count * SIZE, P::default());
+    }
+
+    #[inline]
+    fn append_array(&mut self, array: &Self::Array) {
+        self.data.extend_from_slice(&array.data);
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional * SIZE);
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.data.len() / SIZE
+    }
+
+    #[inline]
+    fn finish(self) -> Self::Array {
+        ConstSizeListArray { data: self.data }
+    }
+}
+
+/// A null list has every element null.
+#[derive(Debug, Clone)]
+pub struct OptionListBuilder<P> {
+    validity: BitVec,
+    data: Vec<P>,
+    list_size: usize,
+}
+
+impl<P: Primitive> OptionListBuilder<P> {
+    #[inline]
+    pub fn new(list_size: usize) -> Self {
+        Self::with_capacity(0, list_size)
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize, list_size: usize) -> Self {
+        Self {
+            validity: BitVec::with_capacity((capacity * list_size).div_ceil(8)),
+            data: Vec::with_capacity(capacity * list_size),
+            list_size,
+        }
+    }
+
+    /// `value` must have the list size.
+    pub fn append_value(&mut self, value: OptionSlice<'_, P>) {
+        assert_eq!(value.len(), self.list_size, "list size mismatch");
+        self.validity.extend(value.validity);
+        self.data.extend_from_slice(value.data);
+    }
+
+    /// Appends `values.len() / list_size` lists with every element valid.
+    pub fn append_slice(&mut self, values: &[P]) {
+        assert!(
+            values.len().is_multiple_of(self.list_size),
+            "{} values do not fill lists of {}",
+            values.len(),
+            self.list_size
+        );
+        self.validity.push_n(true, values.len());
+        self.data.extend_from_slice(values);
+    }
+
+    #[inline]
+    pub fn append_null(&mut self) {
+        self.append_n_nulls(1);
+    }
+
+    #[inline]
+    pub fn append_n_nulls(&mut self, count: usize) {
+        let elements = count * self.list_size;
+        self.validity.push_n(false, elements);
+        self.data.resize(self.data.len() + elements, P::default());
+    }
+}
+
+impl<P: Primitive> ArrayBuilder for OptionListBuilder<P> {
+    type Array = OptionListArray<P>;
+
+    #[inline]
+    fn append_zero(&mut self) {
+        self.append_null();
+    }
+
+    #[inline]
+    fn append_zeros(&mut self, count: usize) {
+        self.append_n_nulls(count);
+    }
+
+    fn append_array(&mut self, array: &Self::Array) {
+        assert_eq!(array.list_size, self.list_size, "list size mismatch");
+        self.validity.extend(array.validity.as_slice());
+        self.data.extend_from_slice(&array.data);
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.validity.reserve(additional * self.list_size);
+        self.data.reserve(additional * self.list_size);
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.data.len().checked_div(self.list_size).unwrap_or(0)
+    }
+
+    #[inline]
+    fn finish(self) -> Self::Array {
+        OptionListArray {
+            validity: self.validity,
+            data: self.data,
+            list_size: self.list_size,
+        }
+    }
+}
+
+/// Wraps the builder of the values; nulls take a zero row in it.
+#[derive(Debug, Clone, Default)]
+pub struct OptionBuilder<B> {
+    validity: BitVec,
+    values: B,
+}
+
+impl<B: ArrayBuilder> OptionBuilder<B> {
+    #[inline]
+    pub fn new(values: B) -> Self {
+        Self {
+            validity: BitVec::default(),
+            values,
+        }
+    }
+
+    /// Appends a valid row; `append` must add exactly one row to the values.
+    #[inline]
+    pub fn append_with(&mut self, append: impl FnOnce(&mut B)) {
+        append(&mut self.values);
+        self.validity.push(true);
+        debug_assert_eq!(self.values.len(), self.validity.len());
+    }
+
+    #[inline]
+    pub fn append_null(&mut self) {
+        self.append_n_nulls(1);
+    }
+
+    #[inline]
+    pub fn append_n_nulls(&mut self, count: usize) {
+        self.validity.push_n(false, count);
+        self.values.append_zeros(count);
+    }
+}
+
+impl<P: Primitive> OptionBuilder<PrimitiveBuilder<P>> {
+    #[inline]
+    pub fn append_value(&mut self, value: P) {
+        self.append_with(|values| values.append_value(value));
+    }
+
+    #[inline]
+    pub fn append_option(&mut self, value: Option<P>) {
+        match value {
+            Some(value) => self.append_value(value),
+            None => self.append_null(),
+        }
+    }
+
+    /// Appends every value as valid.
+    #[inline]
+    pub fn append_slice(&mut self, values: &[P]) {
+        self.validity.push_n(true, values.len());
+        self.values.append_slice(values);
+    }
+}
+
+impl<B: ArrayBuilder> ArrayBuilder for OptionBuilder<B> {
+    type Array = OptionArray<B::Array>;
+
+    #[inline]
+    fn append_zero(&mut self) {
+        self.append_null();
+    }
+
+    #[inline]
+    fn append_zeros(&mut self, count: usize) {
+        self.append_n_nulls(count);
+    }
+
+    #[inline]
+    fn append_array(&mut self, array: &Self::Array) {
+        self.validity.extend(array.validity());
+        self.values.append_array(&array.data);
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.validity.reserve(additional);
+        self.values.reserve(additional);
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.validity.len()
+    }
+
+    #[inline]
+    fn finish(self) -> Self::Array {
+        OptionArray {
+            validity: self.validity,
+            data: self.values.finish(),
+        }
+    }
+}
+
+/// Interns borrowed values, copying only those not seen before.
+#[derive(Debug, Clone)]
+pub struct IdArrayBuilder<A: Array, S = RandomState> {
+    values: SlotMap<A, S>,
+    data: IdVec,
+    /// Whether the dictionary is still in value order, which holds while it only has the values of
+    /// one sorted array.
+    sorted: bool,
+    /// The order hook of the first appended array whose dictionary is kept sorted.
+    keep_sorted: Option<fn(&mut IdArray<A, S>)>,
+}
+
+impl<A: Array> IdArrayBuilder<A>
+where
+    for<'a, 'b> A::ItemRef<'a>: PartialEq<A::ItemRef<'b>>,
+    for<'a> A::ItemRef<'a>: Hash,
+{
+    #[inline]
+    pub fn new(values: A) -> Self {
+        Self::with_hasher(values, RandomState::new())
+    }
+}
+
+impl<A: Array, S: BuildHasher> IdArrayBuilder<A, S>
+where
+    for<'a, 'b> A::ItemRef<'a>: PartialEq<A::ItemRef<'b>>,
+    for<'a> A::ItemRef<'a>: Hash,
+{
+    /// Hashes values with `hash_builder`, which the finished array keeps.
+    #[inline]
+    pub fn with_hasher(values: A, hash_builder: S) -> Self {
+        Self {
+            values: SlotMap::with_hasher(values, hash_builder),
+            data: IdVec::new(),
+            sorted: true,
+            keep_sorted: None,
+        }
+    }
+
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        self.values.hasher()
+    }
+
+    #[inline]
+    pub fn append_value(&mut self, value: A::ItemRef<'_>) {
+        let len = self.values.data.len();
+        let id = self.values.lookup_or_insert_ref(value);
+        self.sorted &= len == 0 || id <= len;
+        self.data.push(id);
+    }
+
+    #[inline]
+    pub fn append_option(&mut self, value: Option<A::ItemRef<'_>>) {
+        match value {
+            Some(value) => self.append_value(value),
+            None => self.append_null(),
+        }
+    }
+
+    pub fn append_values<'v>(&mut self, values: impl IntoIterator<Item = A::ItemRef<'v>>) {
+        let values = values.into_iter();
+        self.data.reserve(values.size_hint().0);
+        for value in values {
+            self.append_value(value);
+        }
+    }
+
+    #[inline]
+    pub fn append_null(&mut self) {
+        self.data.push(0);
+    }
+
+    #[inline]
+    pub fn append_n_nulls(&mut self, count: usize) {
+        self.data.resize(self.data.len() + count, 0);
+    }
+}
+
+impl<A: Array, S: BuildHasher + 'static> ArrayBuilder for IdArrayBuilder<A, S>
+where
+    for<'a, 'b> A::ItemRef<'a>: PartialEq<A::ItemRef<'b>>,
+    for<'a> A::ItemRef<'a>: Hash,
+{
+    type Array = IdArray<A, S>;
+
+    #[inline]
+    fn append_zero(&mut self) {
+        self.append_null();
+    }
+
+    #[inline]
+    fn append_zeros(&mut self, count: usize) {
+        self.append_n_nulls(count);
+    }
+
+    /// Each distinct value of `array` is interned once and its ids remapped.
+    fn append_array(&mut self, array: &Self::Array) {
+        let len = self.values.data.len();
+        let mut ids = Vec::with_capacity(array.values.data.len() + 1);
+        ids.push(0);
+        for value in array.values.data.iter() {
+            ids.push(self.values.lookup_or_insert_ref(value));
+        }
+        if self.values.data.len() > len {
+            self.sorted &= len == 0 && array.sorted;
+        }
+        self.keep_sorted = self.keep_sorted.or(array.keep_sorted);
+        self.data.extend(array.data.iter().map(|id| ids[id]));
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline]
+    fn finish(self) -> Self::Array {
+        IdArray::from_parts(self.values, self.data, self.sorted, self.keep_sorted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ArrayBuilder, ConstSizeListBuilder, IdArrayBuilder, ListBuilder, OptionBuilder,
+        OptionListBuilder, PrimitiveBuilder,
+    };
+    use crate::{
+        array::{list::ListArray, Array},
+        scalar::{list::OptionList, Scalar},
+    };
+
+    #[test]
+    fn bulk_and_nulls() {
+        let mut ints = PrimitiveBuilder::with_capacity(4);
+        ints.append_slice(&[1u32, 2]);
+        ints.append_value(3);
+        let ints = ints.finish();
+        assert_eq!(ints.as_slice(), &[1, 2, 3]);
+
+        let mut options = OptionBuilder::new(PrimitiveBuilder::<i64>::new());
+        options.append_slice(&[4, 5]);
+        options.append_n_nulls(10);
+        options.append_option(Some(6));
+        assert_eq!(options.len(), 13);
+        let options = options.finish();
+        assert_eq!(options.null_count(), 10);
+        assert_eq!(options.get(12), Some(Some(&6)));
+
+        let mut merged = OptionBuilder::new(PrimitiveBuilder::new());
+        merged.append_null();
+        merged.append_array(&options);
+        let merged = merged.finish();
+        assert_eq!(merged.len(), 14);
+        assert_eq!(merged.get(1), Some(Some(&4)));
+        assert_eq!(merged.get(3), Some(None));
+
+        let mut pairs = ConstSizeListBuilder::<u8, 2>::new();
+        pairs.append_slice(&[1, 2, 3, 4]);
+        pairs.append_value(&[5, 6]);
+        assert_eq!(pairs.finish().get(2), Some(&[5, 6]));
+
+        let mut lists = OptionListBuilder::new(2);
+        lists.append_slice(&[1u16, 2]);
+        lists.append_null();
+        lists.append_value(OptionList::from([None, Some(3)]).as_ref());
+        let lists = lists.finish();
+        assert_eq!(lists.len(), 3);
+        assert_eq!(lists.get(1).unwrap().get(0), Some(None));
+        assert_eq!(lists.get(2).unwrap().get(1), Some(Some(&3)));
+    }
+
+    #[test]
+    fn lists_element_by_element() {
+        let mut lists = ListBuilder::with_capacity(3, 8);
+        for word in ["ab", "", "cde"] {
+            for byte in word.bytes() {
+                lists.append_value_to_current_list(byte);
+            }
+            lists.finish_list();
+        }
+        lists.append_values_to_current_list(b"f");
+        lists.append_values_to_current_list(b"g");
+        let mut lists = {
+            let head = lists.finish();
+            let mut all = ListBuilder::new();
+            all.append_value(b"z");
+            all.append_array(&head);
+            all
+        };
+        lists.append_slice(&[b"h".to_vec()]);
+        let lists = lists.finish();
+        let words = lists.iter().collect::<Vec<_>>();
+        assert_eq!(words, [&b"z"[..], b"ab", b"", b"cde", b"fg", b"h"]);
+
+        let mut nullable = OptionBuilder::new(ListBuilder::new());
+        nullable.append_with(|list| {
+            list.append_values_to_current_list(&[1, 2]);
+            list.finish_list();
+        });
+        nullable.append_null();
+        let nullable = nullable.finish();
+        assert_eq!(nullable.get(0), Some(Some(&[1u8, 2][..])));
+        assert_eq!(nullable.get(1), Some(None));
+    }
+
+    #[test]
+    fn interned_values() {
+        let mut ids = IdArrayBuilder::new(ListArray::<u8>::new());
+        ids.append_values([&b"a"[..], b"b", b"a"]);
+        ids.append_n_nulls(2);
+        let head = ids.finish();
+        assert_eq!(head.values.data.len(), 2);
+
+        let mut ids = IdArrayBuilder::new(ListArray::<u8>::new());
+        ids.append_value(b"b");
+        ids.append_array(&head);
+        let ids = ids.finish();
+        assert_eq!(ids.values.data.len(), 2);
+        assert_eq!(
+            ids.iter().collect::<Vec<_>>(),
+            [
+                Some(&b"b"[..]),
+                Some(b"a"),
+                Some(b"b"),
+                Some(b"a"),
+                None,
+                None
+            ]
+        );
+    }
+}
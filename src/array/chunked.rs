@@ -0,0 +1,555 @@
+use std::{
+    hash::{BuildHasher, Hash},
+    ops::Range,
+};
+
+use ahash::RandomState;
+
+use super::{
+    id::IdArray,
+    idvec::{IdVec, IdWidth},
+    mutate::{check, check_insert, ArrayMut},
+    slotmap::SlotMap,
+    Array,
+};
+use crate::scalar::ScalarRef;
+
+/// Finds the chunk holding `offset` given the end offset of every chunk.
+#[inline]
+fn locate(ends: &[usize], offset: usize) -> Option<(usize, usize)> {
+    let chunk = ends.partition_point(|&end| end <= offset);
+    let start = if chunk == 0 { 0 } else { ends[chunk - 1] };
+    (chunk < ends.len()).then(|| (chunk, offset - start))
+}
+
+/// One logical array stored as a list of chunks, so appending never copies earlier data.
+#[derive(Debug, Clone)]
+pub struct ChunkedArray<A> {
+    chunks: Vec<A>,
+    ends: Vec<usize>,
+}
+
+impl<A> Default for ChunkedArray<A> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            chunks: Vec::new(),
+            ends: Vec::new(),
+        }
+    }
+}
+
+impl<A: Array> ChunkedArray<A> {
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn from_chunks(chunks: impl IntoIterator<Item = A>) -> Self {
+        let mut this = Self::new();
+        for chunk in chunks {
+            this.push_chunk(chunk);
+        }
+        this
+    }
+
+    #[inline]
+    pub fn push_chunk(&mut self, chunk: A) {
+        self.ends.push(self.len() + chunk.len());
+        self.chunks.push(chunk);
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.ends.last().copied().unwrap_or(0)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn chunks(&self) -> &[A] {
+        &self.chunks
+    }
+
+    #[inline]
+    pub fn num_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    #[inline]
+    pub fn into_chunks(self) -> Vec<A> {
+        self.chunks
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = A::ItemRef<'_>> + '_ {
+        self.chunks.iter().flat_map(|chunk| chunk.iter())
+    }
+
+    /// A view of `range` that may span several chunks.
+    #[inline]
+    pub fn slice(&self, range: Range<usize>) -> ChunkedSlice<'_, A> {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "slice {range:?} out of bounds for length {}",
+            self.len()
+        );
+        ChunkedSlice { array: self, range }
+    }
+}
+
+impl<A: Array + Default> ChunkedArray<A> {
+    /// Merges all chunks into one.
+    pub fn rechunk(&mut self) {
+        if self.chunks.len() <= 1 {
+            return;
+        }
+        let mut merged = A::default();
+        for item in self.iter() {
+            merged.push(item.into_owned());
+        }
+        *self = Self::from_chunks([merged]);
+    }
+}
+
+impl<A: Array + Default> Array for ChunkedArray<A> {
+    type Item = A::Item;
+
+    type ItemRef<'s> = A::ItemRef<'s>
+    where
+        Self: 's;
+
+    type ItemMut<'s> = A::ItemMut<'s>
+    where
+        Self: 's;
+
+    /// Pushes onto the last chunk, starting one if there is none.
+    #[inline]
+    fn push(&mut self, item: Self::Item) {
+        if self.chunks.is_empty() {
+            self.push_chunk(A::default());
+        }
+        self.chunks.last_mut().unwrap().push(item);
+        *self.ends.last_mut().unwrap() += 1;
+    }
+
+    #[inline]
+    fn push_zero(&mut self) {
+        if self.chunks.is_empty() {
+            self.push_chunk(A::default());
+        }
+        self.chunks.last_mut().unwrap().push_zero();
+        *self.ends.last_mut().unwrap() += 1;
+    }
+
+    #[inline]
+    fn get(&self, offset: usize) -> Option<Self::ItemRef<'_>> {
+        let (chunk, offset) = locate(&self.ends, offset)?;
+        self.chunks[chunk].get(offset)
+    }
+
+    #[inline]
+    unsafe fn get_unchecked(&self, offset: usize) -> Self::ItemRef<'_> {
+        let (chunk, offset) = locate(&self.ends, offset).unwrap_unchecked();
+        self.chunks.get_unchecked(chunk).get_unchecked(offset)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, offset: usize) -> Option<Self::ItemMut<'_>> {
+        let (chunk, offset) = locate(&self.ends, offset)?;
+        self.chunks[chunk].get_mut(offset)
+    }
+
+    #[inline]
+    unsafe fn get_unchecked_mut(&mut self, offset: usize) -> Self::ItemMut<'_> {
+        let (chunk, offset) = locate(&self.ends, offset).unwrap_unchecked();
+        self.chunks
+            .get_unchecked_mut(chunk)
+            .get_unchecked_mut(offset)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.ends.last().copied().unwrap_or(0)
+    }
+}
+
+impl<A: ArrayMut + Default> ChunkedArray<A> {
+    /// Moves the ends of the chunks from `chunk` on by one row.
+    #[inline]
+    fn shift_ends(&mut self, chunk: usize, grow: bool) {
+        for end in &mut self.ends[chunk..] {
+            if grow {
+                *end += 1;
+            } else {
+                *end -= 1;
+            }
+        }
+    }
+
+    /// Inserts into the chunk holding `offset`, or pushes when `offset` is the length.
+    fn insert_with(&mut self, offset: usize, insert: impl FnOnce(&mut A, usize)) {
+        check_insert(offset, self.len());
+        let (chunk, local) = match locate(&self.ends, offset) {
+            Some(position) => position,
+            None => {
+                if self.chunks.is_empty() {
+                    self.push_chunk(A::default());
+                }
+                let chunk = self.chunks.len() - 1;
+                (chunk, self.chunks[chunk].len())
+            }
+        };
+        insert(&mut self.chunks[chunk], local);
+        self.shift_ends(chunk, true);
+    }
+}
+
+/// Rows move only within their chunk; chunks are never merged or split.
+impl<A: ArrayMut + Default> ArrayMut for ChunkedArray<A> {
+    #[inline]
+    fn set(&mut self, offset: usize, item: Self::Item) {
+        check(offset, self.len());
+        let (chunk, offset) = locate(&self.ends, offset).unwrap();
+        self.chunks[chunk].set(offset, item);
+    }
+
+    fn remove(&mut self, offset: usize) -> Self::Item {
+        check(offset, self.len());
+        let (chunk, local) = locate(&self.ends, offset).unwrap();
+        let item = self.chunks[chunk].remove(local);
+        self.shift_ends(chunk, false);
+        item
+    }
+
+    #[inline]
+    fn insert(&mut self, offset: usize, item: Self::Item) {
+        self.insert_with(offset, |chunk, offset| chunk.insert(offset, item));
+    }
+
+    #[inline]
+    fn insert_zero(&mut self, offset: usize) {
+        self.insert_with(offset, |chunk, offset| chunk.insert_zero(offset));
+    }
+
+    fn truncate(&mut self, len: usize) {
+        let Some((chunk, local)) = locate(&self.ends, len) else {
+            return;
+        };
+        let keep = if local == 0 { chunk } else { chunk + 1 };
+        if local > 0 {
+            self.chunks[chunk].truncate(local);
+            self.ends[chunk] = len;
+        }
+        self.chunks.truncate(keep);
+        self.ends.truncate(keep);
+    }
+
+    fn retain<F>(&mut self, mut keep: F)
+    where
+        F: for<'a> FnMut(A::ItemRef<'a>) -> bool,
+    {
+        let mut end = 0;
+        for (chunk, chunk_end) in self.chunks.iter_mut().zip(&mut self.ends) {
+            chunk.retain(&mut keep);
+            end += chunk.len();
+            *chunk_end = end;
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ChunkedSlice<'a, A> {
+    array: &'a ChunkedArray<A>,
+    range: Range<usize>,
+}
+
+impl<A> Clone for ChunkedSlice<'_, A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            array: self.array,
+            range: self.range.clone(),
+        }
+    }
+}
+
+impl<'a, A: Array> ChunkedSlice<'a, A> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    #[inline]
+    pub fn get(&self, offset: usize) -> Option<A::ItemRef<'a>> {
+        if offset >= self.len() {
+            return None;
+        }
+        let (chunk, offset) = locate(&self.array.ends, self.range.start + offset)?;
+        self.array.chunks[chunk].get(offset)
+    }
+
+    /// The chunks overlapping the slice, each with the range it contributes.
+    pub fn chunks(&self) -> impl Iterator<Item = (&'a A, Range<usize>)> + '_ {
+        let first = locate(&self.array.ends, self.range.start).map_or(0, |(chunk, _)| chunk);
+        let array = self.array;
+        (first..array.chunks.len())
+            .map(move |chunk| {
+                let start = if chunk == 0 { 0 } else { array.ends[chunk - 1] };
+                (chunk, start)
+            })
+            .take_while(|&(_, start)| start < self.range.end)
+            .map(move |(chunk, start)| {
+                let end = array.ends[chunk].min(self.range.end);
+                let range = start.max(self.range.start) - start..end - start;
+                (&array.chunks[chunk], range)
+            })
+            .filter(|(_, range)| !range.is_empty())
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = A::ItemRef<'a>> + '_ {
+        self.chunks().flat_map(|(chunk, range)| {
+            range.map(move |offset| unsafe { chunk.get_unchecked(offset) })
+        })
+    }
+}
+
+/// Chunks of ids sharing one dictionary, so equal values get equal ids in every chunk.
+#[derive(Debug, Clone)]
+pub struct ChunkedIdArray<A: Array, S = RandomState> {
+    pub(crate) values: SlotMap<A, S>,
+    pub(crate) chunks: Vec<IdVec>,
+    pub(crate) ends: Vec<usize>,
+}
+
+impl<A: Array> ChunkedIdArray<A> {
+    #[inline]
+    pub fn new(array: A) -> Self {
+        Self::from_values(SlotMap::new(array))
+    }
+}
+
+impl<A: Array, S> ChunkedIdArray<A, S> {
+    /// Hashes dictionary values with `hash_builder`.
+    #[inline]
+    pub fn with_hasher(array: A, hash_builder: S) -> Self {
+        Self::from_values(SlotMap::with_hasher(array, hash_builder))
+    }
+
+    #[inline]
+    pub(crate) fn from_values(values: SlotMap<A, S>) -> Self {
+        Self {
+            values,
+            chunks: Vec::new(),
+            ends: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.ends.last().copied().unwrap_or(0)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn num_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Ids of a chunk; `0` is null.
+    #[inline]
+    pub fn chunk_ids(&self, chunk: usize) -> Option<&IdVec> {
+        self.chunks.get(chunk)
+    }
+
+    #[inline]
+    pub fn values(&self) -> &A {
+        &self.values.data
+    }
+
+    /// The state dictionary values are hashed with.
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        self.values.hasher()
+    }
+
+    #[inline]
+    pub fn id(&self, offset: usize) -> Option<usize> {
+        let (chunk, offset) = locate(&self.ends, offset)?;
+        self.chunks[chunk].get(offset)
+    }
+
+    /// Starts a new, empty chunk for subsequent pushes.
+    #[inline]
+    pub fn finish_chunk(&mut self) {
+        self.chunks.push(IdVec::new());
+        self.ends.push(self.len());
+    }
+
+    /// Concatenates the id chunks; the dictionary is already shared so nothing is rehashed.
+    pub fn rechunk(&mut self) {
+        let width = self.chunks.iter().map(IdVec::width).max();
+        let mut ids = IdVec::with_width(width.unwrap_or(IdWidth::U8), self.len());
+        for chunk in &self.chunks {
+            ids.extend(chunk);
+        }
+        self.ends = vec![ids.len()];
+        self.chunks = vec![ids];
+    }
+}
+
+impl<A: Array, S: BuildHasher> ChunkedIdArray<A, S>
+where
+    for<'a, 'b> A::ItemRef<'a>: PartialEq<A::ItemRef<'b>>,
+    for<'a> A::ItemRef<'a>: Hash,
+{
+    #[inline]
+    pub fn get(&self, offset: usize) -> Option<Option<A::ItemRef<'_>>> {
+        self.values.get(self.id(offset)?)
+    }
+
+    #[inline]
+    pub fn lookup_id(&self, value: A::ItemRef<'_>) -> Option<usize> {
+        self.values.lookup(value)
+    }
+
+    /// Pushes onto the last chunk and returns the id of the value.
+    pub fn push(&mut self, item: Option<A::Item>) -> usize {
+        if self.chunks.is_empty() {
+            self.finish_chunk();
+        }
+        let id = item.map_or(0, |item| self.values.lookup_or_insert(item));
+        self.chunks.last_mut().unwrap().push(id);
+        *self.ends.last_mut().unwrap() += 1;
+        id
+    }
+
+    /// Appends a chunk, interning each of its dictionary values once and remapping its ids.
+    pub fn push_chunk<T>(&mut self, chunk: IdArray<A, T>) {
+        let mut remap = Vec::with_capacity(chunk.values.data.len() + 1);
+        remap.push(0);
+        for offset in 0..chunk.values.data.len() {
+            let value = unsafe { chunk.values.data.get_unchecked(offset) };
+            remap.push(self.values.lookup_or_insert(value.into_owned()));
+        }
+        let ids = chunk.data.remap(&remap);
+        self.ends.push(self.len() + ids.len());
+        self.chunks.push(ids);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChunkedArray, ChunkedIdArray};
+    use crate::array::{
+        id::IdArray, idvec::IdVec, list::ListArray, mutate::ArrayMut, primitive::PrimitiveArray,
+        Array,
+    };
+
+    #[test]
+    fn get_slice_and_rechunk() {
+        let mut array = ChunkedArray::new();
+        for chunk in [0..3, 3..3, 3..7, 7..8] {
+            let mut values = PrimitiveArray::new();
+            for value in chunk {
+                values.push(value);
+            }
+            array.push_chunk(values);
+        }
+        array.push(8);
+        assert_eq!(array.len(), 9);
+        assert_eq!(array.num_chunks(), 4);
+        assert_eq!(array.get(3), Some(&3));
+        assert_eq!(array.get(8), Some(&8));
+        assert_eq!(array.get(9), None);
+        *array.get_mut(4).unwrap() = 40;
+
+        let slice = array.slice(2..8);
+        assert_eq!(slice.len(), 6);
+        assert_eq!(slice.get(2), Some(&40));
+        assert_eq!(
+            slice
+                .chunks()
+                .map(|(chunk, range)| (chunk.len(), range))
+                .collect::<Vec<_>>(),
+            [(3, 2..3), (4, 0..4), (2, 0..1)]
+        );
+        assert_eq!(
+            slice.iter().copied().collect::<Vec<_>>(),
+            [2, 3, 40, 5, 6, 7]
+        );
+        assert_eq!(array.slice(4..4).iter().count(), 0);
+
+        array.rechunk();
+        assert_eq!(array.num_chunks(), 1);
+        assert_eq!(
+            array.iter().copied().collect::<Vec<_>>(),
+            [0, 1, 2, 3, 40, 5, 6, 7, 8]
+        );
+    }
+
+    #[test]
+    fn mutate_across_chunks() {
+        let mut array = ChunkedArray::from_chunks([
+            (0..3u32).collect::<PrimitiveArray<_>>(),
+            PrimitiveArray::new(),
+            (3..6).collect(),
+        ]);
+        assert_eq!(array.remove(2), 2);
+        array.insert(2, 20);
+        array.insert(6, 60);
+        array.swap(0, 5);
+        array.retain(|&value| value != 1);
+        assert_eq!(
+            array.iter().copied().collect::<Vec<_>>(),
+            [5, 20, 3, 4, 0, 60]
+        );
+        assert_eq!(array.get(2), Some(&3));
+        array.truncate(2);
+        assert_eq!(array.len(), 2);
+        assert_eq!(array.pop(), Some(20));
+        array.clear();
+        assert!(array.is_empty());
+    }
+
+    #[test]
+    fn shared_dictionary() {
+        let mut array = ChunkedIdArray::new(ListArray::<u8>::new());
+        array.push(Some(b"b".to_vec()));
+        let mut chunk = IdArray::new(ListArray::<u8>::new());
+        for value in [Some("a"), None, Some("b"), Some("a")] {
+            chunk.push(value.map(Vec::from));
+        }
+        array.push_chunk(chunk);
+        assert_eq!(
+            array.chunk_ids(1).map(IdVec::to_vec),
+            Some(vec![2, 0, 1, 2])
+        );
+        assert_eq!(array.get(3), Some(Some(&b"b"[..])));
+        assert_eq!(array.get(2), Some(None));
+        assert_eq!(array.lookup_id(b"a"), Some(2));
+        assert_eq!(array.values().len(), 2);
+
+        let mut chunked = ChunkedArray::new();
+        chunked.push_chunk(IdArray::new(ListArray::<u8>::new()));
+        chunked.push(Some(b"x".to_vec()));
+        chunked.push_chunk(IdArray::new(ListArray::<u8>::new()));
+        chunked.push(None);
+        chunked.rechunk();
+        assert_eq!(chunked.get(0), Some(Some(&b"x"[..])));
+        assert_eq!(chunked.get(1), Some(None));
+    }
+}
@@ -0,0 +1,317 @@
+use std::{
+    hash::{BuildHasher, Hash},
+    sync::{Mutex, MutexGuard},
+};
+
+use ahash::RandomState;
+use hashbrown::{hash_map::RawEntryMut, HashMap};
+
+use super::{
+    chunked::ChunkedIdArray,
+    id::IdArray,
+    idvec::{IdVec, IdWidth},
+    slotmap::{seeded_state, SlotMap},
+    Array,
+};
+use crate::scalar::{Scalar, ScalarRef};
+
+#[derive(Debug, Default)]
+struct Shard<A> {
+    dedup: HashMap<usize, (), ()>,
+    data: A,
+}
+
+/// A dictionary that many threads can intern into at once.
+///
+/// Values are spread over independently locked shards by hash. Ids are stable for the
+/// lifetime of the map and `0` stays null; finishing renumbers them densely.
+#[derive(Debug)]
+pub struct ConcurrentSlotMap<A, S = RandomState> {
+    hash_state: S,
+    shards: Box<[Mutex<Shard<A>>]>,
+}
+
+impl<A: Array + Default> ConcurrentSlotMap<A>
+where
+    for<'lhs, 'rhs> A::ItemRef<'lhs>: PartialEq<A::ItemRef<'rhs>>,
+    for<'r> A::ItemRef<'r>: Hash,
+{
+    pub fn new(shards: usize) -> Self {
+        Self::with_hasher(shards, RandomState::new())
+    }
+
+    /// Hashes deterministically, so values land in the same shards on every run; see
+    /// [`seeded_state`].
+    pub fn with_seed(shards: usize, seed: u64) -> Self {
+        Self::with_hasher(shards, seeded_state(seed))
+    }
+}
+
+impl<A: Array + Default, S: BuildHasher> ConcurrentSlotMap<A, S>
+where
+    for<'lhs, 'rhs> A::ItemRef<'lhs>: PartialEq<A::ItemRef<'rhs>>,
+    for<'r> A::ItemRef<'r>: Hash,
+{
+    /// Hashes values with `hash_builder`, which the finished dictionary keeps.
+    pub fn with_hasher(shards: usize, hash_builder: S) -> Self {
+        assert!(shards > 0, "a concurrent slot map needs at least one shard");
+        Self {
+            hash_state: hash_builder,
+            shards: (0..shards).map(|_| Mutex::default()).collect(),
+        }
+    }
+
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        &self.hash_state
+    }
+
+    #[inline]
+    fn shard(&self, hash: u64) -> (usize, MutexGuard<'_, Shard<A>>) {
+        let shard = (hash >> 32) as usize % self.shards.len();
+        let guard = self.shards[shard]
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        (shard, guard)
+    }
+
+    #[inline]
+    fn id(&self, shard: usize, index: usize) -> usize {
+        index * self.shards.len() + shard + 1
+    }
+
+    pub fn lookup_or_insert(&self, value: A::Item) -> usize {
+        let hash = self.hash_state.hash_one(value.as_ref());
+        let (shard, mut guard) = self.shard(hash);
+        let Shard { dedup, data } = &mut *guard;
+        let entry = dedup.raw_entry_mut().from_hash(hash, |key| {
+            value.as_ref() == unsafe { data.get_unchecked(*key) }
+        });
+
+        let index = match entry {
+            RawEntryMut::Occupied(entry) => *entry.into_key(),
+            RawEntryMut::Vacant(entry) => {
+                data.push(value);
+                *entry
+                    .insert_with_hasher(hash, data.len() - 1, (), |index| {
+                        self.hash_state.hash_one(data.get(*index).unwrap())
+                    })
+                    .0
+            }
+        };
+        self.id(shard, index)
+    }
+
+    pub fn lookup(&self, value: A::ItemRef<'_>) -> Option<usize> {
+        let hash = self.hash_state.hash_one(&value);
+        let (shard, guard) = self.shard(hash);
+        let index = guard
+            .dedup
+            .raw_entry()
+            .from_hash(hash, |key| unsafe {
+                guard.data.get_unchecked(*key) == value
+            })
+            .map(|(&index, &())| index)?;
+        Some(self.id(shard, index))
+    }
+
+    /// Returns a copy of the value behind `id`, or `None` for null and unknown ids.
+    pub fn get(&self, id: usize) -> Option<A::Item> {
+        let id = id.checked_sub(1)?;
+        let guard = self.shards[id % self.shards.len()]
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        guard
+            .data
+            .get(id / self.shards.len())
+            .map(ScalarRef::into_owned)
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| {
+                shard
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .data
+                    .len()
+            })
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Starts a column whose values are interned into this map.
+    #[inline]
+    pub fn builder(&self) -> IdBuilder<'_, A, S> {
+        IdBuilder {
+            values: self,
+            data: Vec::new(),
+        }
+    }
+
+    /// Moves the values into one dictionary and returns the dense id of every stable id.
+    fn into_slot_map(self) -> (SlotMap<A, S>, Vec<usize>) {
+        let shards = self
+            .shards
+            .into_vec()
+            .into_iter()
+            .map(|shard| {
+                shard
+                    .into_inner()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+            })
+            .collect::<Vec<_>>();
+        let stride = shards.len();
+        let len = shards
+            .iter()
+            .map(|shard| shard.data.len())
+            .max()
+            .unwrap_or(0);
+        let mut map = SlotMap::with_hasher(A::default(), self.hash_state);
+        let mut remap = vec![0; len * stride + 1];
+        for (shard_id, shard) in shards.iter().enumerate() {
+            for index in 0..shard.data.len() {
+                let value = unsafe { shard.data.get_unchecked(index) };
+                remap[index * stride + shard_id + 1] = map.lookup_or_insert(value.into_owned());
+            }
+        }
+        (map, remap)
+    }
+
+    /// Merges the ids of every builder, in order, into one array.
+    pub fn into_id_array(self, parts: impl IntoIterator<Item = Vec<usize>>) -> IdArray<A, S> {
+        let (values, remap) = self.into_slot_map();
+        let mut data = IdVec::with_width(IdWidth::of(values.data.len()), 0);
+        for part in parts {
+            data.extend(part.into_iter().map(|id| remap[id]));
+        }
+        IdArray::from_parts(values, data, false, None)
+    }
+
+    /// Keeps the ids of every builder as a separate chunk over one shared dictionary.
+    pub fn into_chunked(self, parts: impl IntoIterator<Item = Vec<usize>>) -> ChunkedIdArray<A, S> {
+        let (values, remap) = self.into_slot_map();
+        let width = IdWidth::of(values.data.len());
+        let mut chunked = ChunkedIdArray::from_values(values);
+        for part in parts {
+            let mut ids = IdVec::with_width(width, part.len());
+            ids.extend(part.into_iter().map(|id| remap[id]));
+            chunked.ends.push(chunked.len() + ids.len());
+            chunked.chunks.push(ids);
+        }
+        chunked
+    }
+}
+
+/// Per-thread ids interned into a shared [`ConcurrentSlotMap`].
+#[derive(Debug)]
+pub struct IdBuilder<'a, A, S = RandomState> {
+    values: &'a ConcurrentSlotMap<A, S>,
+    data: Vec<usize>,
+}
+
+impl<A: Array + Default, S: BuildHasher> IdBuilder<'_, A, S>
+where
+    for<'lhs, 'rhs> A::ItemRef<'lhs>: PartialEq<A::ItemRef<'rhs>>,
+    for<'r> A::ItemRef<'r>: Hash,
+{
+    #[inline]
+    pub fn push(&mut self, item: Option<A::Item>) -> usize {
+        let id = item.map_or(0, |item| self.values.lookup_or_insert(item));
+        self.data.push(id);
+        id
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The stable ids, to be passed back to the map once every thread is done.
+    #[inline]
+    pub fn into_ids(self) -> Vec<usize> {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::ConcurrentSlotMap;
+    use crate::array::{list::ListArray, Array};
+
+    #[test]
+    fn parallel_interning() {
+        let map = ConcurrentSlotMap::<ListArray<u8>>::new(4);
+        let parts = thread::scope(|scope| {
+            let handles = (0..4)
+                .map(|thread| {
+                    let map = &map;
+                    scope.spawn(move || {
+                        let mut builder = map.builder();
+                        for row in 0..1000 {
+                            let value = (row % 7 != thread).then(|| format!("v{}", row % 50));
+                            builder.push(value.map(String::into_bytes));
+                        }
+                        builder.into_ids()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(map.len(), 50);
+        let id = map.lookup(b"v3").unwrap();
+        assert_eq!(parts[1][3], id);
+        assert_eq!(parts[2][53], id);
+        assert_eq!(map.get(id), Some(b"v3".to_vec()));
+        assert_eq!(map.get(0), None);
+
+        let chunked = map.into_chunked(parts.clone());
+        assert_eq!(chunked.num_chunks(), 4);
+        assert_eq!(chunked.get(1003), Some(Some(&b"v3"[..])));
+        assert_eq!(chunked.get(1001), Some(None));
+
+        let map = ConcurrentSlotMap::<ListArray<u8>>::new(3);
+        let mut builder = map.builder();
+        for value in ["x", "y", "x"] {
+            builder.push(Some(value.into()));
+        }
+        let ids = builder.into_ids();
+        let array = map.into_id_array([ids.clone(), ids]);
+        assert_eq!(array.len(), 6);
+        assert_eq!(array.get(5), Some(Some(&b"x"[..])));
+        assert_eq!(array.get(4), Some(Some(&b"y"[..])));
+        assert_eq!(array.values.data.len(), 2);
+    }
+
+    #[test]
+    fn poisoned_shard_keeps_its_values() {
+        let map = ConcurrentSlotMap::<ListArray<u8>>::new(1);
+        map.builder().push(Some(b"kept".to_vec()));
+        let poisoned = thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let _guard = map.shards[0].lock().unwrap();
+                    panic!("poison the shard");
+                })
+                .join()
+        });
+        assert!(poisoned.is_err());
+        assert!(map.shards[0].is_poisoned());
+        assert_eq!(map.len(), 1);
+        assert!(!map.is_empty());
+    }
+}
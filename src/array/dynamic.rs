@@ -0,0 +1,437 @@
+//! Type-erased arrays.
+//!
+//! [`DynArray`] holds any [`TypedArray`] behind its [`DataType`], so columns of different types
+//! can share a `Vec` and be chosen at runtime. The concrete array is recovered by downcasting.
+
+use std::{
+    any::Any,
+    fmt,
+    hash::{BuildHasher, Hash},
+    ops::Range,
+};
+
+use super::{
+    id::IdArray,
+    list::{ConstSizeListArray, ListArray, OptionListArray},
+    option::OptionArray,
+    primitive::PrimitiveArray,
+    Array,
+};
+use crate::{
+    bitvec::BitSlice,
+    compute::select::{mask_indices, Select},
+    datatype::{with_numeric, DataType, ScalarValue, TypedPrimitive},
+    primitive::{bf16, f16, Date32, PrimitiveType},
+    scalar::ScalarRef,
+};
+
+/// An array that can describe its type and values at runtime.
+pub trait TypedArray: Array + Select + Clone + fmt::Debug + Send + Sync {
+    fn data_type(&self) -> DataType;
+
+    fn to_scalar(item: Self::ItemRef<'_>) -> ScalarValue;
+}
+
+impl<P: TypedPrimitive> TypedArray for PrimitiveArray<P> {
+    #[inline]
+    fn data_type(&self) -> DataType {
+        P::DATA_TYPE
+    }
+
+    #[inline]
+    fn to_scalar(item: &P) -> ScalarValue {
+        item.into_scalar()
+    }
+}
+
+impl<P: TypedPrimitive> TypedArray for ListArray<P> {
+    #[inline]
+    fn data_type(&self) -> DataType {
+        DataType::List(Box::new(P::DATA_TYPE))
+    }
+
+    #[inline]
+    fn to_scalar(item: &[P]) -> ScalarValue {
+        ScalarValue::List(item.iter().map(|value| value.into_scalar()).collect())
+    }
+}
+
+impl<P: TypedPrimitive, const SIZE: usize> TypedArray for ConstSizeListArray<P, SIZE> {
+    #[inline]
+    fn data_type(&self) -> DataType {
+        DataType::ConstSizeList(Box::new(P::DATA_TYPE), SIZE)
+    }
+
+    #[inline]
+    fn to_scalar(item: &[P; SIZE]) -> ScalarValue {
+        ScalarValue::List(item.iter().map(|value| value.into_scalar()).collect())
+    }
+}
+
+impl<P: TypedPrimitive> TypedArray for OptionListArray<P> {
+    #[inline]
+    fn data_type(&self) -> DataType {
+        DataType::OptionList(Box::new(P::DATA_TYPE), self.list_size())
+    }
+
+    #[inline]
+    fn to_scalar(item: Self::ItemRef<'_>) -> ScalarValue {
+        ScalarValue::List(
+            (0..item.data.len())
+                .map(|i| match item.get(i).flatten() {
+                    Some(value) => value.into_scalar(),
+                    None => ScalarValue::Null,
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<A: TypedArray> TypedArray for OptionArray<A> {
+    #[inline]
+    fn data_type(&self) -> DataType {
+        DataType::Option(Box::new(self.data.data_type()))
+    }
+
+    #[inline]
+    fn to_scalar(item: Self::ItemRef<'_>) -> ScalarValue {
+        item.map_or(ScalarValue::Null, A::to_scalar)
+    }
+}
+
+impl<A: TypedArray, S> TypedArray for IdArray<A, S>
+where
+    S: BuildHasher + Clone + fmt::Debug + Send + Sync + 'static,
+    for<'a, 'b> A::ItemRef<'a>: PartialEq<A::ItemRef<'b>>,
+    for<'a> A::ItemRef<'a>: Hash,
+{
+    #[inline]
+    fn data_type(&self) -> DataType {
+        DataType::Id(Box::new(self.values.data.data_type()))
+    }
+
+    #[inline]
+    fn to_scalar(item: Self::ItemRef<'_>) -> ScalarValue {
+        item.map_or(ScalarValue::Null, A::to_scalar)
+    }
+}
+
+/// The object-safe part of [`TypedArray`].
+trait AnyArray: Send + Sync + fmt::Debug {
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+
+    fn clone_box(&self) -> Box<dyn AnyArray>;
+
+    fn data_type(&self) -> DataType;
+
+    fn len(&self) -> usize;
+
+    fn get(&self, offset: usize) -> Option<ScalarValue>;
+
+    fn push_zero(&mut self);
+
+    fn take(&self, indices: &[usize]) -> Box<dyn AnyArray>;
+
+    fn append(&mut self, other: &dyn AnyArray) -> bool;
+}
+
+impl<A: TypedArray> AnyArray for A {
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    #[inline]
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+
+    #[inline]
+    fn clone_box(&self) -> Box<dyn AnyArray> {
+        Box::new(self.clone())
+    }
+
+    #[inline]
+    fn data_type(&self) -> DataType {
+        TypedArray::data_type(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        Array::len(self)
+    }
+
+    #[inline]
+    fn get(&self, offset: usize) -> Option<ScalarValue> {
+        Array::get(self, offset).map(A::to_scalar)
+    }
+
+    #[inline]
+    fn push_zero(&mut self) {
+        Array::push_zero(self)
+    }
+
+    #[inline]
+    fn take(&self, indices: &[usize]) -> Box<dyn AnyArray> {
+        Box::new(Select::take(self, indices))
+    }
+
+    fn append(&mut self, other: &dyn AnyArray) -> bool {
+        let Some(other) = other.as_any().downcast_ref::<A>() else {
+            return false;
+        };
+        for offset in 0..other.len() {
+            self.push(unsafe { other.get_unchecked(offset) }.into_owned());
+        }
+        true
+    }
+}
+
+/// Any [`TypedArray`], with its type known only at runtime.
+#[derive(Debug)]
+pub struct DynArray(Box<dyn AnyArray>);
+
+impl DynArray {
+    #[inline]
+    pub fn new<A: TypedArray>(array: A) -> Self {
+        Self(Box::new(array))
+    }
+
+    /// An empty array of the given type.
+    ///
+    /// Supported are booleans, numbers and dates, bare or in a list, an option list, an
+    /// `Option` of either, and dictionaries of values or lists other than `f32` and `f64`. Other
+    /// types have no array to build without knowing their const parameters.
+    pub fn new_empty(data_type: &DataType) -> Option<Self> {
+        match data_type.leaf() {
+            DataType::Boolean => empty_hashable::<bool>(data_type),
+            DataType::Date32 => empty_hashable::<Date32>(data_type),
+            DataType::Numeric(kind) => with_numeric!(
+                *kind,
+                T => empty_hashable::<T>(data_type),
+                float => match kind {
+                    PrimitiveType::F16 => empty_hashable::<f16>(data_type),
+                    PrimitiveType::BF16 => empty_hashable::<bf16>(data_type),
+                    _ => empty::<T>(data_type),
+                }
+            ),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn data_type(&self) -> DataType {
+        self.0.data_type()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn get(&self, offset: usize) -> Option<ScalarValue> {
+        self.0.get(offset)
+    }
+
+    /// Pushes a null, or the zero value for non-nullable types.
+    #[inline]
+    pub fn push_zero(&mut self) {
+        self.0.push_zero()
+    }
+
+    /// See [`Select::take`]; indices must be in bounds.
+    #[inline]
+    pub fn take(&self, indices: &[usize]) -> Self {
+        Self(self.0.take(indices))
+    }
+
+    #[inline]
+    pub fn filter(&self, mask: &BitSlice<'_>) -> Self {
+        self.take(&mask_indices(mask))
+    }
+
+    #[inline]
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        self.take(&range.collect::<Vec<_>>())
+    }
+
+    /// Appends the rows of `other`; returns `false` and leaves `self` unchanged if `other` is a
+    /// different concrete array.
+    #[inline]
+    pub fn append(&mut self, other: &DynArray) -> bool {
+        self.0.append(&*other.0)
+    }
+
+    #[inline]
+    pub fn is<A: TypedArray>(&self) -> bool {
+        self.0.as_any().is::<A>()
+    }
+
+    #[inline]
+    pub fn downcast_ref<A: TypedArray>(&self) -> Option<&A> {
+        self.0.as_any().downcast_ref()
+    }
+
+    #[inline]
+    pub fn downcast_mut<A: TypedArray>(&mut self) -> Option<&mut A> {
+        self.0.as_any_mut().downcast_mut()
+    }
+
+    /// Returns the array unchanged if it is not an `A`.
+    #[inline]
+    pub fn downcast<A: TypedArray>(self) -> Result<A, Self> {
+        if self.is::<A>() {
+            Ok(*self.0.into_any().downcast().unwrap())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Clone for DynArray {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(self.0.clone_box())
+    }
+}
+
+impl<A: TypedArray> From<A> for DynArray {
+    #[inline]
+    fn from(array: A) -> Self {
+        Self::new(array)
+    }
+}
+
+fn empty_hashable<P: TypedPrimitive + Hash>(data_type: &DataType) -> Option<DynArray> {
+    match data_type {
+        DataType::Id(inner) => match **inner {
+            DataType::List(ref values) if values.is_primitive() => {
+                Some(DynArray::new(IdArray::new(ListArray::<P>::new())))
+            }
+            ref leaf if leaf.is_primitive() => {
+                Some(DynArray::new(IdArray::new(PrimitiveArray::<P>::new())))
+            }
+            _ => None,
+        },
+        _ => empty::<P>(data_type),
+    }
+}
+
+/// `data_type` has `P` as its leaf; lists must hold `P` directly.
+fn empty<P: TypedPrimitive>(data_type: &DataType) -> Option<DynArray> {
+    Some(match data_type {
+        DataType::List(values) if values.is_primitive() => DynArray::new(ListArray::<P>::new()),
+        DataType::OptionList(values, size) if values.is_primitive() => {
+            DynArray::new(OptionListArray::<P>::new(*size))
+        }
+        DataType::Option(inner) => match **inner {
+            DataType::List(ref values) if values.is_primitive() => {
+                DynArray::new(OptionArray::new(ListArray::<P>::new()))
+            }
+            DataType::OptionList(ref values, size) if values.is_primitive() => {
+                DynArray::new(OptionArray::new(OptionListArray::<P>::new(size)))
+            }
+            ref leaf if leaf.is_primitive() => {
+                DynArray::new(OptionArray::new(PrimitiveArray::<P>::new()))
+            }
+            _ => return None,
+        },
+        leaf if leaf.is_primitive() => DynArray::new(PrimitiveArray::<P>::new()),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DynArray;
+    use crate::{
+        array::{
+            id::IdArray, list::ListArray, option::OptionArray, primitive::PrimitiveArray, Array,
+            OptionListArray,
+        },
+        datatype::{DataType, ScalarValue},
+        primitive::{bf16, PrimitiveType},
+        scalar::list::OptionList,
+    };
+
+    #[test]
+    fn erase_and_downcast() {
+        let mut features = OptionListArray::<bf16>::new(2);
+        features.push(OptionList::from([Some(bf16::ONE), None]));
+        let mut names = IdArray::new(ListArray::<u8>::new());
+        names.push(Some(b"a".to_vec()));
+        names.push(None);
+
+        let mut columns = vec![DynArray::new(features), DynArray::new(names)];
+        assert_eq!(columns[0].data_type().to_string(), "list<option<bf16>; 2>");
+        assert_eq!(
+            columns[0].get(0),
+            Some(ScalarValue::List(vec![
+                ScalarValue::BF16(bf16::ONE),
+                ScalarValue::Null
+            ]))
+        );
+        assert_eq!(columns[1].get(1), Some(ScalarValue::Null));
+        assert_eq!(columns[1].get(2), None);
+
+        assert!(columns[0].downcast_ref::<OptionListArray<f32>>().is_none());
+        columns[1]
+            .downcast_mut::<IdArray<ListArray<u8>>>()
+            .unwrap()
+            .push(Some(b"a".to_vec()));
+        let names = columns.pop().unwrap().downcast::<IdArray<ListArray<u8>>>();
+        assert_eq!(names.unwrap().get(2), Some(Some(&b"a"[..])));
+    }
+
+    #[test]
+    fn empty_from_data_type() {
+        let numeric = |kind| Box::new(DataType::Numeric(kind));
+        let data_type = DataType::Option(numeric(PrimitiveType::U16));
+        let mut array = DynArray::new_empty(&data_type).unwrap();
+        array.push_zero();
+        assert_eq!(array.data_type(), data_type);
+        assert!(array.is::<OptionArray<PrimitiveArray<u16>>>());
+        assert_eq!(array.get(0), Some(ScalarValue::Null));
+
+        let strings = DataType::Id(Box::new(DataType::List(numeric(PrimitiveType::U8))));
+        assert_eq!(DynArray::new_empty(&strings).unwrap().data_type(), strings);
+        let floats = DataType::Id(numeric(PrimitiveType::F64));
+        assert!(DynArray::new_empty(&floats).is_none());
+        let lists = DataType::OptionList(numeric(PrimitiveType::F32), 3);
+        assert_eq!(DynArray::new_empty(&lists).unwrap().data_type(), lists);
+
+        let list = |inner| Box::new(DataType::List(inner));
+        for data_type in [
+            DataType::List(list(numeric(PrimitiveType::I8))),
+            DataType::OptionList(Box::new(DataType::Option(numeric(PrimitiveType::I8))), 2),
+            DataType::Option(list(Box::new(DataType::Option(numeric(PrimitiveType::I8))))),
+            DataType::Id(list(list(numeric(PrimitiveType::U8)))),
+        ] {
+            assert!(DynArray::new_empty(&data_type).is_none(), "{data_type:?}");
+        }
+        for data_type in [
+            DataType::Boolean,
+            DataType::Option(list(numeric(PrimitiveType::I64))),
+            DataType::Option(Box::new(lists)),
+            DataType::Id(numeric(PrimitiveType::I32)),
+        ] {
+            let array = DynArray::new_empty(&data_type).unwrap();
+            assert_eq!(array.data_type(), data_type);
+        }
+    }
+}
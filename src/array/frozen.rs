@@ -0,0 +1,495 @@
+//! Immutable arrays backed by shared buffers: clones and slices are O(1) and can be sent
+//! between threads, `into_mut` copies only if the storage is still shared.
+
+use std::{
+    hash::{BuildHasher, Hash},
+    ops::Range,
+    sync::Arc,
+};
+
+use ahash::RandomState;
+
+use super::{
+    id::IdArray,
+    idvec::{IdIter, IdKey, IdVec, IdWidth},
+    list::ListArray,
+    primitive::PrimitiveArray,
+    slotmap::SlotMap,
+    Array, OptionListArray,
+};
+use crate::{
+    bitvec::FrozenBitVec, buffer::Buffer, primitive::Primitive, scalar::list::OptionSlice,
+};
+
+#[inline]
+fn check_range(range: &Range<usize>, len: usize) {
+    assert!(
+        range.start <= range.end && range.end <= len,
+        "slice {range:?} out of bounds for length {len}"
+    );
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrozenPrimitiveArray<P> {
+    data: Buffer<P>,
+}
+
+impl<P: Primitive> PrimitiveArray<P> {
+    #[inline]
+    pub fn freeze(self) -> FrozenPrimitiveArray<P> {
+        FrozenPrimitiveArray {
+            data: self.data.into(),
+        }
+    }
+}
+
+impl<P: Primitive> FrozenPrimitiveArray<P> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    #[inline]
+    pub fn get(&self, offset: usize) -> Option<&P> {
+        self.data.get(offset)
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[P] {
+        &self.data
+    }
+
+    #[inline]
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        Self {
+            data: self.data.slice(range),
+        }
+    }
+
+    #[inline]
+    pub fn into_mut(self) -> PrimitiveArray<P> {
+        PrimitiveArray {
+            data: self.data.into_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FrozenListArray<P> {
+    data: Buffer<P>,
+    offsets: Buffer<usize>,
+    /// Start of the first list in `data`.
+    first: usize,
+}
+
+impl<P: Primitive> ListArray<P> {
+    #[inline]
+    pub fn freeze(self) -> FrozenListArray<P> {
+        FrozenListArray {
+            data: self.data.into(),
+            offsets: self.offsets.into(),
+            first: 0,
+        }
+    }
+}
+
+impl<P: Primitive> FrozenListArray<P> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    #[inline]
+    fn start(&self, offset: usize) -> usize {
+        match offset {
+            0 => self.first,
+            _ => self.offsets[offset - 1],
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, offset: usize) -> Option<&[P]> {
+        let end = *self.offsets.get(offset)?;
+        Some(&self.data[self.start(offset)..end])
+    }
+
+    #[inline]
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        check_range(&range, self.len());
+        Self {
+            data: self.data.clone(),
+            first: self.start(range.start),
+            offsets: self.offsets.slice(range),
+        }
+    }
+
+    pub fn into_mut(self) -> ListArray<P> {
+        let end = self.offsets.last().copied().unwrap_or(self.first);
+        if self.first == 0 && end == self.data.len() {
+            return ListArray {
+                data: self.data.into_vec(),
+                offsets: self.offsets.into_vec(),
+            };
+        }
+        ListArray {
+            data: self.data[self.first..end].to_vec(),
+            offsets: self
+                .offsets
+                .iter()
+                .map(|offset| offset - self.first)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FrozenOptionListArray<P> {
+    validity: FrozenBitVec,
+    data: Buffer<P>,
+    list_size: usize,
+}
+
+impl<P: Primitive> OptionListArray<P> {
+    #[inline]
+    pub fn freeze(self) -> FrozenOptionListArray<P> {
+        FrozenOptionListArray {
+            validity: self.validity.freeze(),
+            data: self.data.into(),
+            list_size: self.list_size,
+        }
+    }
+}
+
+impl<P: Primitive> FrozenOptionListArray<P> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len().checked_div(self.list_size).unwrap_or(0)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    #[inline]
+    pub fn list_size(&self) -> usize {
+        self.list_size
+    }
+
+    #[inline]
+    pub fn get(&self, offset: usize) -> Option<OptionSlice<'_, P>> {
+        if offset >= self.len() {
+            return None;
+        }
+        let range = offset * self.list_size..(offset + 1) * self.list_size;
+        Some(OptionSlice {
+            validity: self.validity.view(range.clone()),
+            data: &self.data[range],
+        })
+    }
+
+    #[inline]
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        check_range(&range, self.len());
+        let range = range.start * self.list_size..range.end * self.list_size;
+        Self {
+            validity: self.validity.slice(range.clone()),
+            data: self.data.slice(range),
+            list_size: self.list_size,
+        }
+    }
+
+    #[inline]
+    pub fn into_mut(self) -> OptionListArray<P> {
+        OptionListArray {
+            validity: self.validity.into_mut(),
+            data: self.data.into_vec(),
+            list_size: self.list_size,
+        }
+    }
+}
+
+/// Ids in a shared buffer, at the width they had when frozen.
+#[derive(Debug, Clone)]
+pub enum FrozenIdVec {
+    U8(Buffer<u8>),
+    U16(Buffer<u16>),
+    U32(Buffer<u32>),
+    U64(Buffer<u64>),
+}
+
+macro_rules! dispatch {
+    ($vec:expr, $ids:ident => $body:expr) => {
+        match $vec {
+            FrozenIdVec::U8($ids) => $body,
+            FrozenIdVec::U16($ids) => $body,
+            FrozenIdVec::U32($ids) => $body,
+            FrozenIdVec::U64($ids) => $body,
+        }
+    };
+}
+
+impl IdVec {
+    #[inline]
+    pub fn freeze(self) -> FrozenIdVec {
+        match self {
+            Self::U8(ids) => FrozenIdVec::U8(ids.into()),
+            Self::U16(ids) => FrozenIdVec::U16(ids.into()),
+            Self::U32(ids) => FrozenIdVec::U32(ids.into()),
+            Self::U64(ids) => FrozenIdVec::U64(ids.into()),
+        }
+    }
+}
+
+impl FrozenIdVec {
+    #[inline]
+    pub fn width(&self) -> IdWidth {
+        match self {
+            Self::U8(_) => IdWidth::U8,
+            Self::U16(_) => IdWidth::U16,
+            Self::U32(_) => IdWidth::U32,
+            Self::U64(_) => IdWidth::U64,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        dispatch!(self, ids => ids.len())
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<usize> {
+        dispatch!(self, ids => ids.get(index).map(|id| id.to_id()))
+    }
+
+    #[inline]
+    pub fn iter(&self) -> IdIter<'_> {
+        match self {
+            Self::U8(ids) => IdIter::U8(ids.iter()),
+            Self::U16(ids) => IdIter::U16(ids.iter()),
+            Self::U32(ids) => IdIter::U32(ids.iter()),
+            Self::U64(ids) => IdIter::U64(ids.iter()),
+        }
+    }
+
+    #[inline]
+    pub fn to_vec(&self) -> Vec<usize> {
+        self.iter().collect()
+    }
+
+    #[inline]
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        match self {
+            Self::U8(ids) => Self::U8(ids.slice(range)),
+            Self::U16(ids) => Self::U16(ids.slice(range)),
+            Self::U32(ids) => Self::U32(ids.slice(range)),
+            Self::U64(ids) => Self::U64(ids.slice(range)),
+        }
+    }
+
+    #[inline]
+    pub fn into_mut(self) -> IdVec {
+        match self {
+            Self::U8(ids) => IdVec::U8(ids.into_vec()),
+            Self::U16(ids) => IdVec::U16(ids.into_vec()),
+            Self::U32(ids) => IdVec::U32(ids.into_vec()),
+            Self::U64(ids) => IdVec::U64(ids.into_vec()),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a FrozenIdVec {
+    type Item = usize;
+    type IntoIter = IdIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Ids over a dictionary that is shared, not copied, by clones and slices.
+#[derive(Debug)]
+pub struct FrozenIdArray<A: Array, S = RandomState> {
+    values: Arc<SlotMap<A, S>>,
+    data: FrozenIdVec,
+    sorted: bool,
+    keep_sorted: Option<fn(&mut IdArray<A, S>)>,
+}
+
+impl<A: Array, S> Clone for FrozenIdArray<A, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            data: self.data.clone(),
+            sorted: self.sorted,
+            keep_sorted: self.keep_sorted,
+        }
+    }
+}
+
+impl<A: Array, S> IdArray<A, S> {
+    #[inline]
+    pub fn freeze(self) -> FrozenIdArray<A, S> {
+        FrozenIdArray {
+            values: Arc::new(self.values),
+            data: self.data.freeze(),
+            sorted: self.sorted,
+            keep_sorted: self.keep_sorted,
+        }
+    }
+}
+
+impl<A: Array, S> FrozenIdArray<A, S> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Ids of every row; `0` is null.
+    #[inline]
+    pub fn ids(&self) -> &FrozenIdVec {
+        &self.data
+    }
+
+    #[inline]
+    pub fn values(&self) -> &A {
+        &self.values.data
+    }
+
+    /// The state dictionary values are hashed with.
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        self.values.hasher()
+    }
+
+    #[inline]
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        Self {
+            values: self.values.clone(),
+            data: self.data.slice(range),
+            sorted: self.sorted,
+            keep_sorted: self.keep_sorted,
+        }
+    }
+}
+
+impl<A: Array + Clone, S: Clone> FrozenIdArray<A, S> {
+    /// The dictionary is only copied if another frozen array still shares it.
+    #[inline]
+    pub fn into_mut(self) -> IdArray<A, S> {
+        IdArray::from_parts(
+            Arc::try_unwrap(self.values).unwrap_or_else(|values| values.as_ref().clone()),
+            self.data.into_mut(),
+            self.sorted,
+            self.keep_sorted,
+        )
+    }
+}
+
+impl<A: Array, S: BuildHasher> FrozenIdArray<A, S>
+where
+    for<'a, 'b> A::ItemRef<'a>: PartialEq<A::ItemRef<'b>>,
+    for<'a> A::ItemRef<'a>: Hash,
+{
+    #[inline]
+    pub fn get(&self, offset: usize) -> Option<Option<A::ItemRef<'_>>> {
+        self.values.get(self.data.get(offset)?)
+    }
+
+    #[inline]
+    pub fn lookup_id(&self, value: A::ItemRef<'_>) -> Option<usize> {
+        self.values.lookup(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::{
+        array::{
+            id::IdArray, idvec::IdWidth, list::ListArray, primitive::PrimitiveArray, Array,
+            OptionListArray,
+        },
+        scalar::list::OptionList,
+    };
+
+    #[test]
+    fn freeze_slice_thaw() {
+        let mut lists = ListArray::new();
+        for row in 0..6 {
+            lists.push((0..row).collect::<Vec<u32>>());
+        }
+        let frozen = lists.freeze();
+        let slice = frozen.slice(2..5);
+        assert_eq!(slice.get(0), Some(&[0, 1][..]));
+        assert_eq!(slice.slice(1..3).get(1), Some(&[0, 1, 2, 3][..]));
+        let thawed = slice.into_mut();
+        assert_eq!(thawed.len(), 3);
+        assert_eq!(thawed.get(2), Some(&[0, 1, 2, 3][..]));
+        assert_eq!(frozen.into_mut().get(5), Some(&[0, 1, 2, 3, 4][..]));
+
+        let mut points = OptionListArray::new(3);
+        for row in 0..4 {
+            points.push(OptionList::from([Some(row), None, Some(row * 2)]));
+        }
+        let points = points.freeze().slice(1..4);
+        let moved = points.clone();
+        let handle = thread::spawn(move || moved.get(2).unwrap().get(2).unwrap().copied());
+        assert_eq!(handle.join().unwrap(), Some(6));
+        assert_eq!(points.get(0).unwrap().get(1), Some(None));
+        let points = points.into_mut();
+        assert_eq!(points.len(), 3);
+        assert_eq!(points.get(0).unwrap().get(0), Some(Some(&1)));
+
+        let empty = OptionListArray::<u8>::new(0).freeze();
+        assert_eq!(empty.len(), 0);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn shared_dictionary() {
+        let mut values = PrimitiveArray::new();
+        for value in 0..4 {
+            values.push(value);
+        }
+        let values = values.freeze();
+        assert_eq!(values.slice(1..3).as_slice(), &[1, 2]);
+
+        let mut ids = IdArray::new(ListArray::<u8>::new());
+        for value in [Some("a"), None, Some("b"), Some("a")] {
+            ids.push(value.map(Vec::from));
+        }
+        let frozen = ids.freeze();
+        let tail = frozen.slice(2..4);
+        assert_eq!(tail.ids().to_vec(), [2, 1]);
+        assert_eq!(tail.ids().width(), IdWidth::U8);
+        assert_eq!(tail.get(1), Some(Some(&b"a"[..])));
+        drop(frozen);
+        let mut thawed = tail.into_mut();
+        thawed.push(Some(b"c".to_vec()));
+        assert_eq!(thawed.get(2), Some(Some(&b"c"[..])));
+    }
+}
@@ -1,32 +1,212 @@
-use std::hash::Hash;
+use std::{
+    cmp::Ordering,
+    hash::{BuildHasher, Hash},
+    ops::{Bound, Range, RangeBounds},
+};
 
-use super::{slotmap::SlotMap, Array};
+use ahash::RandomState;
+
+use super::{
+    idvec::{IdVec, IdWidth},
+    mutate::ArrayMut,
+    option::OptionArray,
+    slotmap::{seeded_state, SlotMap},
+    Array,
+};
+use crate::{
+    bitvec::BitVec,
+    compute::{dictionary::unify_dictionaries, sort::compare},
+    scalar::ScalarRef,
+};
 
 #[derive(Debug, Clone)]
-pub struct IdArray<A: Array> {
-    values: SlotMap<A>,
-    data: Vec<usize>,
+pub struct IdArray<A: Array, S = RandomState> {
+    pub(crate) values: SlotMap<A, S>,
+    pub(crate) data: IdVec,
+    /// Rows per id, null rows under `0`, while tracking is enabled.
+    pub(crate) ref_counts: Option<Vec<usize>>,
+    /// Whether id order is known to match value order.
+    pub(crate) sorted: bool,
+    /// Restores the dictionary order after a value is interned, while it is kept sorted.
+    pub(crate) keep_sorted: Option<fn(&mut Self)>,
 }
 
 impl<A: Array> IdArray<A> {
     #[inline]
     pub fn new(array: A) -> Self {
-        Self {
-            values: SlotMap::new(array),
-            data: Vec::new(),
-        }
+        Self::with_capacity(0, array)
     }
 
     #[inline]
     pub fn with_capacity(capacity: usize, array: A) -> Self {
-        Self {
-            values: SlotMap::new(array),
-            data: Vec::<usize>::with_capacity(capacity),
+        Self::from_parts(
+            SlotMap::new(array),
+            IdVec::with_capacity(capacity),
+            true,
+            None,
+        )
+    }
+
+    /// Hashes dictionary values deterministically; see [`seeded_state`](super::seeded_state).
+    #[inline]
+    pub fn with_seed(array: A, seed: u64) -> Self {
+        Self::with_hasher(array, seeded_state(seed))
+    }
+}
+
+impl<A: Array, S> IdArray<A, S> {
+    /// Hashes dictionary values with `hash_builder`, which is kept through
+    /// [`compact`](Self::compact) and [`sort_dictionary`](Self::sort_dictionary).
+    #[inline]
+    pub fn with_hasher(array: A, hash_builder: S) -> Self {
+        Self::from_parts(
+            SlotMap::with_hasher(array, hash_builder),
+            IdVec::new(),
+            true,
+            None,
+        )
+    }
+
+    /// The state dictionary values are hashed with.
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        self.values.hasher()
+    }
+
+    /// The dictionary; the value with id `n` is at offset `n - 1`.
+    #[inline]
+    pub fn values(&self) -> &A {
+        &self.values.data
+    }
+
+    /// The id of every row, `0` for null.
+    #[inline]
+    pub fn ids(&self) -> &IdVec {
+        &self.data
+    }
+
+    /// The width ids are stored at; it grows with the dictionary.
+    #[inline]
+    pub fn id_width(&self) -> IdWidth {
+        self.data.width()
+    }
+
+    /// Stores ids at `width` or wider from now on, sparing the promotions on the way there when
+    /// the number of distinct values is known up front.
+    #[inline]
+    pub fn widen_ids(&mut self, width: IdWidth) {
+        self.data.widen(width);
+    }
+
+    /// `sorted` and `keep_sorted` carry the dictionary order of the array the parts come from; a
+    /// dictionary to be kept sorted that is not yet sorted is sorted here.
+    #[inline]
+    pub(crate) fn from_parts(
+        values: SlotMap<A, S>,
+        data: IdVec,
+        sorted: bool,
+        keep_sorted: Option<fn(&mut Self)>,
+    ) -> Self {
+        let mut array = Self {
+            sorted: sorted || values.data.is_empty(),
+            keep_sorted,
+            values,
+            data,
+            ref_counts: None,
+        };
+        if let (Some(restore), false) = (keep_sorted, array.sorted) {
+            restore(&mut array);
+        }
+        array
+    }
+
+    /// Call once the rows refer to a freshly interned value, which may break the dictionary order.
+    #[inline]
+    pub(crate) fn interned(&mut self) {
+        match self.keep_sorted {
+            Some(restore) => restore(self),
+            None => self.sorted = false,
+        }
+    }
+
+    /// Whether id order matches value order, so ids can be compared in place of values.
+    #[inline]
+    pub fn is_dictionary_sorted(&self) -> bool {
+        self.sorted
+    }
+
+    #[inline]
+    pub(crate) fn acquire(&mut self, id: usize) {
+        if let Some(counts) = &mut self.ref_counts {
+            if counts.len() <= id {
+                counts.resize(id + 1, 0);
+            }
+            counts[id] += 1;
         }
     }
+
+    #[inline]
+    pub(crate) fn release(&mut self, id: usize) {
+        if let Some(counts) = &mut self.ref_counts {
+            counts[id] -= 1;
+        }
+    }
+
+    fn count_refs(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.values.data.len() + 1];
+        for id in &self.data {
+            counts[id] += 1;
+        }
+        counts
+    }
+
+    /// Keeps the number of rows per id up to date from now on, so
+    /// [`ref_count`](Self::ref_count) and [`unused_ids`](Self::unused_ids) need no scan.
+    #[inline]
+    pub fn track_ref_counts(&mut self) {
+        if self.ref_counts.is_none() {
+            self.ref_counts = Some(self.count_refs());
+        }
+    }
+
+    #[inline]
+    pub fn untrack_ref_counts(&mut self) {
+        self.ref_counts = None;
+    }
+
+    #[inline]
+    pub fn is_tracking_ref_counts(&self) -> bool {
+        self.ref_counts.is_some()
+    }
+
+    /// Rows referring to `id`; `0` counts the null rows.
+    pub fn ref_count(&self, id: usize) -> usize {
+        match &self.ref_counts {
+            Some(counts) => counts.get(id).copied().unwrap_or(0),
+            None => self.data.iter().filter(|&row| row == id).count(),
+        }
+    }
+
+    /// Ids of dictionary values no row refers to.
+    pub fn unused_ids(&self) -> Vec<usize> {
+        let counts = match &self.ref_counts {
+            Some(counts) => counts.clone(),
+            None => self.count_refs(),
+        };
+        (1..=self.values.data.len())
+            .filter(|&id| counts.get(id).is_none_or(|&count| count == 0))
+            .collect()
+    }
+}
+
+impl<A: Array + Default, S: Default> Default for IdArray<A, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_hasher(A::default(), S::default())
+    }
 }
 
-impl<A: Array> IdArray<A>
+impl<A: Array, S: BuildHasher + 'static> IdArray<A, S>
 where
     for<'a, 'b> A::ItemRef<'a>: PartialEq<A::ItemRef<'b>>,
     for<'a> A::ItemRef<'a>: Hash,
@@ -36,23 +216,263 @@ where
         self.values.lookup(value)
     }
 
+    /// Interns `item`, returning its id and whether it is new to the dictionary.
+    #[inline]
+    pub(crate) fn intern(&mut self, item: A::Item) -> (usize, bool) {
+        let len = self.values.data.len();
+        let id = self.values.lookup_or_insert(item);
+        (id, id > len)
+    }
+
+    /// The id may change later if the dictionary is sorted or kept sorted.
     #[inline]
     pub fn push_and_get_id(&mut self, value: <Self as Array>::Item) -> usize {
-        match value {
-            Some(value) => {
-                let valud_id = self.values.lookup_or_insert(value);
-                self.data.push(valud_id);
-                valud_id
+        self.push(value);
+        self.data.last().unwrap()
+    }
+}
+
+impl<A: Array + Default> IdArray<A>
+where
+    for<'a, 'b> A::ItemRef<'a>: PartialEq<A::ItemRef<'b>>,
+    for<'a> A::ItemRef<'a>: Hash,
+{
+    /// Dictionary-encodes every row of `array`.
+    pub fn encode(array: &A) -> Self {
+        let mut encoded = Self::with_capacity(array.len(), A::default());
+        encoded.extend(array.iter().map(|item| Some(item.into_owned())));
+        encoded
+    }
+
+    /// Dictionary-encodes the valid rows of `array`; null rows get id `0`.
+    pub fn encode_option(array: &OptionArray<A>) -> Self {
+        let mut encoded = Self::with_capacity(array.len(), A::default());
+        encoded.extend(array.iter().map(|item| item.map(ScalarRef::into_owned)));
+        encoded
+    }
+}
+
+impl<A: Array + Default, S: BuildHasher + Clone> IdArray<A, S>
+where
+    for<'a, 'b> A::ItemRef<'a>: PartialEq<A::ItemRef<'b>>,
+    for<'a> A::ItemRef<'a>: Hash,
+{
+    /// Joins the rows of `arrays` over one dictionary holding the values of all of theirs, hashed
+    /// like the first input's. The dictionary is kept sorted if every input's is.
+    pub fn concat(arrays: &[Self]) -> Self
+    where
+        S: Default,
+    {
+        let unified = unify_dictionaries(arrays);
+        let mut data = IdVec::with_width(
+            IdWidth::of(unified.values().len()),
+            arrays.iter().map(|array| array.data.len()).sum(),
+        );
+        for (input, array) in arrays.iter().enumerate() {
+            let remap = unified.remap(input);
+            data.extend(array.data.iter().map(|id| remap[id]));
+        }
+        let mut concatenated = unified.into_array(data);
+        if arrays.iter().all(|array| array.keep_sorted.is_some()) {
+            if let Some(restore) = arrays.first().and_then(|array| array.keep_sorted) {
+                concatenated.keep_sorted = Some(restore);
+                restore(&mut concatenated);
             }
-            None => {
-                self.push_zero();
-                0
+        }
+        concatenated
+    }
+
+    /// Drops the dictionary values no row refers to and renumbers the remaining ones in their
+    /// current order. Returns how many values were dropped.
+    pub fn compact(&mut self) -> usize {
+        let counts = match &self.ref_counts {
+            Some(counts) => counts.clone(),
+            None => self.count_refs(),
+        };
+        let len = self.values.data.len();
+        let live = (1..=len)
+            .filter(|&id| counts.get(id).is_some_and(|&count| count > 0))
+            .collect::<Vec<_>>();
+        self.reorder(&live);
+        len - self.values.data.len()
+    }
+
+    /// Rebuilds the dictionary from the old ids in `order` and remaps the rows; ids missing from
+    /// `order` must be unused.
+    fn reorder(&mut self, order: &[usize]) {
+        let mut values = SlotMap::with_hasher(A::default(), self.values.hasher().clone());
+        let mut remap = vec![0; self.values.data.len() + 1];
+        for &id in order {
+            let value = unsafe { self.values.data.get_unchecked(id - 1) };
+            remap[id] = values.lookup_or_insert_ref(value);
+        }
+        self.data = self.data.remap(&remap);
+        if let Some(counts) = self.ref_counts.take() {
+            let mut remapped = vec![0; values.data.len() + 1];
+            for (id, count) in counts.into_iter().enumerate() {
+                remapped[remap[id]] += count;
             }
+            self.ref_counts = Some(remapped);
         }
+        self.values = values;
+    }
+
+    /// Expands the ids back into a plain array of values.
+    pub fn decode(&self) -> OptionArray<A> {
+        let mut decoded = OptionArray::new(A::default());
+        for id in &self.data {
+            decoded.push(unsafe { self.values.get_unchecked(id) }.map(ScalarRef::into_owned));
+        }
+        decoded
     }
 }
 
-impl<A: Array> Array for IdArray<A>
+impl<A: ArrayMut + Default, S: BuildHasher + Clone> IdArray<A, S>
+where
+    for<'a, 'b> A::ItemRef<'a>: PartialEq<A::ItemRef<'b>>,
+    for<'a, 'b> A::ItemRef<'a>: PartialOrd<A::ItemRef<'b>>,
+    for<'a> A::ItemRef<'a>: Hash,
+{
+    /// Renumbers the dictionary so id order matches value order. New values may break the order
+    /// again unless it is [kept](Self::keep_dictionary_sorted).
+    pub fn sort_dictionary(&mut self) {
+        if !self.sorted {
+            let mut order = (1..=self.values.data.len()).collect::<Vec<_>>();
+            order.sort_unstable_by(|&lhs, &rhs| unsafe {
+                let values = &self.values.data;
+                compare(
+                    &values.get_unchecked(lhs - 1),
+                    &values.get_unchecked(rhs - 1),
+                )
+            });
+            self.reorder(&order);
+            self.sorted = true;
+        }
+    }
+
+    /// Sorts the dictionary and keeps it sorted: a value interned out of order takes the id of
+    /// its sorted position and the ids above it shift up by one, remapping the rows that refer to
+    /// them, so this suits dictionaries that are small or mostly filled in order.
+    pub fn keep_dictionary_sorted(&mut self, keep: bool) {
+        if keep {
+            self.sort_dictionary();
+            self.keep_sorted = Some(Self::restore_order);
+        } else {
+            self.keep_sorted = None;
+        }
+    }
+
+    fn restore_order(&mut self) {
+        if self.sorted {
+            self.insert_sorted();
+        } else {
+            self.sort_dictionary();
+        }
+    }
+
+    /// Moves the last interned value to its sorted position.
+    fn insert_sorted(&mut self) {
+        let Some(last) = self.values.data.len().checked_sub(1) else {
+            return;
+        };
+        // First offset whose value sorts after the new one.
+        let (mut lo, mut hi) = (0, last);
+        {
+            let values = &self.values.data;
+            let value = unsafe { values.get_unchecked(last) };
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                if compare(unsafe { &values.get_unchecked(mid) }, &value).is_gt() {
+                    hi = mid;
+                } else {
+                    lo = mid + 1;
+                }
+            }
+        }
+        if lo == last {
+            return;
+        }
+        self.values.move_last(lo);
+        let (old, new) = (last + 1, lo + 1);
+        let remap = (0..=old)
+            .map(|id| match id {
+                _ if id == old => new,
+                _ if id >= new => id + 1,
+                _ => id,
+            })
+            .collect::<Vec<_>>();
+        let width = self.data.width();
+        self.data = self.data.remap(&remap);
+        self.data.widen(width);
+        if let Some(counts) = &mut self.ref_counts {
+            if counts.len() > old {
+                let count = counts.remove(old);
+                counts.insert(new, count);
+            }
+        }
+    }
+
+    /// Ids of the dictionary values within `range`, or `None` unless the dictionary is sorted.
+    pub fn lookup_range<'v>(
+        &self,
+        range: impl RangeBounds<A::ItemRef<'v>>,
+    ) -> Option<Range<usize>> {
+        if !self.sorted {
+            return None;
+        }
+        let values = &self.values.data;
+        // First offset whose value is not below `bound`, past it when `inclusive` is false.
+        let partition = |bound: &A::ItemRef<'v>, inclusive: bool| {
+            let (mut lo, mut hi) = (0, values.len());
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                let value = unsafe { values.get_unchecked(mid) };
+                let below = match value.partial_cmp(bound) {
+                    Some(Ordering::Less) => true,
+                    Some(Ordering::Equal) => !inclusive,
+                    _ => false,
+                };
+                if below {
+                    lo = mid + 1;
+                } else {
+                    hi = mid;
+                }
+            }
+            lo
+        };
+        let start = match range.start_bound() {
+            Bound::Included(lo) => partition(lo, true),
+            Bound::Excluded(lo) => partition(lo, false),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(hi) => partition(hi, false),
+            Bound::Excluded(hi) => partition(hi, true),
+            Bound::Unbounded => values.len(),
+        };
+        Some(start + 1..end.max(start) + 1)
+    }
+
+    /// Marks the rows whose value lies within `range` by comparing ids only; null rows are never
+    /// marked. `None` unless the dictionary is sorted.
+    pub fn in_range<'v>(&self, range: impl RangeBounds<A::ItemRef<'v>>) -> Option<BitVec> {
+        let ids = self.lookup_range(range)?;
+        Some(self.data.iter().map(|id| ids.contains(&id)).collect())
+    }
+
+    /// Row indices in ascending order of their values, nulls first, sorting ids only. `None`
+    /// unless the dictionary is sorted.
+    pub fn argsort_ids(&self) -> Option<Vec<usize>> {
+        if !self.sorted {
+            return None;
+        }
+        let mut indices = (0..self.data.len()).collect::<Vec<_>>();
+        indices.sort_by_key(|&row| unsafe { self.data.get_unchecked(row) });
+        Some(indices)
+    }
+}
+
+impl<A: Array, S: BuildHasher + 'static> Array for IdArray<A, S>
 where
     for<'a, 'b> A::ItemRef<'a>: PartialEq<A::ItemRef<'b>>,
     for<'a> A::ItemRef<'a>: Hash,
@@ -71,7 +491,12 @@ where
     fn push(&mut self, item: Self::Item) {
         match item {
             Some(item) => {
-                self.data.push(self.values.lookup_or_insert(item));
+                let (id, new) = self.intern(item);
+                self.data.push(id);
+                self.acquire(id);
+                if new {
+                    self.interned();
+                }
             }
             None => {
                 self.push_zero();
@@ -82,28 +507,29 @@ where
     #[inline]
     fn push_zero(&mut self) {
         self.data.push(0);
+        self.acquire(0);
     }
 
     #[inline]
     fn get(&self, offset: usize) -> Option<Self::ItemRef<'_>> {
-        let id = self.data.get(offset)?;
-        self.values.get(*id)
+        self.values.get(self.data.get(offset)?)
     }
 
     #[inline]
     unsafe fn get_unchecked(&self, offset: usize) -> Self::ItemRef<'_> {
-        self.values.get_unchecked(self.data[offset])
+        self.values.get_unchecked(self.data.get_unchecked(offset))
     }
 
     #[inline]
     fn get_mut(&mut self, offset: usize) -> Option<Self::ItemMut<'_>> {
         let id = self.data.get(offset)?;
-        self.values.get_mut(*id)
+        self.values.get_mut(id)
     }
 
     #[inline]
     unsafe fn get_unchecked_mut(&mut self, offset: usize) -> Self::ItemMut<'_> {
-        self.values.get_unchecked_mut(self.data[offset])
+        self.values
+            .get_unchecked_mut(self.data.get_unchecked(offset))
     }
 
     #[inline]
@@ -111,3 +537,186 @@ where
         self.data.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::hash_map::DefaultHasher, hash::BuildHasherDefault};
+
+    use super::IdArray;
+    use crate::{
+        array::{
+            idvec::IdWidth, list::ListArray, mutate::ArrayMut, primitive::PrimitiveArray, Array,
+            ArrayBuilder, ChunkedIdArray, ConcurrentSlotMap, DynArray, IdArrayBuilder,
+        },
+        compute::{self, select::Select},
+    };
+
+    fn words(words: &[&str]) -> IdArray<ListArray<u8>> {
+        words
+            .iter()
+            .map(|word| (!word.is_empty()).then(|| word.as_bytes().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn compact_drops_unused_values() {
+        let mut array = words(&["a", "b", "", "c", "b"]);
+        array.set(0, Some(b"b".to_vec()));
+        array.retain(|word| word != Some(&b"c"[..]));
+        assert_eq!(array.unused_ids(), [1, 3]);
+        assert_eq!(array.ref_count(0), 1);
+
+        assert_eq!(array.compact(), 2);
+        assert_eq!(array.values.data.len(), 1);
+        assert_eq!(array.ids().to_vec(), [1, 1, 0, 1]);
+        assert_eq!(array.get(3), Some(Some(&b"b"[..])));
+        assert_eq!(array.compact(), 0);
+    }
+
+    #[test]
+    fn concat_remaps_ids() {
+        let first = words(&["x", "", "y"]);
+        let second = words(&["y", "z"]);
+        let all = IdArray::concat(&[first, second]);
+        assert_eq!(all.values().len(), 3);
+        assert_eq!(all.ids().to_vec(), [1, 0, 2, 2, 3]);
+        assert_eq!(all.get(4), Some(Some(&b"z"[..])));
+    }
+
+    #[test]
+    fn ids_widen_with_dictionary() {
+        let mut array = IdArray::new(PrimitiveArray::<u32>::new());
+        array.extend((0..255).map(Some));
+        assert_eq!(array.id_width(), IdWidth::U8);
+        array.push(Some(255));
+        assert_eq!(array.id_width(), IdWidth::U16);
+        assert_eq!(array.get(255), Some(Some(&255)));
+
+        array.truncate(10);
+        array.compact();
+        assert_eq!(array.id_width(), IdWidth::U8);
+        array.widen_ids(IdWidth::U32);
+        assert_eq!(array.ids().byte_len(), 40);
+    }
+
+    #[test]
+    fn seeded_hasher_survives_compaction() {
+        let mut array = IdArray::with_seed(ListArray::<u8>::new(), 42);
+        array.extend([Some(b"b".to_vec()), Some(b"a".to_vec())]);
+        let hash = array.hasher().hash_one(&b"a"[..]);
+        array.set(0, None);
+        array.compact();
+        array.sort_dictionary();
+        assert_eq!(array.hasher().hash_one(&b"a"[..]), hash);
+        let joined = IdArray::concat(&[array.clone(), array]);
+        assert_eq!(joined.hasher().hash_one(&b"a"[..]), hash);
+        assert_eq!(
+            IdArray::<ListArray<u8>>::with_seed(ListArray::new(), 42)
+                .hasher()
+                .hash_one(&b"a"[..]),
+            hash
+        );
+    }
+
+    #[test]
+    fn custom_hasher() {
+        type Fixed = BuildHasherDefault<DefaultHasher>;
+        let mut array = IdArray::with_hasher(ListArray::<u8>::new(), Fixed::default());
+        array.extend([Some(b"b".to_vec()), None, Some(b"a".to_vec())]);
+        array.keep_dictionary_sorted(true);
+        assert_eq!(array.lookup_id(&b"b"[..]), Some(2));
+
+        let mut builder = IdArrayBuilder::with_hasher(ListArray::new(), Fixed::default());
+        builder.append_array(&array);
+        let frozen = array.freeze();
+        assert_eq!(frozen.lookup_id(&b"a"[..]), Some(1));
+        let mut thawed = frozen.into_mut();
+        thawed.push(Some(b"c".to_vec()));
+        let built = builder.finish();
+        assert!(built.is_dictionary_sorted());
+        assert_eq!(built.ids().to_vec(), [2, 0, 1]);
+
+        let joined = IdArray::concat(&[built, thawed.clone()]);
+        assert!(joined.is_dictionary_sorted());
+        let hashes = compute::hash(&joined, joined.hasher());
+        assert_eq!(hashes[2], hashes[5]);
+        let erased = DynArray::new(joined);
+        assert!(erased.is::<IdArray<ListArray<u8>, Fixed>>());
+
+        let mut chunked = ChunkedIdArray::with_hasher(ListArray::new(), Fixed::default());
+        chunked.push_chunk(thawed);
+        assert_eq!(chunked.lookup_id(&b"c"[..]), Some(3));
+
+        let map = ConcurrentSlotMap::with_hasher(2, Fixed::default());
+        let mut ids = map.builder();
+        ids.push(Some(b"x".to_vec()));
+        let parts = vec![ids.into_ids()];
+        let array: IdArray<ListArray<u8>, Fixed> = map.into_id_array(parts);
+        assert_eq!(array.get(0), Some(Some(&b"x"[..])));
+    }
+
+    #[test]
+    fn sorted_dictionary_ranges() {
+        let mut array = words(&["pear", "", "apple", "fig", "pear"]);
+        assert!(!array.is_dictionary_sorted());
+        assert_eq!(array.lookup_range(..), None);
+        array.sort_dictionary();
+        assert_eq!(array.ids().to_vec(), [3, 0, 1, 2, 3]);
+        assert_eq!(array.get(2), Some(Some(&b"apple"[..])));
+
+        assert_eq!(array.lookup_range(&b"b"[..]..&b"pear"[..]), Some(2..3));
+        assert_eq!(array.lookup_range(&b"fig"[..]..=&b"pear"[..]), Some(2..4));
+        assert_eq!(array.lookup_range(&b"q"[..]..), Some(4..4));
+        let mask = array.in_range(..&b"g"[..]).unwrap();
+        assert_eq!(mask, [false, false, true, true, false].into());
+        assert_eq!(array.argsort_ids(), Some(vec![1, 2, 3, 0, 4]));
+
+        array.push(Some(b"zebra".to_vec()));
+        assert!(!array.is_dictionary_sorted());
+        array.keep_dictionary_sorted(true);
+        array.track_ref_counts();
+        assert_eq!(array.push_and_get_id(Some(b"banana".to_vec())), 2);
+        array.set(1, Some(b"cherry".to_vec()));
+        assert!(array.is_dictionary_sorted());
+        assert_eq!(array.ids().to_vec(), [5, 3, 1, 4, 5, 6, 2]);
+        assert_eq!(array.ref_counts, Some(array.count_refs()));
+        assert_eq!(array.lookup_id(&b"fig"[..]), Some(4));
+
+        let taken = array.take(&[6, 0]);
+        assert!(taken.is_dictionary_sorted());
+        let mut joined = IdArray::concat(&[taken, array]);
+        assert_eq!(joined.push_and_get_id(Some(b"date".to_vec())), 4);
+        assert!(joined.is_dictionary_sorted());
+        assert_eq!(joined.ids().iter().take(3).collect::<Vec<_>>(), [2, 6, 6]);
+
+        let mut builder = IdArrayBuilder::new(ListArray::new());
+        builder.append_values([&b"b"[..], b"a"]);
+        assert!(!builder.clone().finish().is_dictionary_sorted());
+        builder.append_array(&joined);
+        let built = builder.finish();
+        assert!(built.is_dictionary_sorted());
+        assert_eq!(built.ids().iter().take(2).collect::<Vec<_>>(), [3, 1]);
+    }
+
+    #[test]
+    fn tracked_ref_counts() {
+        let mut array = words(&["a", "b", "a"]);
+        array.track_ref_counts();
+        assert_eq!(array.ref_count(1), 2);
+        array.push(Some(b"c".to_vec()));
+        array.push(None);
+        array.set(1, Some(b"a".to_vec()));
+        array.swap_remove(0);
+        assert_eq!(array.ref_count(1), 2);
+        assert_eq!(array.unused_ids(), [2]);
+
+        array.truncate(3);
+        assert_eq!(array.unused_ids(), [2, 3]);
+        assert_eq!(array.ref_count(0), 1);
+        assert_eq!(array.compact(), 2);
+        assert!(array.is_tracking_ref_counts());
+        assert_eq!(array.ref_count(1), 2);
+        assert_eq!(array.ids().to_vec(), [0, 1, 1]);
+        assert_eq!(array.ref_counts, Some(array.count_refs()));
+    }
+}
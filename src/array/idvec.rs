@@ -0,0 +1,406 @@
+//! Row ids stored in the narrowest unsigned width that holds them.
+//!
+//! [`IdVec`] starts with one byte per id and promotes every id to the next [`IdWidth`] as soon
+//! as one does not fit, so a column with a handful of distinct values costs a byte per row.
+
+use std::{iter::FusedIterator, slice};
+
+/// An unsigned type ids can be stored as.
+pub(crate) trait IdKey: Copy + Send + Sync + 'static {
+    /// `id` must fit the type.
+    fn from_id(id: usize) -> Self;
+
+    fn to_id(self) -> usize;
+}
+
+macro_rules! id_key {
+    ($($ty:ty),*) => {
+        $(
+            impl IdKey for $ty {
+                #[inline]
+                fn from_id(id: usize) -> Self {
+                    id as $ty
+                }
+
+                #[inline]
+                fn to_id(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+id_key!(u8, u16, u32, u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IdWidth {
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl IdWidth {
+    /// The narrowest width holding `id`.
+    #[inline]
+    pub fn of(id: usize) -> Self {
+        if id <= u8::MAX as usize {
+            Self::U8
+        } else if id <= u16::MAX as usize {
+            Self::U16
+        } else if id as u64 <= u32::MAX as u64 {
+            Self::U32
+        } else {
+            Self::U64
+        }
+    }
+
+    #[inline]
+    pub fn max_id(self) -> usize {
+        match self {
+            Self::U8 => u8::MAX as usize,
+            Self::U16 => u16::MAX as usize,
+            Self::U32 => u32::MAX as usize,
+            Self::U64 => u64::MAX as usize,
+        }
+    }
+
+    #[inline]
+    pub fn bytes(self) -> usize {
+        match self {
+            Self::U8 => 1,
+            Self::U16 => 2,
+            Self::U32 => 4,
+            Self::U64 => 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum IdVec {
+    U8(Vec<u8>),
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+    U64(Vec<u64>),
+}
+
+macro_rules! dispatch {
+    ($vec:expr, $ids:ident => $body:expr) => {
+        match $vec {
+            IdVec::U8($ids) => $body,
+            IdVec::U16($ids) => $body,
+            IdVec::U32($ids) => $body,
+            IdVec::U64($ids) => $body,
+        }
+    };
+}
+
+#[inline]
+fn convert<K: IdKey>(ids: impl ExactSizeIterator<Item = usize>) -> Vec<K> {
+    let mut converted = Vec::with_capacity(ids.len());
+    converted.extend(ids.map(K::from_id));
+    converted
+}
+
+impl Default for IdVec {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdVec {
+    #[inline]
+    pub fn new() -> Self {
+        Self::U8(Vec::new())
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_width(IdWidth::U8, capacity)
+    }
+
+    #[inline]
+    pub fn with_width(width: IdWidth, capacity: usize) -> Self {
+        match width {
+            IdWidth::U8 => Self::U8(Vec::with_capacity(capacity)),
+            IdWidth::U16 => Self::U16(Vec::with_capacity(capacity)),
+            IdWidth::U32 => Self::U32(Vec::with_capacity(capacity)),
+            IdWidth::U64 => Self::U64(Vec::with_capacity(capacity)),
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> IdWidth {
+        match self {
+            Self::U8(_) => IdWidth::U8,
+            Self::U16(_) => IdWidth::U16,
+            Self::U32(_) => IdWidth::U32,
+            Self::U64(_) => IdWidth::U64,
+        }
+    }
+
+    /// Converts every id to `width` unless they are already at least that wide.
+    pub fn widen(&mut self, width: IdWidth) {
+        if width <= self.width() {
+            return;
+        }
+        let ids = self.iter();
+        *self = match width {
+            IdWidth::U8 => unreachable!(),
+            IdWidth::U16 => Self::U16(convert(ids)),
+            IdWidth::U32 => Self::U32(convert(ids)),
+            IdWidth::U64 => Self::U64(convert(ids)),
+        };
+    }
+
+    #[inline]
+    fn fit(&mut self, id: usize) {
+        if id > self.width().max_id() {
+            self.widen(IdWidth::of(id));
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        dispatch!(self, ids => ids.len())
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Bytes taken by the ids themselves, not counting spare capacity.
+    #[inline]
+    pub fn byte_len(&self) -> usize {
+        self.len() * self.width().bytes()
+    }
+
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<usize> {
+        dispatch!(self, ids => ids.get(index).map(|id| id.to_id()))
+    }
+
+    /// # Safety
+    ///
+    /// `index` must be in bounds.
+    #[inline]
+    pub unsafe fn get_unchecked(&self, index: usize) -> usize {
+        dispatch!(self, ids => ids.get_unchecked(index).to_id())
+    }
+
+    #[inline]
+    pub fn last(&self) -> Option<usize> {
+        dispatch!(self, ids => ids.last().map(|id| id.to_id()))
+    }
+
+    #[inline]
+    pub fn push(&mut self, id: usize) {
+        self.fit(id);
+        dispatch!(self, ids => ids.push(IdKey::from_id(id)))
+    }
+
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[inline]
+    pub fn set(&mut self, index: usize, id: usize) {
+        self.fit(id);
+        dispatch!(self, ids => ids[index] = IdKey::from_id(id))
+    }
+
+    #[inline]
+    pub fn insert(&mut self, index: usize, id: usize) {
+        self.fit(id);
+        dispatch!(self, ids => ids.insert(index, IdKey::from_id(id)))
+    }
+
+    #[inline]
+    pub fn remove(&mut self, index: usize) -> usize {
+        dispatch!(self, ids => ids.remove(index).to_id())
+    }
+
+    #[inline]
+    pub fn swap(&mut self, a: usize, b: usize) {
+        dispatch!(self, ids => ids.swap(a, b))
+    }
+
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        dispatch!(self, ids => ids.truncate(len))
+    }
+
+    #[inline]
+    pub fn resize(&mut self, len: usize, id: usize) {
+        self.fit(id);
+        dispatch!(self, ids => ids.resize(len, IdKey::from_id(id)))
+    }
+
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        dispatch!(self, ids => ids.reserve(additional))
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(usize) -> bool) {
+        dispatch!(self, ids => ids.retain(|id| keep(id.to_id())))
+    }
+
+    /// Replaces every id with `remap[id]`, at the narrowest width holding the largest of them.
+    pub fn remap(&self, remap: &[usize]) -> Self {
+        let width = IdWidth::of(remap.iter().copied().max().unwrap_or(0));
+        let ids = self.iter().map(|id| remap[id]);
+        match width {
+            IdWidth::U8 => Self::U8(convert(ids)),
+            IdWidth::U16 => Self::U16(convert(ids)),
+            IdWidth::U32 => Self::U32(convert(ids)),
+            IdWidth::U64 => Self::U64(convert(ids)),
+        }
+    }
+
+    #[inline]
+    pub fn iter(&self) -> IdIter<'_> {
+        match self {
+            Self::U8(ids) => IdIter::U8(ids.iter()),
+            Self::U16(ids) => IdIter::U16(ids.iter()),
+            Self::U32(ids) => IdIter::U32(ids.iter()),
+            Self::U64(ids) => IdIter::U64(ids.iter()),
+        }
+    }
+
+    #[inline]
+    pub fn to_vec(&self) -> Vec<usize> {
+        self.iter().collect()
+    }
+}
+
+/// Equal ids compare equal whatever their width.
+impl PartialEq for IdVec {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for IdVec {}
+
+/// Stored at the narrowest width holding the largest id.
+impl From<Vec<usize>> for IdVec {
+    fn from(ids: Vec<usize>) -> Self {
+        let width = IdWidth::of(ids.iter().copied().max().unwrap_or(0));
+        let ids = ids.into_iter();
+        match width {
+            IdWidth::U8 => Self::U8(convert(ids)),
+            IdWidth::U16 => Self::U16(convert(ids)),
+            IdWidth::U32 => Self::U32(convert(ids)),
+            IdWidth::U64 => Self::U64(convert(ids)),
+        }
+    }
+}
+
+impl FromIterator<usize> for IdVec {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = usize>>(ids: T) -> Self {
+        let ids = ids.into_iter();
+        let mut vec = Self::with_capacity(ids.size_hint().0);
+        vec.extend(ids);
+        vec
+    }
+}
+
+impl Extend<usize> for IdVec {
+    #[inline]
+    fn extend<T: IntoIterator<Item = usize>>(&mut self, ids: T) {
+        let ids = ids.into_iter();
+        self.reserve(ids.size_hint().0);
+        for id in ids {
+            self.push(id);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a IdVec {
+    type Item = usize;
+    type IntoIter = IdIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum IdIter<'a> {
+    U8(slice::Iter<'a, u8>),
+    U16(slice::Iter<'a, u16>),
+    U32(slice::Iter<'a, u32>),
+    U64(slice::Iter<'a, u64>),
+}
+
+macro_rules! dispatch_iter {
+    ($iter:expr, $ids:ident => $body:expr) => {
+        match $iter {
+            IdIter::U8($ids) => $body,
+            IdIter::U16($ids) => $body,
+            IdIter::U32($ids) => $body,
+            IdIter::U64($ids) => $body,
+        }
+    };
+}
+
+impl Iterator for IdIter<'_> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        dispatch_iter!(self, ids => ids.next().map(|id| id.to_id()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        dispatch_iter!(self, ids => ids.size_hint())
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<usize> {
+        dispatch_iter!(self, ids => ids.nth(n).map(|id| id.to_id()))
+    }
+}
+
+impl DoubleEndedIterator for IdIter<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<usize> {
+        dispatch_iter!(self, ids => ids.next_back().map(|id| id.to_id()))
+    }
+}
+
+impl ExactSizeIterator for IdIter<'_> {}
+
+impl FusedIterator for IdIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{IdVec, IdWidth};
+
+    #[test]
+    fn promotes_on_push() {
+        let mut ids = IdVec::new();
+        ids.extend([0, 3, 255]);
+        assert_eq!(ids.width(), IdWidth::U8);
+        assert_eq!(ids.byte_len(), 3);
+        ids.push(256);
+        assert_eq!(ids.width(), IdWidth::U16);
+        ids.set(0, 1 << 20);
+        assert_eq!(ids.width(), IdWidth::U32);
+        assert_eq!(ids.to_vec(), [1 << 20, 3, 255, 256]);
+        assert_eq!(ids.iter().rev().nth(1), Some(255));
+
+        ids.retain(|id| id < 256);
+        assert_eq!(ids, IdVec::from(vec![3, 255]));
+        assert_eq!(IdVec::from(vec![3, 255]).width(), IdWidth::U8);
+        let remapped = IdVec::from(vec![2, 0, 1]).remap(&[0, 1000, 1]);
+        assert_eq!(remapped.width(), IdWidth::U16);
+        assert_eq!(remapped.to_vec(), [1, 0, 1000]);
+    }
+}
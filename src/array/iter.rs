@@ -0,0 +1,329 @@
+//! Standard iteration over arrays.
+//!
+//! [`Iter`] borrows an array and yields its [`Array::ItemRef`]s, [`IntoIter`] consumes it and
+//! yields owned [`Array::Item`]s. Every array can be collected from and extended with its items.
+
+use std::{
+    hash::{BuildHasher, Hash},
+    iter::FusedIterator,
+    ops::Range,
+};
+
+use super::{
+    id::IdArray,
+    list::{ConstSizeListArray, ListArray, OptionListArray},
+    option::OptionArray,
+    primitive::PrimitiveArray,
+    Array,
+};
+use crate::{primitive::Primitive, scalar::ScalarRef};
+
+#[derive(Debug)]
+pub struct Iter<'a, A> {
+    array: &'a A,
+    range: Range<usize>,
+}
+
+impl<'a, A: Array> Iter<'a, A> {
+    #[inline]
+    pub fn new(array: &'a A) -> Self {
+        Self {
+            array,
+            range: 0..array.len(),
+        }
+    }
+}
+
+impl<A> Clone for Iter<'_, A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            array: self.array,
+            range: self.range.clone(),
+        }
+    }
+}
+
+impl<'a, A: Array> Iterator for Iter<'a, A> {
+    type Item = A::ItemRef<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.range.next()?;
+        Some(unsafe { self.array.get_unchecked(offset) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let offset = self.range.nth(n)?;
+        Some(unsafe { self.array.get_unchecked(offset) })
+    }
+}
+
+impl<A: Array> DoubleEndedIterator for Iter<'_, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let offset = self.range.next_back()?;
+        Some(unsafe { self.array.get_unchecked(offset) })
+    }
+}
+
+impl<A: Array> ExactSizeIterator for Iter<'_, A> {}
+
+impl<A: Array> FusedIterator for Iter<'_, A> {}
+
+/// Owned items are copied out of the array, which is dropped with the iterator.
+#[derive(Debug, Clone)]
+pub struct IntoIter<A> {
+    array: A,
+    range: Range<usize>,
+}
+
+impl<A: Array> IntoIter<A> {
+    #[inline]
+    pub fn new(array: A) -> Self {
+        Self {
+            range: 0..array.len(),
+            array,
+        }
+    }
+}
+
+impl<A: Array> Iterator for IntoIter<A> {
+    type Item = A::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.range.next()?;
+        Some(unsafe { self.array.get_unchecked(offset) }.into_owned())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let offset = self.range.nth(n)?;
+        Some(unsafe { self.array.get_unchecked(offset) }.into_owned())
+    }
+}
+
+impl<A: Array> DoubleEndedIterator for IntoIter<A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let offset = self.range.next_back()?;
+        Some(unsafe { self.array.get_unchecked(offset) }.into_owned())
+    }
+}
+
+impl<A: Array> ExactSizeIterator for IntoIter<A> {}
+
+impl<A: Array> FusedIterator for IntoIter<A> {}
+
+/// `IntoIterator` for the array and a reference to it, and `Extend` with its items.
+macro_rules! array_iter {
+    (impl<$($G:ident $(: $B:path)?),*> $array:ty $(where $($W:tt)+)?) => {
+        impl<$($G $(: $B)?),*> IntoIterator for $array $(where $($W)+)? {
+            type Item = <$array as Array>::Item;
+            type IntoIter = IntoIter<Self>;
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                IntoIter::new(self)
+            }
+        }
+
+        impl<'a, $($G $(: $B)?),*> IntoIterator for &'a $array $(where $($W)+)? {
+            type Item = <$array as Array>::ItemRef<'a>;
+            type IntoIter = Iter<'a, $array>;
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                Iter::new(self)
+            }
+        }
+
+        impl<$($G $(: $B)?),*> Extend<<$array as Array>::Item> for $array $(where $($W)+)? {
+            #[inline]
+            fn extend<T: IntoIterator<Item = <$array as Array>::Item>>(&mut self, items: T) {
+                for item in items {
+                    self.push(item);
+                }
+            }
+        }
+    };
+}
+
+array_iter!(impl<P: Primitive> PrimitiveArray<P>);
+array_iter!(impl<P: Primitive> ListArray<P>);
+array_iter!(impl<P: Primitive> OptionListArray<P>);
+array_iter!(impl<A: Array> OptionArray<A>);
+array_iter!(impl<A: Array, S> IdArray<A, S> where
+    S: BuildHasher + 'static,
+    for<'x, 'y> A::ItemRef<'x>: PartialEq<A::ItemRef<'y>>,
+    for<'x> A::ItemRef<'x>: Hash,
+);
+
+impl<P: Primitive, const SIZE: usize> IntoIterator for ConstSizeListArray<P, SIZE> {
+    type Item = [P; SIZE];
+    type IntoIter = IntoIter<Self>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self)
+    }
+}
+
+impl<'a, P: Primitive, const SIZE: usize> IntoIterator for &'a ConstSizeListArray<P, SIZE> {
+    type Item = &'a [P; SIZE];
+    type IntoIter = Iter<'a, ConstSizeListArray<P, SIZE>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        Iter::new(self)
+    }
+}
+
+impl<P: Primitive, const SIZE: usize> Extend<[P; SIZE]> for ConstSizeListArray<P, SIZE> {
+    #[inline]
+    fn extend<T: IntoIterator<Item = [P; SIZE]>>(&mut self, items: T) {
+        for item in items {
+            self.push(item);
+        }
+    }
+}
+
+impl<P: Primitive> FromIterator<P> for PrimitiveArray<P> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = P>>(items: T) -> Self {
+        Self {
+            data: items.into_iter().collect(),
+        }
+    }
+}
+
+impl<P: Primitive> FromIterator<Vec<P>> for ListArray<P> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = Vec<P>>>(items: T) -> Self {
+        let mut array = Self::new();
+        array.extend(items);
+        array
+    }
+}
+
+impl<P: Primitive, const SIZE: usize> FromIterator<[P; SIZE]> for ConstSizeListArray<P, SIZE> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = [P; SIZE]>>(items: T) -> Self {
+        Self {
+            data: items.into_iter().flatten().collect(),
+        }
+    }
+}
+
+impl<P: Primitive> FromIterator<<Self as Array>::Item> for OptionListArray<P> {
+    /// The list size is taken from the first item; with no items it is zero.
+    fn from_iter<T: IntoIterator<Item = <Self as Array>::Item>>(items: T) -> Self {
+        let mut items = items.into_iter().peekable();
+        let list_size = items.peek().map_or(0, |item| item.data.len());
+        let mut array = Self::with_capacity(items.size_hint().0, list_size);
+        array.extend(items);
+        array
+    }
+}
+
+impl<A: Array + Default> FromIterator<<Self as Array>::Item> for OptionArray<A> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = <Self as Array>::Item>>(items: T) -> Self {
+        let mut array = Self::default();
+        array.extend(items);
+        array
+    }
+}
+
+impl<A: Array + Default, S: BuildHasher + Default + 'static> FromIterator<<Self as Array>::Item>
+    for IdArray<A, S>
+where
+    for<'x, 'y> A::ItemRef<'x>: PartialEq<A::ItemRef<'y>>,
+    for<'x> A::ItemRef<'x>: Hash,
+{
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = <Self as Array>::Item>>(items: T) -> Self {
+        let items = items.into_iter();
+        let mut array = Self::default();
+        array.data.reserve(items.size_hint().0);
+        array.extend(items);
+        array
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{
+            id::IdArray,
+            list::{ConstSizeListArray, ListArray, OptionListArray},
+            primitive::PrimitiveArray,
+            Array,
+        },
+        scalar::list::OptionList,
+    };
+
+    #[test]
+    fn borrow_and_consume() {
+        let array = (1..=4u16).collect::<PrimitiveArray<_>>();
+        assert_eq!(
+            array.iter().rev().copied().collect::<Vec<_>>(),
+            [4, 3, 2, 1]
+        );
+        assert_eq!(array.iter().len(), 4);
+        assert_eq!(array.iter().nth(2), Some(&3));
+
+        let lists = [vec![1u8], vec![], vec![2, 3]]
+            .into_iter()
+            .collect::<ListArray<_>>();
+        let lengths = (&lists).into_iter().map(<[u8]>::len).collect::<Vec<_>>();
+        assert_eq!(lengths, [1, 0, 2]);
+        let mut owned = lists.into_iter();
+        assert_eq!(owned.next_back(), Some(vec![2, 3]));
+        assert_eq!(owned.collect::<Vec<_>>(), [vec![1], vec![]]);
+
+        let pairs = [[1i8, 2], [3, 4]]
+            .into_iter()
+            .collect::<ConstSizeListArray<_, 2>>();
+        assert_eq!(pairs.iter().map(|pair| pair[1]).sum::<i8>(), 6);
+    }
+
+    #[test]
+    fn collect_and_extend() {
+        let mut ids = [Some(b"a".to_vec()), None, Some(b"a".to_vec())]
+            .into_iter()
+            .collect::<IdArray<ListArray<u8>>>();
+        ids.extend([Some(b"b".to_vec())]);
+        assert_eq!(ids.values.data.len(), 2);
+        assert_eq!(
+            ids.iter().collect::<Vec<_>>(),
+            [Some(&b"a"[..]), None, Some(&b"a"[..]), Some(&b"b"[..])]
+        );
+
+        let lists = [
+            OptionList::from([Some(1u32), None]),
+            OptionList::from([None, Some(2)]),
+        ]
+        .into_iter()
+        .collect::<OptionListArray<_>>();
+        assert_eq!(lists.list_size(), 2);
+        assert_eq!(lists.len(), 2);
+        let flat = lists
+            .iter()
+            .flat_map(|list| list.iter().flatten().copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(flat, [1, 2]);
+    }
+}
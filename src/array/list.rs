@@ -7,9 +7,9 @@ use crate::{
 
 #[derive(Debug, Clone)]
 pub struct OptionListArray<P> {
-    validity: BitVec,
-    data: Vec<P>,
-    list_size: usize,
+    pub(crate) validity: BitVec,
+    pub(crate) data: Vec<P>,
+    pub(crate) list_size: usize,
 }
 
 impl<P> OptionListArray<P> {
@@ -30,6 +30,23 @@ impl<P> OptionListArray<P> {
             list_size,
         }
     }
+
+    #[inline]
+    pub fn list_size(&self) -> usize {
+        self.list_size
+    }
+}
+
+impl<P: Primitive> OptionListArray<P> {
+    /// Returns `None` if `SIZE` differs from the list size or any element is null.
+    pub fn to_const_size<const SIZE: usize>(&self) -> Option<ConstSizeListArray<P, SIZE>> {
+        if SIZE != self.list_size || self.validity.as_slice().into_iter().any(|v| !v) {
+            return None;
+        }
+        Some(ConstSizeListArray {
+            data: self.data.clone(),
+        })
+    }
 }
 
 impl<P: Primitive> Array for OptionListArray<P> {
@@ -54,12 +71,13 @@ impl<P: Primitive> Array for OptionListArray<P> {
         for _ in 0..self.list_size {
             self.validity.push(false);
         }
-        self.data.resize_with(self.list_size, Default::default);
+        self.data
+            .resize_with(self.data.len() + self.list_size, Default::default);
     }
 
     #[inline]
     fn get(&self, id: usize) -> Option<Self::ItemRef<'_>> {
-        if id * self.list_size > self.data.len() {
+        if (id + 1) * self.list_size > self.data.len() {
             None
         } else {
             Some(unsafe { self.get_unchecked(id) })
@@ -80,7 +98,7 @@ impl<P: Primitive> Array for OptionListArray<P> {
 
     #[inline]
     fn get_mut(&mut self, offset: usize) -> Option<Self::ItemMut<'_>> {
-        if offset * self.list_size > self.data.len() {
+        if (offset + 1) * self.list_size > self.data.len() {
             None
         } else {
             Some(unsafe { self.get_unchecked_mut(offset) })
@@ -101,14 +119,14 @@ impl<P: Primitive> Array for OptionListArray<P> {
 
     #[inline]
     fn len(&self) -> usize {
-        self.data.len() / self.list_size
+        self.data.len().checked_div(self.list_size).unwrap_or(0)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct ListArray<P> {
-    data: Vec<P>,
-    offsets: Vec<usize>,
+    pub(crate) data: Vec<P>,
+    pub(crate) offsets: Vec<usize>,
 }
 
 impl<P> Default for ListArray<P> {
@@ -128,6 +146,22 @@ impl<P> ListArray<P> {
     }
 }
 
+impl<P: Primitive> ListArray<P> {
+    /// Returns `None` if any list is not exactly `SIZE` long.
+    pub fn to_const_size<const SIZE: usize>(&self) -> Option<ConstSizeListArray<P, SIZE>> {
+        let mut start = 0;
+        for &end in &self.offsets {
+            if end - start != SIZE {
+                return None;
+            }
+            start = end;
+        }
+        Some(ConstSizeListArray {
+            data: self.data.clone(),
+        })
+    }
+}
+
 impl<P: Primitive> Array for ListArray<P> {
     type Item = Vec<P>;
 
@@ -198,7 +232,25 @@ impl<P: Primitive> Array for ListArray<P> {
 
 #[derive(Default, Debug, Clone)]
 pub struct ConstSizeListArray<P, const SIZE: usize> {
-    data: Vec<P>,
+    pub(crate) data: Vec<P>,
+}
+
+impl<P: Primitive, const SIZE: usize> ConstSizeListArray<P, SIZE> {
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Every element of the result is valid.
+    pub fn to_option_list(&self) -> OptionListArray<P> {
+        let mut validity = BitVec::with_capacity(self.data.len());
+        validity.extend(self.data.iter().map(|_| true));
+        OptionListArray {
+            validity,
+            data: self.data.clone(),
+            list_size: SIZE,
+        }
+    }
 }
 
 impl<P: Primitive, const SIZE: usize> Array for ConstSizeListArray<P, SIZE> {
@@ -228,8 +280,8 @@ impl<P: Primitive, const SIZE: usize> Array for ConstSizeListArray<P, SIZE> {
     unsafe fn get_unchecked(&self, id: usize) -> Self::ItemRef<'_> {
         self.data
             .get_unchecked(id * SIZE..(id + 1) * SIZE)
-            .split_array_ref::<SIZE>()
-            .0
+            .first_chunk::<SIZE>()
+            .unwrap_unchecked()
     }
 
     #[inline]
@@ -238,19 +290,15 @@ impl<P: Primitive, const SIZE: usize> Array for ConstSizeListArray<P, SIZE> {
             return None;
         }
 
-        Some(
-            self.data[id * SIZE..(id + 1) * SIZE]
-                .split_array_mut::<SIZE>()
-                .0,
-        )
+        self.data[id * SIZE..(id + 1) * SIZE].first_chunk_mut::<SIZE>()
     }
 
     #[inline]
     unsafe fn get_unchecked_mut(&mut self, id: usize) -> Self::ItemMut<'_> {
         self.data
             .get_unchecked_mut(id * SIZE..(id + 1) * SIZE)
-            .split_array_mut::<SIZE>()
-            .0
+            .first_chunk_mut::<SIZE>()
+            .unwrap_unchecked()
     }
 
     #[inline]
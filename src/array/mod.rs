@@ -1,9 +1,33 @@
+pub mod builder;
+pub mod chunked;
+pub mod concurrent;
+pub mod dynamic;
+pub mod frozen;
 pub mod id;
+pub mod idvec;
+pub mod iter;
 pub mod list;
+pub mod mutate;
+pub mod option;
 pub mod primitive;
 pub mod slotmap;
 
+pub use builder::{
+    ArrayBuilder, ConstSizeListBuilder, IdArrayBuilder, ListBuilder, OptionBuilder,
+    OptionListBuilder, PrimitiveBuilder,
+};
+pub use chunked::{ChunkedArray, ChunkedIdArray};
+pub use concurrent::{ConcurrentSlotMap, IdBuilder};
+pub use dynamic::{DynArray, TypedArray};
+pub use frozen::{
+    FrozenIdArray, FrozenIdVec, FrozenListArray, FrozenOptionListArray, FrozenPrimitiveArray,
+};
+pub use idvec::{IdIter, IdVec, IdWidth};
+pub use iter::{IntoIter, Iter};
 pub use list::OptionListArray;
+pub use mutate::ArrayMut;
+pub use option::OptionArray;
+pub use slotmap::{seeded_state, SlotMap};
 
 use crate::scalar::{Scalar, ScalarMut, ScalarRef};
 
@@ -41,6 +65,11 @@ pub trait Array: 'static + Sized {
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    #[inline]
+    fn iter(&self) -> Iter<'_, Self> {
+        Iter::new(self)
+    }
 }
 
 #[cfg(test)]
@@ -48,6 +77,7 @@ mod tests {
     use super::{
         id::IdArray,
         list::{ListArray, OptionListArray},
+        option::OptionArray,
         primitive::PrimitiveArray,
         Array,
     };
@@ -92,6 +122,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn option_array() {
+        let mut array = OptionArray::new(ListArray::<u8>::new());
+        array.push(Some(Vec::from("foo")));
+        array.push(None);
+        array.push(Some(Vec::new()));
+        assert_eq!(array.len(), 3);
+        assert_eq!(array.get(0), Some(Some("foo".as_ref())));
+        assert_eq!(array.get(1), Some(None));
+        assert_eq!(array.get(2), Some(Some("".as_ref())));
+        assert_eq!(array.get(3), None);
+        assert_eq!(array.null_count(), 1);
+    }
+
     #[test]
     fn primitive_array() {
         let mut array = PrimitiveArray::new();
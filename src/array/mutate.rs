@@ -0,0 +1,559 @@
+//! Positional mutation of arrays.
+//!
+//! Offsets out of bounds panic, as they do for `Vec`.
+
+use std::hash::{BuildHasher, Hash};
+
+use super::{
+    id::IdArray,
+    list::{ConstSizeListArray, ListArray, OptionListArray},
+    option::OptionArray,
+    primitive::PrimitiveArray,
+    Array,
+};
+use crate::{
+    bitvec::BitVec,
+    primitive::Primitive,
+    scalar::{list::OptionList, ScalarRef},
+};
+
+#[inline]
+#[track_caller]
+pub(crate) fn check(offset: usize, len: usize) {
+    assert!(
+        offset < len,
+        "offset {offset} out of bounds for length {len}"
+    );
+}
+
+#[inline]
+#[track_caller]
+pub(crate) fn check_insert(offset: usize, len: usize) {
+    assert!(
+        offset <= len,
+        "insert offset {offset} out of bounds for length {len}"
+    );
+}
+
+pub trait ArrayMut: Array {
+    /// Replaces the item at `offset`.
+    fn set(&mut self, offset: usize, item: Self::Item);
+
+    fn remove(&mut self, offset: usize) -> Self::Item;
+
+    /// Shifts the items from `offset` on back by one.
+    fn insert(&mut self, offset: usize, item: Self::Item);
+
+    /// Inserts the item [`Array::push_zero`] would push.
+    fn insert_zero(&mut self, offset: usize);
+
+    fn truncate(&mut self, len: usize);
+
+    /// Keeps the items for which `keep` is true, visiting each once in order.
+    fn retain<F>(&mut self, keep: F)
+    where
+        F: for<'a> FnMut(Self::ItemRef<'a>) -> bool;
+
+    fn swap(&mut self, a: usize, b: usize) {
+        check(a.max(b), self.len());
+        if a != b {
+            let first = unsafe { self.get_unchecked(a) }.into_owned();
+            let second = unsafe { self.get_unchecked(b) }.into_owned();
+            self.set(a, second);
+            self.set(b, first);
+        }
+    }
+
+    /// Removes the item at `offset`, moving the last item into its place.
+    fn swap_remove(&mut self, offset: usize) -> Self::Item {
+        check(offset, self.len());
+        let last = self.len() - 1;
+        self.swap(offset, last);
+        self.remove(last)
+    }
+
+    fn pop(&mut self) -> Option<Self::Item> {
+        let len = self.len();
+        (len > 0).then(|| self.remove(len - 1))
+    }
+
+    fn clear(&mut self) {
+        self.truncate(0);
+    }
+}
+
+impl<P: Primitive> ArrayMut for PrimitiveArray<P> {
+    #[inline]
+    fn set(&mut self, offset: usize, item: P) {
+        check(offset, self.data.len());
+        self.data[offset] = item;
+    }
+
+    #[inline]
+    fn remove(&mut self, offset: usize) -> P {
+        check(offset, self.data.len());
+        self.data.remove(offset)
+    }
+
+    #[inline]
+    fn insert(&mut self, offset: usize, item: P) {
+        check_insert(offset, self.data.len());
+        self.data.insert(offset, item);
+    }
+
+    #[inline]
+    fn insert_zero(&mut self, offset: usize) {
+        self.insert(offset, P::default());
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.data.truncate(len);
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, mut keep: F)
+    where
+        F: for<'a> FnMut(&'a P) -> bool,
+    {
+        self.data.retain(|value| keep(value));
+    }
+
+    #[inline]
+    fn swap(&mut self, a: usize, b: usize) {
+        self.data.swap(a, b);
+    }
+
+    #[inline]
+    fn swap_remove(&mut self, offset: usize) -> P {
+        check(offset, self.data.len());
+        self.data.swap_remove(offset)
+    }
+}
+
+impl<P: Primitive> ListArray<P> {
+    #[inline]
+    fn bounds(&self, offset: usize) -> (usize, usize) {
+        let start = offset.checked_sub(1).map_or(0, |prev| self.offsets[prev]);
+        (start, self.offsets[offset])
+    }
+
+    /// Moves the ends of the lists from `offset` on by `delta` elements.
+    #[inline]
+    fn shift_offsets(&mut self, offset: usize, delta: isize) {
+        for end in &mut self.offsets[offset..] {
+            *end = end.wrapping_add_signed(delta);
+        }
+    }
+}
+
+impl<P: Primitive> ArrayMut for ListArray<P> {
+    fn set(&mut self, offset: usize, item: Vec<P>) {
+        check(offset, self.offsets.len());
+        let (start, end) = self.bounds(offset);
+        let delta = item.len() as isize - (end - start) as isize;
+        self.data.splice(start..end, item);
+        self.shift_offsets(offset, delta);
+    }
+
+    fn remove(&mut self, offset: usize) -> Vec<P> {
+        check(offset, self.offsets.len());
+        let (start, end) = self.bounds(offset);
+        let item = self.data.drain(start..end).collect::<Vec<_>>();
+        self.offsets.remove(offset);
+        self.shift_offsets(offset, -(item.len() as isize));
+        item
+    }
+
+    fn insert(&mut self, offset: usize, item: Vec<P>) {
+        check_insert(offset, self.offsets.len());
+        let start = offset.checked_sub(1).map_or(0, |prev| self.offsets[prev]);
+        let len = item.len();
+        self.data.splice(start..start, item);
+        self.offsets.insert(offset, start);
+        self.shift_offsets(offset, len as isize);
+    }
+
+    #[inline]
+    fn insert_zero(&mut self, offset: usize) {
+        self.insert(offset, Vec::new());
+    }
+
+    fn truncate(&mut self, len: usize) {
+        if len < self.offsets.len() {
+            let end = len.checked_sub(1).map_or(0, |last| self.offsets[last]);
+            self.data.truncate(end);
+            self.offsets.truncate(len);
+        }
+    }
+
+    fn retain<F>(&mut self, mut keep: F)
+    where
+        F: for<'a> FnMut(&'a [P]) -> bool,
+    {
+        let (mut start, mut write, mut kept) = (0, 0, 0);
+        for row in 0..self.offsets.len() {
+            let end = self.offsets[row];
+            if keep(&self.data[start..end]) {
+                self.data.copy_within(start..end, write);
+                write += end - start;
+                self.offsets[kept] = write;
+                kept += 1;
+            }
+            start = end;
+        }
+        self.data.truncate(write);
+        self.offsets.truncate(kept);
+    }
+}
+
+impl<P: Primitive, const SIZE: usize> ArrayMut for ConstSizeListArray<P, SIZE> {
+    #[inline]
+    fn set(&mut self, offset: usize, item: [P; SIZE]) {
+        check(offset, self.len());
+        self.data[offset * SIZE..(offset + 1) * SIZE].copy_from_slice(&item);
+    }
+
+    #[inline]
+    fn remove(&mut self, offset: usize) -> [P; SIZE] {
+        check(offset, self.len());
+        let item = *self.get(offset).unwrap();
+        self.data.drain(offset * SIZE..(offset + 1) * SIZE);
+        item
+    }
+
+    #[inline]
+    fn insert(&mut self, offset: usize, item: [P; SIZE]) {
+        check_insert(offset, self.len());
+        self.data.splice(offset * SIZE..offset * SIZE, item);
+    }
+
+    #[inline]
+    fn insert_zero(&mut self, offset: usize) {
+        self.insert(offset, [P::default(); SIZE]);
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.data.truncate(len * SIZE);
+    }
+
+    fn retain<F>(&mut self, mut keep: F)
+    where
+        F: for<'a> FnMut(&'a [P; SIZE]) -> bool,
+    {
+        let mut kept = 0;
+        for row in 0..self.len() {
+            if keep(unsafe { self.get_unchecked(row) }) {
+                self.data
+                    .copy_within(row * SIZE..(row + 1) * SIZE, kept * SIZE);
+                kept += 1;
+            }
+        }
+        self.truncate(kept);
+    }
+
+    #[inline]
+    fn swap(&mut self, a: usize, b: usize) {
+        check(a.max(b), self.len());
+        for i in 0..SIZE {
+            self.data.swap(a * SIZE + i, b * SIZE + i);
+        }
+    }
+}
+
+impl<P: Primitive> ArrayMut for OptionListArray<P> {
+    fn set(&mut self, offset: usize, item: OptionList<P>) {
+        check(offset, self.len());
+        assert_eq!(item.data.len(), self.list_size, "list size mismatch");
+        let range = offset * self.list_size..(offset + 1) * self.list_size;
+        self.validity.splice(range.clone(), item.validity);
+        self.data[range].copy_from_slice(&item.data);
+    }
+
+    fn remove(&mut self, offset: usize) -> OptionList<P> {
+        check(offset, self.len());
+        let item = self.get(offset).unwrap().into_owned();
+        let range = offset * self.list_size..(offset + 1) * self.list_size;
+        self.validity.splice(range.clone(), []);
+        self.data.drain(range);
+        item
+    }
+
+    fn insert(&mut self, offset: usize, item: OptionList<P>) {
+        check_insert(offset, self.len());
+        assert_eq!(item.data.len(), self.list_size, "list size mismatch");
+        let start = offset * self.list_size;
+        self.validity.splice(start..start, item.validity);
+        self.data.splice(start..start, item.data);
+    }
+
+    #[inline]
+    fn insert_zero(&mut self, offset: usize) {
+        self.insert(offset, OptionList::from(vec![None; self.list_size]));
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.validity.truncate(len * self.list_size);
+        self.data.truncate(len * self.list_size);
+    }
+
+    fn retain<F>(&mut self, mut keep: F)
+    where
+        F: for<'a> FnMut(<Self as Array>::ItemRef<'a>) -> bool,
+    {
+        let size = self.list_size;
+        let mut validity = BitVec::with_capacity(self.validity.len().div_ceil(8));
+        let mut kept = 0;
+        for row in 0..self.len() {
+            let item = unsafe { self.get_unchecked(row) };
+            if keep(item.clone()) {
+                validity.extend(item.validity);
+                self.data
+                    .copy_within(row * size..(row + 1) * size, kept * size);
+                kept += 1;
+            }
+        }
+        self.validity = validity;
+        self.data.truncate(kept * size);
+    }
+}
+
+impl<A: ArrayMut> ArrayMut for OptionArray<A> {
+    /// A null keeps the previous value behind the cleared validity bit.
+    #[inline]
+    fn set(&mut self, offset: usize, item: Option<A::Item>) {
+        check(offset, self.len());
+        self.validity.set(offset, item.is_some());
+        if let Some(item) = item {
+            self.data.set(offset, item);
+        }
+    }
+
+    #[inline]
+    fn remove(&mut self, offset: usize) -> Option<A::Item> {
+        check(offset, self.len());
+        let valid = self.is_valid(offset);
+        self.validity.splice(offset..offset + 1, []);
+        let item = self.data.remove(offset);
+        valid.then_some(item)
+    }
+
+    #[inline]
+    fn insert(&mut self, offset: usize, item: Option<A::Item>) {
+        check_insert(offset, self.len());
+        self.validity.splice(offset..offset, [item.is_some()]);
+        match item {
+            Some(item) => self.data.insert(offset, item),
+            None => self.data.insert_zero(offset),
+        }
+    }
+
+    #[inline]
+    fn insert_zero(&mut self, offset: usize) {
+        self.insert(offset, None);
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.validity.truncate(len);
+        self.data.truncate(len);
+    }
+
+    fn retain<F>(&mut self, mut keep: F)
+    where
+        F: for<'a> FnMut(Option<A::ItemRef<'a>>) -> bool,
+    {
+        let mask = (0..self.len())
+            .map(|row| keep(unsafe { self.get_unchecked(row) }))
+            .collect::<Vec<_>>();
+        self.validity = self
+            .validity
+            .as_slice()
+            .into_iter()
+            .zip(&mask)
+            .filter_map(|(valid, &kept)| kept.then_some(valid))
+            .collect();
+        let mut mask = mask.into_iter();
+        self.data.retain(|_| mask.next().unwrap());
+    }
+
+    #[inline]
+    fn swap(&mut self, a: usize, b: usize) {
+        check(a.max(b), self.len());
+        let (valid_a, valid_b) = (self.is_valid(a), self.is_valid(b));
+        self.validity.set(a, valid_b);
+        self.validity.set(b, valid_a);
+        self.data.swap(a, b);
+    }
+}
+
+impl<A: Array, S: BuildHasher + 'static> ArrayMut for IdArray<A, S>
+where
+    for<'a, 'b> A::ItemRef<'a>: PartialEq<A::ItemRef<'b>>,
+    for<'a> A::ItemRef<'a>: Hash,
+{
+    /// The new value is interned; the old one stays in the dictionary until
+    /// [`IdArray::compact`].
+    #[inline]
+    fn set(&mut self, offset: usize, item: Option<A::Item>) {
+        check(offset, self.data.len());
+        let (id, new) = item.map_or((0, false), |item| self.intern(item));
+        self.release(self.data.get(offset).unwrap());
+        self.acquire(id);
+        self.data.set(offset, id);
+        if new {
+            self.interned();
+        }
+    }
+
+    #[inline]
+    fn remove(&mut self, offset: usize) -> Option<A::Item> {
+        check(offset, self.data.len());
+        let id = self.data.remove(offset);
+        self.release(id);
+        unsafe { self.values.get_unchecked(id) }.map(ScalarRef::into_owned)
+    }
+
+    #[inline]
+    fn insert(&mut self, offset: usize, item: Option<A::Item>) {
+        check_insert(offset, self.data.len());
+        let (id, new) = item.map_or((0, false), |item| self.intern(item));
+        self.acquire(id);
+        self.data.insert(offset, id);
+        if new {
+            self.interned();
+        }
+    }
+
+    #[inline]
+    fn insert_zero(&mut self, offset: usize) {
+        self.insert(offset, None);
+    }
+
+    fn truncate(&mut self, len: usize) {
+        if let Some(counts) = &mut self.ref_counts {
+            for id in self.data.iter().skip(len) {
+                counts[id] -= 1;
+            }
+        }
+        self.data.truncate(len);
+    }
+
+    fn retain<F>(&mut self, mut keep: F)
+    where
+        F: for<'a> FnMut(Option<A::ItemRef<'a>>) -> bool,
+    {
+        let (values, counts) = (&self.values, &mut self.ref_counts);
+        self.data.retain(|id| {
+            let kept = keep(unsafe { values.get_unchecked(id) });
+            if let (false, Some(counts)) = (kept, counts.as_mut()) {
+                counts[id] -= 1;
+            }
+            kept
+        });
+    }
+
+    #[inline]
+    fn swap(&mut self, a: usize, b: usize) {
+        self.data.swap(a, b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArrayMut;
+    use crate::{
+        array::{
+            id::IdArray,
+            list::{ConstSizeListArray, ListArray, OptionListArray},
+            option::OptionArray,
+            primitive::PrimitiveArray,
+            Array,
+        },
+        scalar::list::OptionList,
+    };
+
+    #[test]
+    fn primitive_and_const_size() {
+        let mut array = (0..6u8).collect::<PrimitiveArray<_>>();
+        array.set(0, 9);
+        array.insert(1, 7);
+        assert_eq!(array.swap_remove(2), 1);
+        array.retain(|value| value % 2 == 1);
+        assert_eq!(array.as_slice(), &[9, 7, 5, 3]);
+        assert_eq!(array.pop(), Some(3));
+
+        let mut pairs = [[1u16, 2], [3, 4], [5, 6]]
+            .into_iter()
+            .collect::<ConstSizeListArray<_, 2>>();
+        pairs.swap(0, 2);
+        assert_eq!(pairs.remove(1), [3, 4]);
+        pairs.insert_zero(0);
+        pairs.retain(|pair| pair[0] != 1);
+        assert_eq!(pairs.iter().collect::<Vec<_>>(), [&[0, 0], &[5, 6]]);
+    }
+
+    #[test]
+    fn lists_shift_offsets() {
+        let mut lists = [vec![1u32], vec![2, 3], vec![], vec![4, 5, 6]]
+            .into_iter()
+            .collect::<ListArray<_>>();
+        lists.set(1, vec![7, 8, 9, 10]);
+        lists.insert(2, vec![11]);
+        assert_eq!(lists.remove(0), vec![1]);
+        lists.swap(0, 3);
+        assert_eq!(
+            lists.iter().collect::<Vec<_>>(),
+            [&[4, 5, 6][..], &[11], &[], &[7, 8, 9, 10]]
+        );
+        lists.retain(|list| !list.is_empty());
+        assert_eq!(lists.get(2), Some(&[7, 8, 9, 10][..]));
+        lists.truncate(1);
+        assert_eq!(lists.len(), 1);
+        assert_eq!(lists.data, vec![4, 5, 6]);
+
+        let mut nullable = [
+            OptionList::from([Some(1i8), None]),
+            OptionList::from([None, Some(2)]),
+        ]
+        .into_iter()
+        .collect::<OptionListArray<_>>();
+        nullable.insert_zero(1);
+        nullable.set(0, OptionList::from([None, Some(3)]));
+        nullable.retain(|list| list.get(1) != Some(None));
+        assert_eq!(nullable.len(), 2);
+        assert_eq!(nullable.get(1).unwrap().get(1), Some(Some(&2)));
+        assert_eq!(nullable.remove(0).get(1), Some(&3));
+    }
+
+    #[test]
+    fn options_and_ids() {
+        let mut options = [Some(1u64), None, Some(3)]
+            .into_iter()
+            .collect::<OptionArray<PrimitiveArray<_>>>();
+        options.insert(0, None);
+        options.swap(0, 3);
+        options.set(2, Some(4));
+        options.retain(|value| value != Some(&1));
+        assert_eq!(
+            options.iter().collect::<Vec<_>>(),
+            [Some(&3), Some(&4), None]
+        );
+        assert_eq!(options.remove(2), None);
+
+        let mut ids = [Some(vec![1u8]), None, Some(vec![2])]
+            .into_iter()
+            .collect::<IdArray<ListArray<u8>>>();
+        ids.set(1, Some(vec![2]));
+        assert_eq!(ids.data.get(1), ids.data.get(2));
+        ids.insert(0, Some(vec![1]));
+        assert_eq!(ids.data.get(0), ids.data.get(1));
+        assert_eq!(ids.swap_remove(0), Some(vec![1]));
+        ids.retain(|value| value != Some(&[1][..]));
+        assert_eq!(ids.len(), 2);
+        ids.clear();
+        assert!(ids.is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+use super::Array;
+use crate::bitvec::{BitSlice, BitVec};
+
+#[derive(Debug, Clone)]
+pub struct OptionArray<A> {
+    pub(crate) validity: BitVec,
+    pub(crate) data: A,
+}
+
+impl<A: Array> OptionArray<A> {
+    #[inline]
+    pub fn new(array: A) -> Self {
+        Self {
+            validity: BitVec::with_capacity(array.len()),
+            data: array,
+        }
+    }
+
+    #[inline]
+    pub fn validity(&self) -> BitSlice<'_> {
+        self.validity.as_slice()
+    }
+
+    #[inline]
+    pub fn values(&self) -> &A {
+        &self.data
+    }
+
+    #[inline]
+    pub fn is_valid(&self, offset: usize) -> bool {
+        self.validity.get(offset).unwrap_or(false)
+    }
+
+    #[inline]
+    pub fn null_count(&self) -> usize {
+        (0..self.len()).filter(|&i| !self.is_valid(i)).count()
+    }
+}
+
+impl<A: Array + Default> Default for OptionArray<A> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(A::default())
+    }
+}
+
+impl<A: Array> Array for OptionArray<A> {
+    type Item = Option<A::Item>;
+
+    type ItemRef<'s> = Option<A::ItemRef<'s>>
+    where
+        Self: 's;
+
+    type ItemMut<'s> = Option<A::ItemMut<'s>>
+    where
+        Self: 's;
+
+    #[inline]
+    fn push(&mut self, item: Self::Item) {
+        match item {
+            Some(item) => {
+                self.validity.push(true);
+                self.data.push(item);
+            }
+            None => self.push_zero(),
+        }
+    }
+
+    #[inline]
+    fn push_zero(&mut self) {
+        self.validity.push(false);
+        self.data.push_zero();
+    }
+
+    #[inline]
+    fn get(&self, offset: usize) -> Option<Self::ItemRef<'_>> {
+        if self.len() <= offset {
+            None
+        } else {
+            Some(unsafe { self.get_unchecked(offset) })
+        }
+    }
+
+    #[inline]
+    unsafe fn get_unchecked(&self, offset: usize) -> Self::ItemRef<'_> {
+        if self.is_valid(offset) {
+            Some(self.data.get_unchecked(offset))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn get_mut(&mut self, offset: usize) -> Option<Self::ItemMut<'_>> {
+        if self.len() <= offset {
+            None
+        } else {
+            Some(unsafe { self.get_unchecked_mut(offset) })
+        }
+    }
+
+    #[inline]
+    unsafe fn get_unchecked_mut(&mut self, offset: usize) -> Self::ItemMut<'_> {
+        if self.is_valid(offset) {
+            Some(self.data.get_unchecked_mut(offset))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+}
@@ -3,7 +3,7 @@ use crate::primitive::Primitive;
 
 #[derive(Default, Debug, Clone)]
 pub struct PrimitiveArray<P> {
-    data: Vec<P>,
+    pub(crate) data: Vec<P>,
 }
 
 impl<P: Primitive> PrimitiveArray<P> {
@@ -11,6 +11,11 @@ impl<P: Primitive> PrimitiveArray<P> {
     pub fn new() -> Self {
         Default::default()
     }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[P] {
+        &self.data
+    }
 }
 
 impl<P: Primitive> Array for PrimitiveArray<P> {
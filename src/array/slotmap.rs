@@ -1,47 +1,87 @@
-use std::hash::Hash;
+use std::hash::{BuildHasher, Hash};
 
 use ahash::RandomState;
 use hashbrown::{hash_map::RawEntryMut, HashMap};
 
-use super::Array;
-use crate::scalar::Scalar;
+use super::{mutate::ArrayMut, Array};
+use crate::scalar::{Scalar, ScalarRef};
 
 #[inline]
-fn hash_with_state<H: Hash>(state: &RandomState, value: &H) -> u64 {
+fn hash_with_state<S: BuildHasher, H: Hash>(state: &S, value: &H) -> u64 {
     state.hash_one(value)
 }
 
+/// A hasher state that is the same in every run for the same `seed`.
+///
+/// Hashes agree across processes built against the same `ahash` version for the same target;
+/// hashes persisted for longer should come from a hasher of your own, see
+/// [`SlotMap::with_hasher`].
+pub fn seeded_state(seed: u64) -> RandomState {
+    RandomState::with_seeds(
+        seed,
+        seed ^ 0x9e37_79b9_7f4a_7c15,
+        seed.rotate_left(32),
+        !seed,
+    )
+}
+
+/// Interns values of `A`, handing out ids from `1`; `0` is null.
 #[derive(Debug, Clone)]
-pub struct SlotMap<A> {
-    hash_state: RandomState,
+pub struct SlotMap<A, S = RandomState> {
+    hash_state: S,
     dedup: HashMap<usize, (), ()>,
-    data: A,
+    pub(crate) data: A,
 }
 
 impl<A> SlotMap<A> {
+    /// Hashes with a random state, so hashes differ from run to run.
+    #[inline]
+    pub fn new(data: A) -> Self {
+        Self::with_hasher(data, RandomState::new())
+    }
+
+    /// Hashes deterministically; see [`seeded_state`].
+    #[inline]
+    pub fn with_seed(data: A, seed: u64) -> Self {
+        Self::with_hasher(data, seeded_state(seed))
+    }
+}
+
+impl<A, S> SlotMap<A, S> {
     #[inline]
-    pub(crate) fn new(data: A) -> Self {
+    pub fn with_hasher(data: A, hash_state: S) -> Self {
         Self {
-            hash_state: RandomState::new(),
+            hash_state,
             dedup: Default::default(),
             data,
         }
     }
+
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        &self.hash_state
+    }
+
+    /// The interned values; the value with id `n` is at offset `n - 1`.
+    #[inline]
+    pub fn values(&self) -> &A {
+        &self.data
+    }
 }
 
-impl<A: Array> SlotMap<A>
+impl<A: Array, S: BuildHasher> SlotMap<A, S>
 where
     for<'lhs, 'rhs> A::ItemRef<'lhs>: PartialEq<A::ItemRef<'rhs>>,
     for<'r> A::ItemRef<'r>: Hash,
 {
     #[inline]
-    pub(crate) fn lookup_or_insert(&mut self, value: A::Item) -> usize {
+    pub fn lookup_or_insert(&mut self, value: A::Item) -> usize {
         let hash = hash_with_state(&self.hash_state, &value.as_ref());
         let entry = self.dedup.raw_entry_mut().from_hash(hash, |key| {
             value.as_ref() == unsafe { self.data.get_unchecked(*key) }
         });
 
-        return match entry {
+        let index = match entry {
             RawEntryMut::Occupied(entry) => *entry.into_key(),
             RawEntryMut::Vacant(entry) => {
                 self.data.push(value);
@@ -52,23 +92,45 @@ where
                     })
                     .0
             }
-        } + 1;
+        };
+        index + 1
+    }
+
+    /// Like [`lookup_or_insert`](Self::lookup_or_insert), but copies `value` only if it is new.
+    pub(crate) fn lookup_or_insert_ref(&mut self, value: A::ItemRef<'_>) -> usize {
+        let hash = hash_with_state(&self.hash_state, &value);
+        let entry = self.dedup.raw_entry_mut().from_hash(
+            hash,
+            |key| unsafe { self.data.get_unchecked(*key) } == value,
+        );
+
+        let index = match entry {
+            RawEntryMut::Occupied(entry) => *entry.into_key(),
+            RawEntryMut::Vacant(entry) => {
+                self.data.push(value.into_owned());
+                *entry
+                    .insert_with_hasher(hash, self.data.len() - 1, (), |index| {
+                        let list = self.data.get(*index).unwrap();
+                        hash_with_state(&self.hash_state, &list)
+                    })
+                    .0
+            }
+        };
+        index + 1
     }
 
-    #[allow(unused)]
     #[inline]
-    pub(crate) fn lookup(&self, value: A::ItemRef<'_>) -> Option<usize> {
-        return self
-            .dedup
+    pub fn lookup(&self, value: A::ItemRef<'_>) -> Option<usize> {
+        self.dedup
             .raw_entry()
             .from_hash(hash_with_state(&self.hash_state, &value), |key| unsafe {
                 self.data.get_unchecked(*key) == value
             })
-            .map(|(&symbol, &())| symbol + 1);
+            .map(|(&symbol, &())| symbol + 1)
     }
 
     #[inline]
-    pub(crate) fn get(&self, id: usize) -> Option<Option<A::ItemRef<'_>>> {
+    pub fn get(&self, id: usize) -> Option<Option<A::ItemRef<'_>>> {
         if id == 0 {
             Some(None)
         } else {
@@ -104,10 +166,53 @@ where
     }
 }
 
+impl<A: ArrayMut, S: BuildHasher> SlotMap<A, S>
+where
+    for<'lhs, 'rhs> A::ItemRef<'lhs>: PartialEq<A::ItemRef<'rhs>>,
+    for<'r> A::ItemRef<'r>: Hash,
+{
+    /// Moves the last value to `offset`, shifting the values from there on up by one id.
+    pub(crate) fn move_last(&mut self, offset: usize) {
+        let last = self.data.len() - 1;
+        if offset >= last {
+            return;
+        }
+        let value = self.data.remove(last);
+        self.data.insert(offset, value);
+        // Re-key from the top down so no two entries share a key on the way.
+        self.rekey(offset, last, usize::MAX);
+        for index in (offset..last).rev() {
+            self.rekey(index + 1, index, index + 1);
+        }
+        self.rekey(offset, usize::MAX, offset);
+    }
+
+    /// Changes the key of the value at `index` from `from` to `to`.
+    fn rekey(&mut self, index: usize, from: usize, to: usize) {
+        let hash = hash_with_state(&self.hash_state, &self.data.get(index).unwrap());
+        if let RawEntryMut::Occupied(mut entry) = self
+            .dedup
+            .raw_entry_mut()
+            .from_hash(hash, |&key| key == from)
+        {
+            *entry.key_mut() = to;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::{collections::hash_map::DefaultHasher, hash::BuildHasherDefault};
+
     use super::SlotMap;
-    use crate::array::list::ListArray;
+    use crate::{
+        array::{
+            list::{ConstSizeListArray, ListArray},
+            primitive::PrimitiveArray,
+            Array,
+        },
+        primitive::bf16,
+    };
 
     #[test]
     fn get_and_lookup() {
@@ -123,4 +228,58 @@ mod tests {
         let id = dict.lookup("hello, world".as_ref());
         assert_eq!(id, Some(1));
     }
+
+    #[test]
+    fn wide_and_half_keys() {
+        let mut ids = SlotMap::<PrimitiveArray<u128>>::new(PrimitiveArray::new());
+        let id = ids.lookup_or_insert(u128::MAX - 7);
+        assert_eq!(ids.lookup_or_insert(1 << 100), id + 1);
+        assert_eq!(ids.lookup(&(u128::MAX - 7)), Some(id));
+
+        let vector = |values: [f32; 4]| values.map(bf16::from_f32);
+        let mut features =
+            SlotMap::<ConstSizeListArray<bf16, 4>>::new(ConstSizeListArray::default());
+        let id = features.lookup_or_insert(vector([0.5, -1.0, 0.0, 3.25]));
+        // Negative zero equals zero, so it must find the same entry.
+        assert_eq!(
+            features.lookup_or_insert(vector([0.5, -1.0, -0.0, 3.25])),
+            id
+        );
+        assert_ne!(features.lookup_or_insert(vector([0.5, -1.0, 0.0, 3.5])), id);
+    }
+
+    #[test]
+    fn seeded_and_custom_hashers() {
+        let value: &[u8] = b"snapshot";
+        let hash = |seed| {
+            SlotMap::<ListArray<u8>>::with_seed(ListArray::new(), seed)
+                .hasher()
+                .hash_one(value)
+        };
+        assert_eq!(hash(7), hash(7));
+        assert_ne!(hash(7), hash(8));
+
+        let mut ids = SlotMap::with_hasher(
+            PrimitiveArray::<u64>::new(),
+            BuildHasherDefault::<DefaultHasher>::default(),
+        );
+        assert_eq!(ids.lookup_or_insert(42), 1);
+        assert_eq!(ids.lookup_or_insert(7), 2);
+        assert_eq!(ids.lookup(&42), Some(1));
+        assert_eq!(ids.values().len(), 2);
+    }
+
+    #[test]
+    fn move_last() {
+        let mut ids = SlotMap::<PrimitiveArray<u32>>::new(PrimitiveArray::new());
+        for value in [10, 20, 30, 15] {
+            ids.lookup_or_insert(value);
+        }
+        ids.move_last(1);
+        assert_eq!(ids.values().as_slice(), [10, 15, 20, 30]);
+        for (id, value) in [(1, 10), (2, 15), (3, 20), (4, 30)] {
+            assert_eq!(ids.lookup(&value), Some(id));
+        }
+        assert_eq!(ids.lookup_or_insert(5), 5);
+    }
 }
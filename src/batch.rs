@@ -0,0 +1,268 @@
+//! Equal-length columns under a shared [`Schema`].
+
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+    array::{DynArray, TypedArray},
+    schema::{Field, Schema, SchemaError},
+};
+
+#[derive(Debug, Clone)]
+pub struct RecordBatch {
+    schema: Arc<Schema>,
+    columns: Vec<DynArray>,
+    rows: usize,
+}
+
+impl RecordBatch {
+    /// Checks every column against its field and that all columns have the same length.
+    pub fn try_new(schema: Arc<Schema>, columns: Vec<DynArray>) -> Result<Self, SchemaError> {
+        if columns.len() != schema.len() {
+            return Err(SchemaError::ColumnCount {
+                expected: schema.len(),
+                found: columns.len(),
+            });
+        }
+        let rows = columns.first().map_or(0, DynArray::len);
+        for (field, column) in schema.fields().iter().zip(&columns) {
+            field.validate(&column.data_type())?;
+            if column.len() != rows {
+                return Err(SchemaError::Length {
+                    field: field.name.clone(),
+                    expected: rows,
+                    found: column.len(),
+                });
+            }
+        }
+        Ok(Self {
+            schema,
+            columns,
+            rows,
+        })
+    }
+
+    /// Builds the schema from the column types, see [`Field::new`].
+    pub fn from_columns<N: Into<String>>(
+        columns: impl IntoIterator<Item = (N, DynArray)>,
+    ) -> Result<Self, SchemaError> {
+        let (fields, columns) = columns
+            .into_iter()
+            .map(|(name, column)| (Field::new(name, column.data_type()), column))
+            .unzip();
+        Self::try_new(Arc::new(Schema::new(fields)?), columns)
+    }
+
+    #[inline]
+    pub fn schema(&self) -> &Arc<Schema> {
+        &self.schema
+    }
+
+    #[inline]
+    pub fn num_rows(&self) -> usize {
+        self.rows
+    }
+
+    #[inline]
+    pub fn num_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    #[inline]
+    pub fn columns(&self) -> &[DynArray] {
+        &self.columns
+    }
+
+    #[inline]
+    pub fn column(&self, index: usize) -> Option<&DynArray> {
+        self.columns.get(index)
+    }
+
+    #[inline]
+    pub fn column_by_name(&self, name: &str) -> Option<&DynArray> {
+        self.schema.index_of(name).map(|index| &self.columns[index])
+    }
+
+    /// The named column as its concrete array, `None` if absent or of another type.
+    #[inline]
+    pub fn column_as<A: TypedArray>(&self, name: &str) -> Option<&A> {
+        self.column_by_name(name)?.downcast_ref()
+    }
+
+    /// The columns at `indices`, in that order.
+    pub fn project(&self, indices: &[usize]) -> Result<Self, SchemaError> {
+        let schema = self.schema.project(indices)?;
+        Ok(Self {
+            schema: Arc::new(schema),
+            columns: indices
+                .iter()
+                .map(|&index| self.columns[index].clone())
+                .collect(),
+            rows: self.rows,
+        })
+    }
+
+    #[inline]
+    pub fn project_by_name(&self, names: &[&str]) -> Result<Self, SchemaError> {
+        self.project(&self.schema.indices_of(names)?)
+    }
+
+    /// Copies the rows in `range`, which must be in bounds.
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        assert!(range.end <= self.rows, "slice out of bounds");
+        Self {
+            schema: Arc::clone(&self.schema),
+            columns: self
+                .columns
+                .iter()
+                .map(|column| column.slice(range.clone()))
+                .collect(),
+            rows: range.len(),
+        }
+    }
+
+    /// Joins the rows of batches with equal schemas; `None` for no batches.
+    pub fn concat(batches: &[RecordBatch]) -> Result<Option<Self>, SchemaError> {
+        let Some((first, rest)) = batches.split_first() else {
+            return Ok(None);
+        };
+        let mut batch = first.clone();
+        for other in rest {
+            if other.schema != batch.schema {
+                // Report the first field that differs, or the count if all shared ones match.
+                let mismatch = batch
+                    .schema
+                    .fields()
+                    .iter()
+                    .zip(other.schema.fields())
+                    .find(|(field, other)| field != other);
+                return Err(match mismatch {
+                    Some((field, other)) if field.name == other.name => SchemaError::Type {
+                        field: field.name.clone(),
+                        expected: field.data_type.clone(),
+                        found: other.data_type.clone(),
+                    },
+                    Some((_, other)) => SchemaError::UnknownField(other.name.clone()),
+                    None => SchemaError::ColumnCount {
+                        expected: batch.num_columns(),
+                        found: other.num_columns(),
+                    },
+                });
+            }
+            for (field, (column, appended)) in batch
+                .schema
+                .fields()
+                .iter()
+                .zip(batch.columns.iter_mut().zip(&other.columns))
+            {
+                if !column.append(appended) {
+                    return Err(SchemaError::Type {
+                        field: field.name.clone(),
+                        expected: column.data_type(),
+                        found: appended.data_type(),
+                    });
+                }
+            }
+            batch.rows += other.rows;
+        }
+        Ok(Some(batch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::RecordBatch;
+    use crate::{
+        array::{
+            id::IdArray, list::ListArray, option::OptionArray, primitive::PrimitiveArray, Array,
+            DynArray,
+        },
+        datatype::{DataType, ScalarValue},
+        primitive::PrimitiveType,
+        schema::{Field, Schema, SchemaError},
+    };
+
+    fn batch(ids: &[u64], names: &[Option<&str>]) -> RecordBatch {
+        let mut name_column = IdArray::new(ListArray::<u8>::new());
+        for name in names {
+            name_column.push(name.map(|name| name.as_bytes().to_vec()));
+        }
+        RecordBatch::from_columns([
+            ("id", DynArray::new(PrimitiveArray { data: ids.to_vec() })),
+            ("name", DynArray::new(name_column)),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn validation() {
+        let schema = Arc::new(
+            Schema::new(vec![Field::new(
+                "id",
+                DataType::Numeric(PrimitiveType::U64),
+            )])
+            .unwrap(),
+        );
+        let nullable = DynArray::new(OptionArray::new(PrimitiveArray::<u64>::new()));
+        assert!(matches!(
+            RecordBatch::try_new(schema.clone(), vec![nullable]),
+            Err(SchemaError::Type { .. })
+        ));
+        let mut nullable_field = schema.fields()[0].clone();
+        nullable_field.data_type = DataType::Option(Box::new(nullable_field.data_type));
+        assert!(matches!(
+            RecordBatch::try_new(
+                Arc::new(Schema::new(vec![nullable_field]).unwrap()),
+                vec![DynArray::new(
+                    OptionArray::new(PrimitiveArray::<u64>::new())
+                )],
+            ),
+            Err(SchemaError::Nullability(_))
+        ));
+
+        let result = RecordBatch::from_columns([
+            ("a", DynArray::new(PrimitiveArray { data: vec![1u8] })),
+            ("b", DynArray::new(PrimitiveArray { data: vec![1u8, 2] })),
+        ]);
+        assert_eq!(
+            result.unwrap_err(),
+            SchemaError::Length {
+                field: "b".into(),
+                expected: 1,
+                found: 2
+            }
+        );
+    }
+
+    #[test]
+    fn select_slice_and_concat() {
+        let first = batch(&[1, 2, 3], &[Some("a"), None, Some("b")]);
+        let second = batch(&[4], &[Some("a")]);
+        assert_eq!(first.num_rows(), 3);
+        assert_eq!(
+            first
+                .column_as::<PrimitiveArray<u64>>("id")
+                .unwrap()
+                .as_slice(),
+            &[1, 2, 3]
+        );
+
+        let names = first.project_by_name(&["name"]).unwrap();
+        assert_eq!(names.num_columns(), 1);
+        assert_eq!(names.schema().fields()[0].name, "name");
+
+        let tail = first.slice(1..3);
+        assert_eq!(tail.num_rows(), 2);
+        assert_eq!(tail.column(0).unwrap().get(0), Some(ScalarValue::U64(2)));
+
+        let all = RecordBatch::concat(&[first, tail, second])
+            .unwrap()
+            .unwrap();
+        assert_eq!(all.num_rows(), 6);
+        let names = all.column_as::<IdArray<ListArray<u8>>>("name").unwrap();
+        assert_eq!(names.get(5), names.get(0));
+        assert_eq!(names.get(3), Some(None));
+        assert!(RecordBatch::concat(&[]).unwrap().is_none());
+    }
+}
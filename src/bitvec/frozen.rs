@@ -0,0 +1,96 @@
+use std::ops::Range;
+
+use super::{get_bit, BitSlice, BitVec};
+use crate::buffer::Buffer;
+
+#[derive(Debug, Clone, Default)]
+pub struct FrozenBitVec {
+    buffer: Buffer<u8>,
+    offset: usize,
+    len: usize,
+}
+
+impl FrozenBitVec {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index >= self.len {
+            return None;
+        }
+        Some(get_bit(&self.buffer, index + self.offset))
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> BitSlice<'_> {
+        BitSlice {
+            offset: self.offset as u8,
+            len: self.len,
+            slice: &self.buffer,
+        }
+    }
+
+    /// Borrows `range` without touching the reference count.
+    #[inline]
+    pub fn view(&self, range: Range<usize>) -> BitSlice<'_> {
+        assert!(range.start <= range.end && range.end <= self.len);
+        let start = self.offset + range.start;
+        let end = self.offset + range.end;
+        BitSlice {
+            offset: (start % 8) as u8,
+            len: range.len(),
+            slice: &self.buffer[start / 8..end.div_ceil(8)],
+        }
+    }
+
+    #[inline]
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        assert!(range.start <= range.end && range.end <= self.len);
+        let start = self.offset + range.start;
+        let end = self.offset + range.end;
+        Self {
+            buffer: self.buffer.slice(start / 8..end.div_ceil(8)),
+            offset: start % 8,
+            len: range.len(),
+        }
+    }
+
+    pub fn into_mut(self) -> BitVec {
+        if self.offset == 0 {
+            let mut vec = self.buffer.into_vec();
+            vec.truncate(self.len.div_ceil(8));
+            if let (Some(last), 1..) = (vec.last_mut(), self.len % 8) {
+                *last &= (1 << (self.len % 8)) - 1;
+            }
+            BitVec { vec, len: self.len }
+        } else {
+            self.as_slice().to_vec()
+        }
+    }
+}
+
+impl BitVec {
+    #[inline]
+    pub fn freeze(self) -> FrozenBitVec {
+        FrozenBitVec {
+            len: self.len,
+            offset: 0,
+            buffer: self.vec.into(),
+        }
+    }
+}
+
+impl PartialEq for FrozenBitVec {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && (0..self.len).all(|i| self.get(i) == other.get(i))
+    }
+}
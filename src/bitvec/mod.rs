@@ -1,3 +1,4 @@
+pub mod frozen;
 pub mod iter;
 
 use std::{
@@ -5,6 +6,8 @@ use std::{
     ops::{BitAnd, Range},
 };
 
+pub use frozen::FrozenBitVec;
+
 const BIT_MASK: [u8; 8] = [1, 2, 4, 8, 16, 32, 64, 128];
 const UNSET_BIT_MASK: [u8; 8] = [
     255 - 1,
@@ -67,7 +70,7 @@ impl BitVec {
 
     #[inline]
     pub fn push(&mut self, value: bool) {
-        if self.len % 8 == 0 {
+        if self.len.is_multiple_of(8) {
             self.vec.push(0);
         }
         let byte = self.vec.as_mut_slice().last_mut().unwrap();
@@ -75,6 +78,97 @@ impl BitVec {
         self.len += 1;
     }
 
+    /// Appends `count` copies of `value`, filling whole bytes at once.
+    pub fn push_n(&mut self, value: bool, count: usize) {
+        let head = ((8 - self.len % 8) % 8).min(count);
+        for _ in 0..head {
+            self.push(value);
+        }
+        let bytes = (count - head) / 8;
+        self.vec
+            .resize(self.vec.len() + bytes, if value { u8::MAX } else { 0 });
+        self.len += bytes * 8;
+        for _ in 0..(count - head) % 8 {
+            self.push(value);
+        }
+    }
+
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    #[inline]
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(
+            index < self.len,
+            "bit {index} out of bounds for length {}",
+            self.len
+        );
+        set_bit(&mut self.vec, index, value);
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        self.vec.truncate(len.div_ceil(8));
+        if !len.is_multiple_of(8) {
+            // Clear the dropped bits so equal vectors have equal bytes.
+            *self.vec.last_mut().unwrap() &= u8::MAX >> (8 - len % 8);
+        }
+        self.len = len;
+    }
+
+    /// Replaces the bits in `range` with `bits`, shifting the bits after it.
+    pub fn splice(&mut self, range: Range<usize>, bits: impl IntoIterator<Item = bool>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "range {range:?} out of bounds for length {}",
+            self.len
+        );
+        let tail = self.slice(range.end..self.len).to_vec();
+        self.truncate(range.start);
+        self.extend(bits);
+        self.append(tail);
+    }
+
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        let bytes = (self.len + additional).div_ceil(8);
+        self.vec.reserve(bytes.saturating_sub(self.vec.len()));
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends eight bits per byte, least significant bit first.
+    pub(crate) fn extend_from_bytes(&mut self, bytes: &[u8]) {
+        if self.len.is_multiple_of(8) {
+            self.vec.extend_from_slice(bytes);
+            self.len += bytes.len() * 8;
+        } else {
+            for byte in bytes {
+                self.extend((0..8).map(|bit| is_set(*byte, bit)));
+            }
+        }
+    }
+
+    /// Appends `other`, copying whole bytes when `self` ends on a byte boundary.
+    pub fn append(&mut self, other: BitVec) {
+        if self.len.is_multiple_of(8) {
+            self.vec.extend(other.vec);
+            self.len += other.len;
+        } else {
+            self.extend(other);
+        }
+    }
+
     #[inline]
     pub fn get(&self, index: usize) -> Option<bool> {
         if index >= self.len {
@@ -88,7 +182,7 @@ impl BitVec {
         BitSlice {
             offset: (range.start % 8) as _,
             len: range.end - range.start,
-            slice: &self.vec[range.start / 8..(range.end + 7) / 8],
+            slice: &self.vec[range.start / 8..range.end.div_ceil(8)],
         }
     }
 
@@ -97,7 +191,7 @@ impl BitVec {
         BitSliceMut {
             offset: (range.start % 8) as _,
             len: range.end - range.start,
-            slice: &mut self.vec[range.start / 8..(range.end + 7) / 8],
+            slice: &mut self.vec[range.start / 8..range.end.div_ceil(8)],
         }
     }
 
@@ -120,6 +214,15 @@ impl BitVec {
     }
 }
 
+impl FromIterator<bool> for BitVec {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut vec = Self::default();
+        vec.extend(iter);
+        vec
+    }
+}
+
 impl Extend<bool> for BitVec {
     #[inline]
     fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
@@ -160,19 +263,51 @@ impl<'slice> BitAnd for BitSlice<'slice> {
 
     #[inline]
     fn bitand(self, rhs: Self) -> Self::Output {
+        let len = min(self.len, rhs.len);
+        if self.offset != 0 || rhs.offset != 0 {
+            return self
+                .into_iter()
+                .zip(rhs)
+                .map(|(l, r)| l & r)
+                .take(len)
+                .collect();
+        }
         BitVec {
             vec: self
                 .slice
                 .iter()
                 .zip(rhs.slice)
                 .map(|(l, r)| l & r)
+                .take(len.div_ceil(8))
                 .collect(),
-            len: min(self.len, rhs.len),
+            len,
         }
     }
 }
 
 impl<'slice> BitSlice<'slice> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bit position of the first element within its byte.
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub(crate) fn offset(&self) -> usize {
+        self.offset as usize
+    }
+
+    #[inline]
+    pub fn count_ones(&self) -> usize {
+        self.clone().into_iter().filter(|&bit| bit).count()
+    }
+
     #[inline]
     pub fn get(&self, n: usize) -> Option<bool> {
         if n >= self.len {
@@ -197,7 +332,7 @@ impl<'slice> BitSlice<'slice> {
         BitSlice {
             offset: (start % 8) as _,
             len: end - start,
-            slice: &self.slice[start / 8..(end + 7) / 8],
+            slice: &self.slice[start / 8..end.div_ceil(8)],
         }
     }
 }
@@ -246,6 +381,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn push_n() {
+        let mut v = BitVec::from([true, false, true]);
+        v.push_n(true, 21);
+        v.push_n(false, 3);
+        assert_eq!(v.len(), 27);
+        assert!((3..24).all(|i| v.get(i) == Some(true)));
+        assert_eq!(v.get(1), Some(false));
+        assert_eq!(v.get(26), Some(false));
+        assert_eq!(v.get(27), None);
+    }
+
+    #[test]
+    fn splice_and_truncate() {
+        let mut v = BitVec::from([true; 11]);
+        v.splice(2..4, [false]);
+        assert_eq!(v.len(), 10);
+        assert_eq!(v.get(2), Some(false));
+        assert_eq!(v.get(3), Some(true));
+        v.set(9, false);
+        v.truncate(3);
+        assert_eq!(v, BitVec::from([true, true, false]));
+    }
+
     #[test]
     fn bit_and() {
         let left = [
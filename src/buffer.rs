@@ -0,0 +1,133 @@
+use std::{
+    fmt,
+    ops::{Deref, Range},
+    sync::Arc,
+};
+
+/// Immutable, reference-counted storage; clones and slices share the allocation.
+pub struct Buffer<T> {
+    data: Arc<Vec<T>>,
+    offset: usize,
+    len: usize,
+}
+
+impl<T> Buffer<T> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        &self.data[self.offset..self.offset + self.len]
+    }
+
+    #[inline]
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "slice {range:?} out of bounds for length {}",
+            self.len
+        );
+        Self {
+            data: self.data.clone(),
+            offset: self.offset + range.start,
+            len: range.len(),
+        }
+    }
+
+    /// Whether no other buffer shares the allocation.
+    #[inline]
+    pub fn is_unique(&self) -> bool {
+        Arc::strong_count(&self.data) == 1
+    }
+}
+
+impl<T: Clone> Buffer<T> {
+    /// Returns the vector without copying if this is the only full view of it.
+    pub fn into_vec(self) -> Vec<T> {
+        if self.offset == 0 && self.len == self.data.len() {
+            match Arc::try_unwrap(self.data) {
+                Ok(vec) => vec,
+                Err(data) => data.as_ref().clone(),
+            }
+        } else {
+            self.as_slice().to_vec()
+        }
+    }
+}
+
+impl<T> Clone for Buffer<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            offset: self.offset,
+            len: self.len,
+        }
+    }
+}
+
+impl<T> Default for Buffer<T> {
+    #[inline]
+    fn default() -> Self {
+        Vec::new().into()
+    }
+}
+
+impl<T> From<Vec<T>> for Buffer<T> {
+    #[inline]
+    fn from(vec: Vec<T>) -> Self {
+        Self {
+            len: vec.len(),
+            offset: 0,
+            data: Arc::new(vec),
+        }
+    }
+}
+
+impl<T> Deref for Buffer<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: PartialEq> PartialEq for Buffer<T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Buffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Buffer;
+
+    #[test]
+    fn slice_and_into_vec() {
+        let buffer = Buffer::from(vec![1, 2, 3, 4, 5]);
+        let slice = buffer.slice(1..4);
+        assert_eq!(&*slice, &[2, 3, 4]);
+        assert_eq!(&*slice.slice(1..3), &[3, 4]);
+        assert!(!buffer.is_unique());
+        assert_eq!(slice.into_vec(), [2, 3, 4]);
+
+        let ptr = buffer.as_ptr();
+        let vec = buffer.into_vec();
+        assert_eq!(vec.as_ptr(), ptr);
+    }
+}
@@ -0,0 +1,179 @@
+use std::{cmp::Ordering, ops::Add};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use super::sort::compare;
+#[cfg(feature = "rayon")]
+use super::{aligned_chunks, PAR_CHUNK};
+use crate::{array::primitive::PrimitiveArray, primitive::Primitive, scalar::list::OptionSlice};
+
+#[inline]
+fn fold_sum<'a, P: Primitive + Add<Output = P>>(values: impl Iterator<Item = &'a P>) -> P {
+    values.fold(P::default(), |acc, &value| acc + value)
+}
+
+#[inline]
+fn fold_by<'a, P: Primitive + PartialOrd>(
+    values: impl Iterator<Item = &'a P>,
+    keep: Ordering,
+) -> Option<P> {
+    values.copied().reduce(|acc, value| {
+        if compare(&value, &acc) == keep {
+            value
+        } else {
+            acc
+        }
+    })
+}
+
+#[cfg(feature = "rayon")]
+#[inline]
+fn pick<P: Primitive + PartialOrd>(lhs: Option<P>, rhs: Option<P>, keep: Ordering) -> Option<P> {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => Some(if compare(&rhs, &lhs) == keep {
+            rhs
+        } else {
+            lhs
+        }),
+        (lhs, rhs) => lhs.or(rhs),
+    }
+}
+
+#[inline]
+fn valid<'a, P: Primitive>(slice: &OptionSlice<'a, P>) -> impl Iterator<Item = &'a P> {
+    slice.iter().flatten()
+}
+
+pub fn sum<P: Primitive + Add<Output = P>>(array: &PrimitiveArray<P>) -> P {
+    fold_sum(array.data.iter())
+}
+
+/// NaN is ignored unless every value is NaN.
+pub fn min<P: Primitive + PartialOrd>(array: &PrimitiveArray<P>) -> Option<P> {
+    fold_by(array.data.iter(), Ordering::Less)
+}
+
+/// NaN is the largest value.
+pub fn max<P: Primitive + PartialOrd>(array: &PrimitiveArray<P>) -> Option<P> {
+    fold_by(array.data.iter(), Ordering::Greater)
+}
+
+/// Sum of the non-null elements.
+pub fn sum_valid<P: Primitive + Add<Output = P>>(slice: &OptionSlice<'_, P>) -> P {
+    fold_sum(valid(slice))
+}
+
+pub fn min_valid<P: Primitive + PartialOrd>(slice: &OptionSlice<'_, P>) -> Option<P> {
+    fold_by(valid(slice), Ordering::Less)
+}
+
+pub fn max_valid<P: Primitive + PartialOrd>(slice: &OptionSlice<'_, P>) -> Option<P> {
+    fold_by(valid(slice), Ordering::Greater)
+}
+
+#[cfg(feature = "rayon")]
+pub fn par_sum<P: Primitive + Add<Output = P>>(array: &PrimitiveArray<P>) -> P {
+    array
+        .data
+        .par_chunks(PAR_CHUNK)
+        .map(|chunk| fold_sum(chunk.iter()))
+        .reduce(P::default, Add::add)
+}
+
+#[cfg(feature = "rayon")]
+pub fn par_min<P: Primitive + PartialOrd>(array: &PrimitiveArray<P>) -> Option<P> {
+    array
+        .data
+        .par_chunks(PAR_CHUNK)
+        .map(|chunk| fold_by(chunk.iter(), Ordering::Less))
+        .reduce(|| None, |lhs, rhs| pick(lhs, rhs, Ordering::Less))
+}
+
+#[cfg(feature = "rayon")]
+pub fn par_max<P: Primitive + PartialOrd>(array: &PrimitiveArray<P>) -> Option<P> {
+    array
+        .data
+        .par_chunks(PAR_CHUNK)
+        .map(|chunk| fold_by(chunk.iter(), Ordering::Greater))
+        .reduce(|| None, |lhs, rhs| pick(lhs, rhs, Ordering::Greater))
+}
+
+#[cfg(feature = "rayon")]
+pub fn par_sum_valid<P: Primitive + Add<Output = P>>(slice: &OptionSlice<'_, P>) -> P {
+    aligned_chunks(slice.data.len(), slice.validity.offset())
+        .into_par_iter()
+        .map(|range| fold_sum(valid(&slice.slice(range))))
+        .reduce(P::default, Add::add)
+}
+
+#[cfg(feature = "rayon")]
+pub fn par_min_valid<P: Primitive + PartialOrd>(slice: &OptionSlice<'_, P>) -> Option<P> {
+    aligned_chunks(slice.data.len(), slice.validity.offset())
+        .into_par_iter()
+        .map(|range| fold_by(valid(&slice.slice(range)), Ordering::Less))
+        .reduce(|| None, |lhs, rhs| pick(lhs, rhs, Ordering::Less))
+}
+
+#[cfg(feature = "rayon")]
+pub fn par_max_valid<P: Primitive + PartialOrd>(slice: &OptionSlice<'_, P>) -> Option<P> {
+    aligned_chunks(slice.data.len(), slice.validity.offset())
+        .into_par_iter()
+        .map(|range| fold_by(valid(&slice.slice(range)), Ordering::Greater))
+        .reduce(|| None, |lhs, rhs| pick(lhs, rhs, Ordering::Greater))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{max, max_valid, min, min_valid, sum, sum_valid};
+    use crate::{
+        array::{primitive::PrimitiveArray, Array},
+        scalar::{list::OptionList, Scalar},
+    };
+
+    #[test]
+    fn aggregate() {
+        let mut array = PrimitiveArray::new();
+        for value in [3.0, f64::NAN, -1.0, 7.5] {
+            array.push(value);
+        }
+        assert_eq!(min(&array), Some(-1.0));
+        assert!(max(&array).unwrap().is_nan());
+        assert_eq!(min(&PrimitiveArray::<u8>::new()), None);
+
+        let list = OptionList::from([Some(4i64), None, Some(-2), Some(9), None]);
+        let slice = list.as_ref();
+        assert_eq!(sum_valid(&slice), 11);
+        assert_eq!(min_valid(&slice), Some(-2));
+        assert_eq!(max_valid(&slice.slice(1..3)), Some(-2));
+        assert_eq!(max_valid(&slice.slice(4..5)), None);
+
+        let mut ints = PrimitiveArray::new();
+        ints.push(2u32);
+        ints.push(5);
+        assert_eq!(sum(&ints), 7);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_matches_serial() {
+        use super::{par_max, par_max_valid, par_min, par_min_valid, par_sum, par_sum_valid};
+
+        let mut array = PrimitiveArray::new();
+        let mut list = OptionList::default();
+        for row in 0..100_000i64 {
+            let value = (row * 7919) % 10_007 - 5_000;
+            array.push(value);
+            list.push((row % 3 != 0).then_some(value));
+        }
+        assert_eq!(par_sum(&array), sum(&array));
+        assert_eq!(par_min(&array), min(&array));
+        assert_eq!(par_max(&array), max(&array));
+
+        let slice = list.as_ref();
+        let slice = slice.slice(3..99_990);
+        assert_eq!(par_sum_valid(&slice), sum_valid(&slice));
+        assert_eq!(par_min_valid(&slice), min_valid(&slice));
+        assert_eq!(par_max_valid(&slice), max_valid(&slice));
+    }
+}
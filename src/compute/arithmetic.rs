@@ -0,0 +1,352 @@
+//! Element-wise kernels over numeric slices.
+//!
+//! Types with a native vector representation process [`Numeric::LANES`] elements per step with
+//! `std::simd`, the rest of the slice and every other type go through the scalar path. Integer
+//! arithmetic wraps on overflow in both paths.
+
+use std::simd::{
+    cmp::{SimdOrd, SimdPartialEq, SimdPartialOrd},
+    num::SimdFloat,
+    Mask, MaskElement, Simd, SimdElement,
+};
+
+use crate::{
+    bitvec::BitVec,
+    primitive::{bf16, f16, NumericPrimitive},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Min,
+    Max,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+pub trait Numeric: NumericPrimitive {
+    /// Elements per vector step; `1` for types without a SIMD path.
+    const LANES: usize = 1;
+
+    fn apply(op: BinaryOp, lhs: Self, rhs: Self) -> Self;
+
+    #[inline]
+    fn compare_one(op: CmpOp, lhs: Self, rhs: Self) -> bool {
+        match op {
+            CmpOp::Eq => lhs == rhs,
+            CmpOp::Ne => lhs != rhs,
+            CmpOp::Lt => lhs < rhs,
+            CmpOp::Le => lhs <= rhs,
+            CmpOp::Gt => lhs > rhs,
+            CmpOp::Ge => lhs >= rhs,
+        }
+    }
+
+    #[inline]
+    fn binary(op: BinaryOp, lhs: &[Self], rhs: &[Self]) -> Vec<Self> {
+        binary_scalar(op, lhs, rhs)
+    }
+
+    #[inline]
+    fn compare(op: CmpOp, lhs: &[Self], rhs: &[Self]) -> BitVec {
+        compare_scalar(op, lhs, rhs)
+    }
+}
+
+/// Applies `op` to every pair; the result is as long as the shorter input.
+#[inline]
+pub fn binary<P: Numeric>(op: BinaryOp, lhs: &[P], rhs: &[P]) -> Vec<P> {
+    P::binary(op, lhs, rhs)
+}
+
+#[inline]
+pub fn binary_scalar<P: Numeric>(op: BinaryOp, lhs: &[P], rhs: &[P]) -> Vec<P> {
+    lhs.iter()
+        .zip(rhs)
+        .map(|(&lhs, &rhs)| P::apply(op, lhs, rhs))
+        .collect()
+}
+
+#[inline]
+pub fn compare<P: Numeric>(op: CmpOp, lhs: &[P], rhs: &[P]) -> BitVec {
+    P::compare(op, lhs, rhs)
+}
+
+#[inline]
+pub fn compare_scalar<P: Numeric>(op: CmpOp, lhs: &[P], rhs: &[P]) -> BitVec {
+    lhs.iter()
+        .zip(rhs)
+        .map(|(&lhs, &rhs)| P::compare_one(op, lhs, rhs))
+        .collect()
+}
+
+#[inline]
+pub fn add<P: Numeric>(lhs: &[P], rhs: &[P]) -> Vec<P> {
+    binary(BinaryOp::Add, lhs, rhs)
+}
+
+#[inline]
+pub fn sub<P: Numeric>(lhs: &[P], rhs: &[P]) -> Vec<P> {
+    binary(BinaryOp::Sub, lhs, rhs)
+}
+
+#[inline]
+pub fn mul<P: Numeric>(lhs: &[P], rhs: &[P]) -> Vec<P> {
+    binary(BinaryOp::Mul, lhs, rhs)
+}
+
+/// Element-wise minimum; for floats a NaN yields the other value.
+#[inline]
+pub fn min<P: Numeric>(lhs: &[P], rhs: &[P]) -> Vec<P> {
+    binary(BinaryOp::Min, lhs, rhs)
+}
+
+#[inline]
+pub fn max<P: Numeric>(lhs: &[P], rhs: &[P]) -> Vec<P> {
+    binary(BinaryOp::Max, lhs, rhs)
+}
+
+#[inline(always)]
+fn zip_simd<P, const N: usize>(
+    op: BinaryOp,
+    lhs: &[P],
+    rhs: &[P],
+    simd: impl Fn(Simd<P, N>, Simd<P, N>) -> Simd<P, N>,
+) -> Vec<P>
+where
+    P: Numeric + SimdElement,
+{
+    let len = lhs.len().min(rhs.len());
+    let (lhs, rhs) = (&lhs[..len], &rhs[..len]);
+    let mut out = Vec::with_capacity(len);
+    let mut lhs_chunks = lhs.chunks_exact(N);
+    let mut rhs_chunks = rhs.chunks_exact(N);
+    for (l, r) in (&mut lhs_chunks).zip(&mut rhs_chunks) {
+        out.extend_from_slice(simd(Simd::from_slice(l), Simd::from_slice(r)).as_array());
+    }
+    out.extend(binary_scalar(
+        op,
+        lhs_chunks.remainder(),
+        rhs_chunks.remainder(),
+    ));
+    out
+}
+
+#[inline(always)]
+fn compare_simd<P, M, const N: usize>(
+    op: CmpOp,
+    lhs: &[P],
+    rhs: &[P],
+    simd: impl Fn(Simd<P, N>, Simd<P, N>) -> Mask<M, N>,
+) -> BitVec
+where
+    P: Numeric + SimdElement,
+    M: MaskElement,
+{
+    let len = lhs.len().min(rhs.len());
+    let (lhs, rhs) = (&lhs[..len], &rhs[..len]);
+    let mut out = BitVec::with_capacity(len);
+    let mut lhs_chunks = lhs.chunks_exact(N);
+    let mut rhs_chunks = rhs.chunks_exact(N);
+    for (l, r) in (&mut lhs_chunks).zip(&mut rhs_chunks) {
+        let mask = simd(Simd::from_slice(l), Simd::from_slice(r)).to_bitmask();
+        out.extend_from_bytes(&mask.to_le_bytes()[..N / 8]);
+    }
+    out.extend(
+        lhs_chunks
+            .remainder()
+            .iter()
+            .zip(rhs_chunks.remainder())
+            .map(|(&lhs, &rhs)| P::compare_one(op, lhs, rhs)),
+    );
+    out
+}
+
+macro_rules! simd_numeric {
+    ($type:ty, $lanes:literal, $add:expr, $sub:expr, $mul:expr, $min:expr, $max:expr) => {
+        impl Numeric for $type {
+            const LANES: usize = $lanes;
+
+            #[inline]
+            fn apply(op: BinaryOp, lhs: Self, rhs: Self) -> Self {
+                match op {
+                    BinaryOp::Add => $add(lhs, rhs),
+                    BinaryOp::Sub => $sub(lhs, rhs),
+                    BinaryOp::Mul => $mul(lhs, rhs),
+                    BinaryOp::Min => $min(lhs, rhs),
+                    BinaryOp::Max => $max(lhs, rhs),
+                }
+            }
+
+            fn binary(op: BinaryOp, lhs: &[Self], rhs: &[Self]) -> Vec<Self> {
+                type V = Simd<$type, $lanes>;
+                match op {
+                    BinaryOp::Add => zip_simd(op, lhs, rhs, |l: V, r: V| l + r),
+                    BinaryOp::Sub => zip_simd(op, lhs, rhs, |l: V, r: V| l - r),
+                    BinaryOp::Mul => zip_simd(op, lhs, rhs, |l: V, r: V| l * r),
+                    BinaryOp::Min => zip_simd(op, lhs, rhs, |l: V, r: V| l.simd_min(r)),
+                    BinaryOp::Max => zip_simd(op, lhs, rhs, |l: V, r: V| l.simd_max(r)),
+                }
+            }
+
+            fn compare(op: CmpOp, lhs: &[Self], rhs: &[Self]) -> BitVec {
+                type V = Simd<$type, $lanes>;
+                match op {
+                    CmpOp::Eq => compare_simd(op, lhs, rhs, |l: V, r: V| l.simd_eq(r)),
+                    CmpOp::Ne => compare_simd(op, lhs, rhs, |l: V, r: V| l.simd_ne(r)),
+                    CmpOp::Lt => compare_simd(op, lhs, rhs, |l: V, r: V| l.simd_lt(r)),
+                    CmpOp::Le => compare_simd(op, lhs, rhs, |l: V, r: V| l.simd_le(r)),
+                    CmpOp::Gt => compare_simd(op, lhs, rhs, |l: V, r: V| l.simd_gt(r)),
+                    CmpOp::Ge => compare_simd(op, lhs, rhs, |l: V, r: V| l.simd_ge(r)),
+                }
+            }
+        }
+    };
+    (int $type:ty, $lanes:literal) => {
+        simd_numeric!(
+            $type,
+            $lanes,
+            <$type>::wrapping_add,
+            <$type>::wrapping_sub,
+            <$type>::wrapping_mul,
+            Ord::min,
+            Ord::max
+        );
+    };
+    (float $type:ty, $lanes:literal) => {
+        simd_numeric!(
+            $type,
+            $lanes,
+            |l, r| l + r,
+            |l, r| l - r,
+            |l, r| l * r,
+            <$type>::min,
+            <$type>::max
+        );
+    };
+}
+
+simd_numeric!(int u8, 64);
+simd_numeric!(int u16, 32);
+simd_numeric!(int u32, 16);
+simd_numeric!(int u64, 8);
+simd_numeric!(int usize, 8);
+simd_numeric!(int i8, 64);
+simd_numeric!(int i16, 32);
+simd_numeric!(int i32, 16);
+simd_numeric!(int i64, 8);
+simd_numeric!(int isize, 8);
+simd_numeric!(float f32, 16);
+simd_numeric!(float f64, 8);
+
+macro_rules! scalar_numeric {
+    ($type:ty, $add:expr, $sub:expr, $mul:expr) => {
+        impl Numeric for $type {
+            #[inline]
+            fn apply(op: BinaryOp, lhs: Self, rhs: Self) -> Self {
+                match op {
+                    BinaryOp::Add => $add(lhs, rhs),
+                    BinaryOp::Sub => $sub(lhs, rhs),
+                    BinaryOp::Mul => $mul(lhs, rhs),
+                    BinaryOp::Min => lhs.min(rhs),
+                    BinaryOp::Max => lhs.max(rhs),
+                }
+            }
+        }
+    };
+    (int $type:ty) => {
+        scalar_numeric!(
+            $type,
+            <$type>::wrapping_add,
+            <$type>::wrapping_sub,
+            <$type>::wrapping_mul
+        );
+    };
+    (float $type:ty) => {
+        scalar_numeric!($type, |l, r| l + r, |l, r| l - r, |l, r| l * r);
+    };
+}
+
+scalar_numeric!(int u128);
+scalar_numeric!(int i128);
+scalar_numeric!(float f16);
+scalar_numeric!(float bf16);
+
+#[cfg(test)]
+mod tests {
+    use super::{binary, binary_scalar, compare, compare_scalar, BinaryOp, CmpOp, Numeric};
+
+    const BINARY: [BinaryOp; 5] = [
+        BinaryOp::Add,
+        BinaryOp::Sub,
+        BinaryOp::Mul,
+        BinaryOp::Min,
+        BinaryOp::Max,
+    ];
+    const COMPARE: [CmpOp; 6] = [
+        CmpOp::Eq,
+        CmpOp::Ne,
+        CmpOp::Lt,
+        CmpOp::Le,
+        CmpOp::Gt,
+        CmpOp::Ge,
+    ];
+
+    fn check<P: Numeric + std::fmt::Debug>(values: impl Fn(u64) -> P) {
+        for len in [0, 1, P::LANES - 1, P::LANES, 3 * P::LANES + 5] {
+            let lhs = (0..len as u64)
+                .map(|i| values(i * 7 + 3))
+                .collect::<Vec<_>>();
+            let rhs = (0..len as u64 + 2)
+                .map(|i| values(i * 13 + 1))
+                .collect::<Vec<_>>();
+            for op in BINARY {
+                let simd = binary(op, &lhs, &rhs);
+                let scalar = binary_scalar(op, &lhs, &rhs);
+                assert_eq!(simd.len(), len);
+                // Compare the formatted values so NaN results count as equal.
+                assert_eq!(format!("{simd:?}"), format!("{scalar:?}"), "{op:?}");
+            }
+            for op in COMPARE {
+                let simd = compare(op, &lhs, &rhs);
+                let scalar = compare_scalar(op, &lhs, &rhs);
+                assert_eq!(simd.len(), len);
+                assert!((0..len).all(|i| simd.get(i) == scalar.get(i)), "{op:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn simd_matches_scalar() {
+        check(|i| (i * 37) as u8);
+        check(|i| (i * 4099) as u16);
+        check(|i| (i * 2_654_435_761) as u32);
+        check(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        check(|i| (i * 37) as i8);
+        check(|i| (i * 4099) as i16);
+        check(|i| (i * 2_654_435_761) as i32);
+        check(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15) as i64);
+        check(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15) as usize);
+        check(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15) as isize);
+        check(|i| match i % 11 {
+            0 => f32::NAN,
+            1 => f32::INFINITY,
+            _ => (i as f32 - 40.0) * 0.37,
+        });
+        check(|i| match i % 13 {
+            0 => f64::NAN,
+            1 => f64::NEG_INFINITY,
+            _ => (i as f64 - 90.0) * 1.5e-3,
+        });
+    }
+}
@@ -0,0 +1,558 @@
+//! Conversions between primitive types.
+//!
+//! Every value passes through a [`Value`] wide enough to hold it exactly, so any pair of
+//! [`Cast`] types converts without a dedicated implementation.
+
+use crate::{
+    array::{list::OptionListArray, option::OptionArray, primitive::PrimitiveArray, Array},
+    bitvec::BitVec,
+    primitive::{
+        bf16,
+        decimal::{pow10, rescale},
+        f16, Decimal128, Decimal64, Primitive,
+    },
+    scalar::list::OptionList,
+};
+
+/// What to do with values the target type cannot represent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CastMode {
+    /// Out-of-range values and NaN become null.
+    #[default]
+    Safe,
+    /// Integers keep their low bits; floats overflow to infinity.
+    Wrapping,
+    /// Values clamp to the nearest representable one; NaN becomes zero.
+    Saturating,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    Signed(i128),
+    Unsigned(u128),
+    Float(f64),
+    /// A raw decimal and its scale.
+    Decimal(i128, u8),
+}
+
+impl Value {
+    /// Drops the fractional digits of a decimal.
+    #[inline]
+    fn truncate(self) -> Self {
+        match self {
+            Value::Decimal(raw, scale) => Value::Signed(raw / pow10(scale)),
+            value => value,
+        }
+    }
+}
+
+pub trait Cast: Primitive {
+    fn into_value(self) -> Value;
+
+    fn checked_from(value: Value) -> Option<Self>;
+
+    fn wrapping_from(value: Value) -> Self;
+
+    fn saturating_from(value: Value) -> Self;
+}
+
+/// Converts one value; `None` only in [`CastMode::Safe`].
+#[inline]
+pub fn cast<S: Cast, T: Cast>(value: S, mode: CastMode) -> Option<T> {
+    let value = value.into_value();
+    match mode {
+        CastMode::Safe => T::checked_from(value),
+        CastMode::Wrapping => Some(T::wrapping_from(value)),
+        CastMode::Saturating => Some(T::saturating_from(value)),
+    }
+}
+
+macro_rules! cast_int {
+    ($type:ty, $variant:ident, $wide:ty) => {
+        impl Cast for $type {
+            #[inline]
+            fn into_value(self) -> Value {
+                Value::$variant(self as $wide)
+            }
+
+            #[inline]
+            fn checked_from(value: Value) -> Option<Self> {
+                match value.truncate() {
+                    Value::Signed(value) => Self::try_from(value).ok(),
+                    Value::Unsigned(value) => Self::try_from(value).ok(),
+                    Value::Float(value) => {
+                        let value = value.trunc();
+                        (value >= Self::MIN as f64 && value < Self::MAX as f64 + 1.0)
+                            .then_some(value as Self)
+                    }
+                    Value::Decimal(..) => unreachable!(),
+                }
+            }
+
+            #[inline]
+            fn wrapping_from(value: Value) -> Self {
+                match value.truncate() {
+                    Value::Signed(value) => value as Self,
+                    Value::Unsigned(value) => value as Self,
+                    Value::Float(value) => value as i128 as Self,
+                    Value::Decimal(..) => unreachable!(),
+                }
+            }
+
+            #[inline]
+            fn saturating_from(value: Value) -> Self {
+                match value.truncate() {
+                    Value::Signed(value) => Self::try_from(value).unwrap_or(if value < 0 {
+                        Self::MIN
+                    } else {
+                        Self::MAX
+                    }),
+                    Value::Unsigned(value) => Self::try_from(value).unwrap_or(Self::MAX),
+                    Value::Float(value) => value as Self,
+                    Value::Decimal(..) => unreachable!(),
+                }
+            }
+        }
+    };
+}
+
+macro_rules! cast_float {
+    ($type:ty) => {
+        impl Cast for $type {
+            #[inline]
+            fn into_value(self) -> Value {
+                Value::Float(self as f64)
+            }
+
+            #[inline]
+            fn checked_from(value: Value) -> Option<Self> {
+                match value {
+                    Value::Float(value) if !value.is_nan() => {
+                        let cast = value as Self;
+                        (cast.is_finite() || value.is_infinite()).then_some(cast)
+                    }
+                    Value::Float(_) => None,
+                    value => Some(Self::wrapping_from(value)),
+                }
+            }
+
+            #[inline]
+            fn wrapping_from(value: Value) -> Self {
+                match value {
+                    Value::Signed(value) => value as Self,
+                    Value::Unsigned(value) => value as Self,
+                    Value::Float(value) => value as Self,
+                    Value::Decimal(raw, scale) => (raw as f64 / pow10(scale) as f64) as Self,
+                }
+            }
+
+            #[inline]
+            fn saturating_from(value: Value) -> Self {
+                match value {
+                    Value::Float(value) if value.is_nan() => 0.0,
+                    Value::Float(value) if value.is_finite() => {
+                        value.clamp(Self::MIN as f64, Self::MAX as f64) as Self
+                    }
+                    value => Self::wrapping_from(value),
+                }
+            }
+        }
+    };
+}
+
+cast_int!(u8, Unsigned, u128);
+cast_int!(u16, Unsigned, u128);
+cast_int!(u32, Unsigned, u128);
+cast_int!(u64, Unsigned, u128);
+cast_int!(i8, Signed, i128);
+cast_int!(i16, Signed, i128);
+cast_int!(i32, Signed, i128);
+cast_int!(i64, Signed, i128);
+cast_float!(f32);
+cast_float!(f64);
+cast_int!(u128, Unsigned, u128);
+cast_int!(usize, Unsigned, u128);
+cast_int!(i128, Signed, i128);
+cast_int!(isize, Signed, i128);
+
+macro_rules! cast_half {
+    ($type:ty) => {
+        /// Converts through `f32`, so the same values overflow as for [`f32`] plus those beyond
+        /// the half range.
+        impl Cast for $type {
+            #[inline]
+            fn into_value(self) -> Value {
+                Value::Float(self.to_f64())
+            }
+
+            #[inline]
+            fn checked_from(value: Value) -> Option<Self> {
+                let wide = f32::checked_from(value)?;
+                let cast = Self::from_f32(wide);
+                (cast.is_finite() || wide.is_infinite()).then_some(cast)
+            }
+
+            #[inline]
+            fn wrapping_from(value: Value) -> Self {
+                Self::from_f32(f32::wrapping_from(value))
+            }
+
+            #[inline]
+            fn saturating_from(value: Value) -> Self {
+                let wide = f32::saturating_from(value);
+                if wide.is_finite() {
+                    Self::from_f32(wide.clamp(Self::MIN.to_f32(), Self::MAX.to_f32()))
+                } else {
+                    Self::from_f32(wide)
+                }
+            }
+        }
+    };
+}
+
+cast_half!(f16);
+cast_half!(bf16);
+
+/// Only `0` and `1` convert safely; the other modes treat any non-zero value as `true`.
+impl Cast for bool {
+    #[inline]
+    fn into_value(self) -> Value {
+        Value::Unsigned(self as u128)
+    }
+
+    #[inline]
+    fn checked_from(value: Value) -> Option<Self> {
+        match value {
+            Value::Decimal(raw, scale) if raw % pow10(scale) != 0 => None,
+            Value::Decimal(..) => Self::checked_from(value.truncate()),
+            Value::Signed(0) | Value::Unsigned(0) => Some(false),
+            Value::Signed(1) | Value::Unsigned(1) => Some(true),
+            Value::Float(0.0) => Some(false),
+            Value::Float(1.0) => Some(true),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn wrapping_from(value: Value) -> Self {
+        match value {
+            Value::Signed(value) => value != 0,
+            Value::Unsigned(value) => value != 0,
+            Value::Float(value) => value != 0.0 && !value.is_nan(),
+            Value::Decimal(raw, _) => raw != 0,
+        }
+    }
+
+    #[inline]
+    fn saturating_from(value: Value) -> Self {
+        Self::wrapping_from(value)
+    }
+}
+
+macro_rules! cast_decimal {
+    ($name:ident) => {
+        /// Wrapping saturates, as decimals have no wrap-around representation.
+        impl<const P: u8, const S: u8> Cast for $name<P, S> {
+            #[inline]
+            fn into_value(self) -> Value {
+                Value::Decimal(self.raw() as i128, S)
+            }
+
+            #[inline]
+            fn checked_from(value: Value) -> Option<Self> {
+                let raw = match value {
+                    Value::Signed(value) => rescale(value, 0, S)?,
+                    Value::Unsigned(value) => rescale(i128::try_from(value).ok()?, 0, S)?,
+                    Value::Float(value) => {
+                        let raw = (value * pow10(S) as f64).round();
+                        if raw.is_nan() || raw.abs() >= 1e38 {
+                            return None;
+                        }
+                        raw as i128
+                    }
+                    Value::Decimal(raw, scale) => rescale(raw, scale, S)?,
+                };
+                Self::from_wide(raw)
+            }
+
+            #[inline]
+            fn wrapping_from(value: Value) -> Self {
+                Self::saturating_from(value)
+            }
+
+            #[inline]
+            fn saturating_from(value: Value) -> Self {
+                if let Some(decimal) = Self::checked_from(value) {
+                    return decimal;
+                }
+                let negative = match value {
+                    Value::Signed(value) | Value::Decimal(value, _) => value < 0,
+                    Value::Unsigned(_) => false,
+                    Value::Float(value) if value.is_nan() => return Self::ZERO,
+                    Value::Float(value) => value < 0.0,
+                };
+                if negative {
+                    Self::MIN
+                } else {
+                    Self::MAX
+                }
+            }
+        }
+    };
+}
+
+cast_decimal!(Decimal64);
+cast_decimal!(Decimal128);
+
+/// Casts the elements of a validity bitmap and its values; failed casts clear the bit.
+fn cast_valid<S: Cast, T: Cast>(validity: &BitVec, data: &[S], mode: CastMode) -> (BitVec, Vec<T>) {
+    let mut bits = BitVec::with_capacity(data.len());
+    let mut values = Vec::with_capacity(data.len());
+    for (valid, &value) in validity.as_slice().into_iter().zip(data) {
+        match cast(value, mode).filter(|_| valid) {
+            Some(value) => {
+                bits.push(true);
+                values.push(value);
+            }
+            None => {
+                bits.push(false);
+                values.push(T::default());
+            }
+        }
+    }
+    (bits, values)
+}
+
+/// Arrays whose elements can be cast to another primitive type.
+pub trait CastArray {
+    type Output<T: Cast>;
+
+    fn cast<T: Cast>(&self, mode: CastMode) -> Self::Output<T>;
+}
+
+/// The result is nullable because safe casts may fail; other modes leave every row valid.
+impl<S: Cast> CastArray for PrimitiveArray<S> {
+    type Output<T: Cast> = OptionArray<PrimitiveArray<T>>;
+
+    fn cast<T: Cast>(&self, mode: CastMode) -> Self::Output<T> {
+        let mut array = OptionArray::new(PrimitiveArray::new());
+        for &value in &self.data {
+            array.push(cast(value, mode));
+        }
+        array
+    }
+}
+
+impl<S: Cast> CastArray for OptionArray<PrimitiveArray<S>> {
+    type Output<T: Cast> = OptionArray<PrimitiveArray<T>>;
+
+    fn cast<T: Cast>(&self, mode: CastMode) -> Self::Output<T> {
+        let (validity, data) = cast_valid(&self.validity, &self.data.data, mode);
+        OptionArray {
+            validity,
+            data: PrimitiveArray { data },
+        }
+    }
+}
+
+impl<S: Cast> CastArray for OptionList<S> {
+    type Output<T: Cast> = OptionList<T>;
+
+    fn cast<T: Cast>(&self, mode: CastMode) -> Self::Output<T> {
+        let (validity, data) = cast_valid(&self.validity, &self.data, mode);
+        OptionList { validity, data }
+    }
+}
+
+impl<S: Cast> CastArray for OptionListArray<S> {
+    type Output<T: Cast> = OptionListArray<T>;
+
+    fn cast<T: Cast>(&self, mode: CastMode) -> Self::Output<T> {
+        let (validity, data) = cast_valid(&self.validity, &self.data, mode);
+        OptionListArray {
+            validity,
+            data,
+            list_size: self.list_size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cast, CastArray, CastMode};
+    use crate::{
+        array::{
+            id::IdArray,
+            list::{ConstSizeListArray, ListArray},
+            primitive::PrimitiveArray,
+            Array, OptionListArray,
+        },
+        primitive::{bf16, f16, Decimal128, Decimal64},
+        scalar::list::OptionList,
+    };
+
+    #[test]
+    fn scalar_modes() {
+        assert_eq!(cast::<i32, u8>(300, CastMode::Safe), None);
+        assert_eq!(cast::<i32, u8>(300, CastMode::Wrapping), Some(44));
+        assert_eq!(cast::<i32, u8>(300, CastMode::Saturating), Some(255));
+        assert_eq!(cast::<i32, u8>(-1, CastMode::Saturating), Some(0));
+        assert_eq!(cast::<u64, i64>(u64::MAX, CastMode::Wrapping), Some(-1));
+        assert_eq!(cast::<i8, u64>(-1, CastMode::Safe), None);
+
+        assert_eq!(cast::<f64, i8>(-128.9, CastMode::Safe), Some(-128));
+        assert_eq!(cast::<f64, i8>(128.0, CastMode::Safe), None);
+        assert_eq!(
+            cast::<f64, u64>(1.8e19, CastMode::Safe),
+            Some(18_000_000_000_000_000_000)
+        );
+        assert_eq!(cast::<f64, u64>(2.0e19, CastMode::Safe), None);
+        assert_eq!(cast::<f32, i16>(f32::NAN, CastMode::Safe), None);
+        assert_eq!(cast::<f32, i16>(f32::NAN, CastMode::Saturating), Some(0));
+        assert_eq!(cast::<f32, i16>(-1e9, CastMode::Saturating), Some(i16::MIN));
+
+        assert_eq!(cast::<f64, f32>(1e300, CastMode::Safe), None);
+        assert_eq!(
+            cast::<f64, f32>(1e300, CastMode::Wrapping),
+            Some(f32::INFINITY)
+        );
+        assert_eq!(
+            cast::<f64, f32>(-1e300, CastMode::Saturating),
+            Some(f32::MIN)
+        );
+        assert_eq!(
+            cast::<f64, f32>(f64::NEG_INFINITY, CastMode::Safe),
+            Some(f32::NEG_INFINITY)
+        );
+        assert_eq!(cast::<u64, f32>(3, CastMode::Safe), Some(3.0));
+
+        assert_eq!(cast::<u8, bool>(2, CastMode::Safe), None);
+        assert_eq!(cast::<u8, bool>(2, CastMode::Wrapping), Some(true));
+        assert_eq!(cast::<f32, bool>(1.0, CastMode::Safe), Some(true));
+        assert_eq!(cast::<bool, f64>(true, CastMode::Safe), Some(1.0));
+        assert_eq!(cast::<bool, i8>(false, CastMode::Safe), Some(0));
+    }
+
+    #[test]
+    fn wide_and_half_casts() {
+        assert_eq!(cast::<u128, i64>(1 << 70, CastMode::Safe), None);
+        assert_eq!(cast::<u128, i128>(u128::MAX, CastMode::Wrapping), Some(-1));
+        assert_eq!(cast::<i128, usize>(-5, CastMode::Saturating), Some(0));
+        assert_eq!(cast::<isize, u16>(65_535, CastMode::Safe), Some(u16::MAX));
+
+        assert_eq!(cast::<f32, f16>(1e5, CastMode::Safe), None);
+        assert_eq!(cast::<f32, f16>(1e5, CastMode::Saturating), Some(f16::MAX));
+        assert_eq!(
+            cast::<f32, f16>(1e5, CastMode::Wrapping),
+            Some(f16::INFINITY)
+        );
+        assert_eq!(
+            cast::<f32, bf16>(1e5, CastMode::Safe),
+            Some(bf16::from_f32(1e5))
+        );
+        assert_eq!(
+            cast::<bf16, i32>(bf16::from_f32(-2.5), CastMode::Safe),
+            Some(-2)
+        );
+        assert_eq!(
+            cast::<u128, f16>(2048, CastMode::Safe),
+            Some(f16::from_f32(2048.0))
+        );
+    }
+
+    #[test]
+    fn decimal_casts() {
+        type Money = Decimal64<6, 2>;
+
+        let price: Money = "-12.75".parse().unwrap();
+        assert_eq!(cast::<_, i32>(price, CastMode::Safe), Some(-12));
+        assert_eq!(cast::<_, u8>(price, CastMode::Safe), None);
+        assert_eq!(cast::<_, u8>(price, CastMode::Saturating), Some(0));
+        assert_eq!(cast::<_, f64>(price, CastMode::Safe), Some(-12.75));
+        assert_eq!(cast::<_, bool>(price, CastMode::Safe), None);
+        assert_eq!(
+            cast::<_, Decimal128<20, 1>>(price, CastMode::Safe).map(|d| d.to_string()),
+            Some("-12.8".into())
+        );
+
+        assert_eq!(
+            cast::<i64, Money>(42, CastMode::Safe).map(|d| d.raw()),
+            Some(4200)
+        );
+        assert_eq!(cast::<i64, Money>(10_000, CastMode::Safe), None);
+        assert_eq!(
+            cast::<i64, Money>(10_000, CastMode::Saturating),
+            Some(Money::MAX)
+        );
+        assert_eq!(
+            cast::<f64, Money>(0.125, CastMode::Safe).map(|d| d.raw()),
+            Some(13)
+        );
+        assert_eq!(cast::<f32, Money>(f32::NAN, CastMode::Safe), None);
+        assert_eq!(
+            cast::<f64, Money>(-1e30, CastMode::Wrapping),
+            Some(Money::MIN)
+        );
+    }
+
+    #[test]
+    fn cast_arrays() {
+        let mut array = PrimitiveArray::new();
+        for value in [1i64, -5, 1000] {
+            array.push(value);
+        }
+        let safe = array.cast::<i8>(CastMode::Safe);
+        assert_eq!(safe.get(1), Some(Some(&-5)));
+        assert_eq!(safe.get(2), Some(None));
+        let saturated = array.cast::<u8>(CastMode::Saturating);
+        assert_eq!(saturated.null_count(), 0);
+        assert_eq!(saturated.get(1), Some(Some(&0)));
+        assert_eq!(safe.cast::<f32>(CastMode::Safe).get(2), Some(None));
+
+        let list = OptionList::from([Some(2.5f64), None, Some(-1.0)]);
+        let ints = list.cast::<u16>(CastMode::Safe);
+        assert_eq!(ints.get(0), Some(&2));
+        assert_eq!(ints.get(1), None);
+        assert_eq!(ints.get(2), None);
+
+        let mut lists = OptionListArray::new(2);
+        lists.push(OptionList::from([Some(70_000u32), Some(7)]));
+        let lists = lists.cast::<u16>(CastMode::Wrapping);
+        assert_eq!(lists.list_size(), 2);
+        assert_eq!(lists.get(0).unwrap().get(0), Some(Some(&4464)));
+        assert_eq!(lists.get(0).unwrap().get(1), Some(Some(&7)));
+    }
+
+    #[test]
+    fn layout_casts() {
+        let mut lists = ListArray::new();
+        lists.push(vec![1u8, 2]);
+        lists.push(vec![3, 4]);
+        let pairs = lists.to_const_size::<2>().unwrap();
+        assert_eq!(pairs.get(1), Some(&[3, 4]));
+        assert!(lists.to_const_size::<3>().is_none());
+        lists.push(vec![5]);
+        assert!(lists.to_const_size::<2>().is_none());
+
+        let options = pairs.to_option_list();
+        assert_eq!(options.list_size(), 2);
+        assert_eq!(options.get(0).unwrap().get(1), Some(Some(&2)));
+        let back: ConstSizeListArray<u8, 2> = options.to_const_size().unwrap();
+        assert_eq!(back.get(0), Some(&[1, 2]));
+
+        let encoded = IdArray::encode(&lists);
+        assert_eq!(encoded.get(2), Some(Some(&[5][..])));
+        let decoded = encoded.decode();
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(decoded.get(0), Some(Some(&[1, 2][..])));
+
+        let mut sparse = IdArray::new(ListArray::<u8>::new());
+        sparse.push(Some(vec![9]));
+        sparse.push(None);
+        sparse.push(Some(vec![9]));
+        let decoded = sparse.decode();
+        assert_eq!(decoded.get(1), Some(None));
+        let encoded = IdArray::encode_option(&decoded);
+        assert_eq!(encoded.data.to_vec(), [1, 0, 1]);
+    }
+}
@@ -0,0 +1,183 @@
+//! Dictionary-level kernels over [`IdArray`]s.
+
+use std::hash::{BuildHasher, Hash};
+
+use ahash::RandomState;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+#[cfg(feature = "rayon")]
+use super::PAR_CHUNK;
+#[cfg(feature = "rayon")]
+use crate::array::concurrent::ConcurrentSlotMap;
+use crate::{
+    array::{id::IdArray, idvec::IdVec, slotmap::SlotMap, Array},
+    bitvec::BitVec,
+};
+
+/// The values of several dictionaries merged into one.
+#[derive(Debug, Clone)]
+pub struct UnifiedDictionary<A, S = RandomState> {
+    values: SlotMap<A, S>,
+    remaps: Vec<Vec<usize>>,
+}
+
+impl<A: Array, S> UnifiedDictionary<A, S> {
+    #[inline]
+    pub fn values(&self) -> &A {
+        &self.values.data
+    }
+
+    #[inline]
+    pub fn num_inputs(&self) -> usize {
+        self.remaps.len()
+    }
+
+    /// Maps the ids of input `input` to shared ids; `0` stays null.
+    #[inline]
+    pub fn remap(&self, input: usize) -> &[usize] {
+        &self.remaps[input]
+    }
+
+    /// The ids of input `input` translated to the shared dictionary.
+    #[inline]
+    pub fn remap_ids(&self, input: usize, ids: &IdVec) -> IdVec {
+        ids.remap(self.remap(input))
+    }
+
+    /// An array over the shared dictionary with `ids` already remapped.
+    #[inline]
+    pub fn into_array(self, ids: IdVec) -> IdArray<A, S> {
+        IdArray::from_parts(self.values, ids, false, None)
+    }
+}
+
+/// Interns every value of every dictionary, in input order, into one shared dictionary hashed
+/// like the first input's, or by `S::default()` without inputs.
+///
+/// Values no row refers to are kept; see [`IdArray::compact`].
+pub fn unify_dictionaries<'a, A, S>(
+    arrays: impl IntoIterator<Item = &'a IdArray<A, S>>,
+) -> UnifiedDictionary<A, S>
+where
+    A: Array + Default + 'a,
+    S: BuildHasher + Clone + Default + 'a,
+    for<'lhs, 'rhs> A::ItemRef<'lhs>: PartialEq<A::ItemRef<'rhs>>,
+    for<'r> A::ItemRef<'r>: Hash,
+{
+    let mut arrays = arrays.into_iter().peekable();
+    let hasher = arrays
+        .peek()
+        .map_or_else(S::default, |array| array.hasher().clone());
+    let mut values = SlotMap::with_hasher(A::default(), hasher);
+    let remaps = arrays
+        .map(|array| {
+            let mut remap = Vec::with_capacity(array.values.data.len() + 1);
+            remap.push(0);
+            for value in array.values.data.iter() {
+                remap.push(values.lookup_or_insert_ref(value));
+            }
+            remap
+        })
+        .collect();
+    UnifiedDictionary { values, remaps }
+}
+
+/// Row-wise equality of two arrays of the same length, comparing ids rather than values.
+///
+/// Each value of `rhs`'s dictionary is looked up in `lhs`'s once, so arrays sharing a
+/// dictionary cost no more than comparing their ids. Nulls equal nulls.
+pub fn eq_ids<A, S>(lhs: &IdArray<A, S>, rhs: &IdArray<A, S>) -> BitVec
+where
+    A: Array,
+    S: BuildHasher + 'static,
+    for<'lhs, 'rhs> A::ItemRef<'lhs>: PartialEq<A::ItemRef<'rhs>>,
+    for<'r> A::ItemRef<'r>: Hash,
+{
+    assert_eq!(lhs.len(), rhs.len(), "arrays differ in length");
+    // Values missing from `lhs` map to an id no row of `lhs` can hold.
+    let remap = std::iter::once(0)
+        .chain(
+            rhs.values
+                .data
+                .iter()
+                .map(|value| lhs.values.lookup(value).unwrap_or(usize::MAX)),
+        )
+        .collect::<Vec<_>>();
+    lhs.data
+        .iter()
+        .zip(&rhs.data)
+        .map(|(lhs, rhs)| lhs == remap[rhs])
+        .collect()
+}
+
+/// Dictionary-encodes a batch on every thread of the pool.
+///
+/// The rows keep their order but, unlike pushing them one by one, ids are not assigned in order
+/// of first appearance.
+#[cfg(feature = "rayon")]
+pub fn par_encode<A>(items: Vec<Option<A::Item>>) -> IdArray<A>
+where
+    A: Array + Default + Send,
+    A::Item: Send,
+    for<'lhs, 'rhs> A::ItemRef<'lhs>: PartialEq<A::ItemRef<'rhs>>,
+    for<'r> A::ItemRef<'r>: Hash,
+{
+    let map = ConcurrentSlotMap::<A>::new(rayon::current_num_threads() * 4);
+    let parts = items
+        .into_par_iter()
+        .chunks(PAR_CHUNK)
+        .map(|chunk| {
+            let mut builder = map.builder();
+            for item in chunk {
+                builder.push(item);
+            }
+            builder.into_ids()
+        })
+        .collect::<Vec<_>>();
+    map.into_id_array(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "rayon")]
+    use super::par_encode;
+    use super::{eq_ids, unify_dictionaries};
+    use crate::array::{id::IdArray, list::ListArray, Array};
+
+    fn words(words: &[Option<&str>]) -> IdArray<ListArray<u8>> {
+        words
+            .iter()
+            .map(|word| word.map(|word| word.as_bytes().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn unify_and_compare() {
+        let first = words(&[Some("a"), Some("b"), None]);
+        let second = words(&[Some("c"), Some("a"), Some("c")]);
+        let unified = unify_dictionaries([&first, &second]);
+        assert_eq!(unified.num_inputs(), 2);
+        assert_eq!(unified.values().len(), 3);
+        assert_eq!(unified.remap(1), &[0, 3, 1]);
+        assert_eq!(unified.remap_ids(1, &second.data).to_vec(), [3, 1, 3]);
+
+        let equal = eq_ids(&first, &words(&[Some("a"), Some("c"), None]));
+        assert_eq!(equal, [true, false, true].into_iter().collect());
+        assert_eq!(eq_ids(&second, &second).as_slice().count_ones(), 3);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn encode_batch() {
+        let items = (0..100_000)
+            .map(|row| (row % 11 != 0).then(|| format!("k{}", row % 97).into_bytes()))
+            .collect::<Vec<_>>();
+        let array = par_encode::<ListArray<u8>>(items.clone());
+        assert_eq!(array.len(), items.len());
+        assert_eq!(array.values.data.len(), 97);
+        for (row, item) in items.iter().enumerate().step_by(997) {
+            assert_eq!(array.get(row), Some(item.as_deref()));
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! Kernels over [`DynArray`], dispatched on the [`DataType`] to the typed kernels.
+//!
+//! Only types whose concrete array follows from the data type are supported: booleans and
+//! numbers, bare or wrapped in `Option`. Selecting rows works for every array through
+//! [`DynArray::take`].
+
+use std::fmt;
+
+use super::{
+    aggregate,
+    arithmetic::{self, BinaryOp},
+    cast::{CastArray, CastMode},
+    sort,
+};
+use crate::{
+    array::{option::OptionArray, primitive::PrimitiveArray, DynArray, TypedArray},
+    datatype::{with_numeric, DataType, ScalarValue, TypedPrimitive},
+    primitive::Primitive,
+    scalar::list::OptionSlice,
+};
+
+/// A kernel was called with a data type it has no implementation for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsupported {
+    pub kernel: &'static str,
+    pub data_type: DataType,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not supported for {}", self.kernel, self.data_type)
+    }
+}
+
+impl std::error::Error for Unsupported {}
+
+pub type Result<T> = std::result::Result<T, Unsupported>;
+
+/// Evaluates `$body` with `$T` aliased to the boolean or numeric leaf type.
+macro_rules! with_leaf {
+    ($leaf:expr, $T:ident => $body:expr, else $other:expr) => {
+        match $leaf {
+            DataType::Boolean => {
+                type $T = bool;
+                $body
+            }
+            DataType::Numeric(kind) => with_numeric!(*kind, $T => $body),
+            _ => $other,
+        }
+    };
+}
+
+#[inline]
+fn unsupported(kernel: &'static str, array: &DynArray) -> Unsupported {
+    Unsupported {
+        kernel,
+        data_type: array.data_type(),
+    }
+}
+
+/// Fails for arrays whose Rust type differs from the one their data type names, such as
+/// wrappers that report the data type of the value they wrap.
+#[inline]
+fn downcast<'a, A: TypedArray>(kernel: &'static str, array: &'a DynArray) -> Result<&'a A> {
+    array
+        .downcast_ref()
+        .ok_or_else(|| unsupported(kernel, array))
+}
+
+#[inline]
+fn option_slice<P: Primitive>(array: &OptionArray<PrimitiveArray<P>>) -> OptionSlice<'_, P> {
+    OptionSlice {
+        validity: array.validity(),
+        data: array.data.as_slice(),
+    }
+}
+
+fn dispatch(
+    kernel: &'static str,
+    array: &DynArray,
+    bare: impl Fn(&DynArray) -> Option<ScalarValue>,
+) -> Result<ScalarValue> {
+    bare(array)
+        .map(Ok)
+        .unwrap_or_else(|| Err(unsupported(kernel, array)))
+}
+
+/// Sum of the non-null values, zero for an empty array.
+pub fn sum(array: &DynArray) -> Result<ScalarValue> {
+    dispatch("sum", array, |array| match array.data_type() {
+        DataType::Numeric(kind) => with_numeric!(kind, T => Some(
+            aggregate::sum(array.downcast_ref::<PrimitiveArray<T>>()?).into_scalar()
+        )),
+        DataType::Option(inner) => match *inner {
+            DataType::Numeric(kind) => with_numeric!(kind, T => {
+                let array = array.downcast_ref::<OptionArray<PrimitiveArray<T>>>()?;
+                Some(aggregate::sum_valid(&option_slice(array)).into_scalar())
+            }),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+fn min_max(kernel: &'static str, array: &DynArray, max: bool) -> Result<ScalarValue> {
+    fn pick<P: TypedPrimitive + PartialOrd>(value: Option<P>) -> Option<ScalarValue> {
+        Some(value.map_or(ScalarValue::Null, P::into_scalar))
+    }
+    dispatch(kernel, array, |array| match array.data_type() {
+        DataType::Numeric(kind) => with_numeric!(kind, T => {
+            let array = array.downcast_ref::<PrimitiveArray<T>>()?;
+            pick(if max { aggregate::max(array) } else { aggregate::min(array) })
+        }),
+        DataType::Option(inner) => match *inner {
+            DataType::Numeric(kind) => with_numeric!(kind, T => {
+                let slice = option_slice(array.downcast_ref::<OptionArray<PrimitiveArray<T>>>()?);
+                pick(if max {
+                    aggregate::max_valid(&slice)
+                } else {
+                    aggregate::min_valid(&slice)
+                })
+            }),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Smallest non-null value, [`ScalarValue::Null`] if there is none.
+#[inline]
+pub fn min(array: &DynArray) -> Result<ScalarValue> {
+    min_max("min", array, false)
+}
+
+#[inline]
+pub fn max(array: &DynArray) -> Result<ScalarValue> {
+    min_max("max", array, true)
+}
+
+/// See [`sort::argsort`]; nulls sort first.
+pub fn argsort(array: &DynArray) -> Result<Vec<usize>> {
+    let data_type = array.data_type();
+    match &data_type {
+        DataType::Option(inner) if inner.is_primitive() => with_leaf!(&**inner, T => {
+            Ok(sort::argsort(downcast::<OptionArray<PrimitiveArray<T>>>("argsort", array)?))
+        }, else Err(unsupported("argsort", array))),
+        leaf => with_leaf!(leaf, T => {
+            Ok(sort::argsort(downcast::<PrimitiveArray<T>>("argsort", array)?))
+        }, else Err(unsupported("argsort", array))),
+    }
+}
+
+/// Applies `op` to two numeric arrays of the same type.
+pub fn binary(op: BinaryOp, lhs: &DynArray, rhs: &DynArray) -> Result<DynArray> {
+    match (lhs.data_type(), rhs.data_type()) {
+        (DataType::Numeric(kind), DataType::Numeric(other)) if kind == other => {
+            with_numeric!(kind, T => {
+                let lhs = downcast::<PrimitiveArray<T>>("binary", lhs)?;
+                let rhs = downcast::<PrimitiveArray<T>>("binary", rhs)?;
+                Ok(DynArray::new(PrimitiveArray {
+                    data: arithmetic::binary(op, lhs.as_slice(), rhs.as_slice()),
+                }))
+            })
+        }
+        (DataType::Numeric(_), _) => Err(unsupported("binary", rhs)),
+        _ => Err(unsupported("binary", lhs)),
+    }
+}
+
+/// Casts boolean or numeric values to `to`, a boolean or numeric type. The result is always
+/// nullable, as [`CastMode::Safe`] turns unrepresentable values into nulls.
+pub fn cast(array: &DynArray, to: &DataType, mode: CastMode) -> Result<DynArray> {
+    let unsupported_target = || {
+        Err(Unsupported {
+            kernel: "cast",
+            data_type: to.clone(),
+        })
+    };
+    let data_type = array.data_type();
+    match &data_type {
+        DataType::Option(inner) if inner.is_primitive() => with_leaf!(&**inner, S => {
+            let array = downcast::<OptionArray<PrimitiveArray<S>>>("cast", array)?;
+            with_leaf!(to, T => Ok(DynArray::new(array.cast::<T>(mode))), else unsupported_target())
+        }, else Err(unsupported("cast", array))),
+        leaf => with_leaf!(leaf, S => {
+            let array = downcast::<PrimitiveArray<S>>("cast", array)?;
+            with_leaf!(to, T => Ok(DynArray::new(array.cast::<T>(mode))), else unsupported_target())
+        }, else Err(unsupported("cast", array))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{argsort, binary, cast, max, min, sum};
+    use crate::{
+        array::{option::OptionArray, primitive::PrimitiveArray, Array, DynArray, OptionListArray},
+        bitvec::BitVec,
+        compute::{arithmetic::BinaryOp, CastMode},
+        datatype::{DataType, ScalarValue, TypedPrimitive},
+        primitive::{bf16, PrimitiveType, Total},
+        scalar::list::OptionList,
+    };
+
+    fn options<P: TypedPrimitive>(values: &[Option<P>]) -> DynArray {
+        let mut array = OptionArray::new(PrimitiveArray::new());
+        for &value in values {
+            array.push(value);
+        }
+        DynArray::new(array)
+    }
+
+    #[test]
+    fn aggregates() {
+        let array = options(&[Some(3i16), None, Some(-7), Some(5)]);
+        assert_eq!(sum(&array), Ok(ScalarValue::I16(1)));
+        assert_eq!(min(&array), Ok(ScalarValue::I16(-7)));
+        assert_eq!(max(&options::<f32>(&[None])), Ok(ScalarValue::Null));
+        let bools = DynArray::new(PrimitiveArray { data: vec![true] });
+        let error = sum(&bools).unwrap_err();
+        assert_eq!(error.to_string(), "sum is not supported for bool");
+        let totals = DynArray::new(PrimitiveArray {
+            data: vec![Total(1.5f64)],
+        });
+        assert!(sum(&totals).is_err());
+        assert!(argsort(&totals).is_err());
+    }
+
+    #[test]
+    fn sort_and_select() {
+        let array = options(&[Some(2.5f64), None, Some(-1.0)]);
+        let order = argsort(&array).unwrap();
+        assert_eq!(order, vec![1, 2, 0]);
+        let sorted = array.take(&order);
+        assert_eq!(sorted.get(1), Some(ScalarValue::F64(-1.0)));
+
+        let mut lists = OptionListArray::<bf16>::new(2);
+        lists.push(OptionList::from([None, Some(bf16::ONE)]));
+        lists.push(OptionList::from([Some(bf16::ZERO), None]));
+        let lists = DynArray::new(lists);
+        let mask = [false, true].into_iter().collect::<BitVec>();
+        let kept = lists.filter(&mask.as_slice());
+        assert_eq!(kept.len(), 1);
+        assert_eq!(
+            kept.get(0),
+            Some(ScalarValue::List(vec![
+                ScalarValue::BF16(bf16::ZERO),
+                ScalarValue::Null
+            ]))
+        );
+    }
+
+    #[test]
+    fn arithmetic_and_casts() {
+        let lhs = DynArray::new(PrimitiveArray {
+            data: vec![1u128, 2, 3],
+        });
+        let rhs = DynArray::new(PrimitiveArray {
+            data: vec![10u128, 20, 30],
+        });
+        let total = binary(BinaryOp::Add, &lhs, &rhs).unwrap();
+        assert_eq!(total.get(2), Some(ScalarValue::U128(33)));
+        assert!(binary(BinaryOp::Add, &lhs, &options(&[Some(1u8)])).is_err());
+
+        let values = options(&[Some(300i32), None, Some(7)]);
+        let to = DataType::Numeric(PrimitiveType::U8);
+        let cast = cast(&values, &to, CastMode::Safe).unwrap();
+        assert_eq!(cast.data_type(), DataType::Option(Box::new(to)));
+        assert_eq!(cast.get(0), Some(ScalarValue::Null));
+        assert_eq!(cast.get(2), Some(ScalarValue::U8(7)));
+    }
+}
@@ -0,0 +1,54 @@
+use std::hash::{BuildHasher, Hash};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+#[cfg(feature = "rayon")]
+use super::PAR_CHUNK;
+use crate::array::Array;
+
+/// Hash of every row; equal rows hash equally under the same `state`.
+pub fn hash<A: Array, S: BuildHasher>(array: &A, state: &S) -> Vec<u64>
+where
+    for<'a> A::ItemRef<'a>: Hash,
+{
+    (0..array.len())
+        .map(|row| state.hash_one(unsafe { array.get_unchecked(row) }))
+        .collect()
+}
+
+#[cfg(feature = "rayon")]
+pub fn par_hash<A: Array + Sync, S: BuildHasher + Sync>(array: &A, state: &S) -> Vec<u64>
+where
+    for<'a> A::ItemRef<'a>: Hash,
+{
+    (0..array.len())
+        .into_par_iter()
+        .with_min_len(PAR_CHUNK)
+        .map(|row| state.hash_one(unsafe { array.get_unchecked(row) }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ahash::RandomState;
+
+    use super::hash;
+    use crate::array::{list::ListArray, option::OptionArray, Array};
+
+    #[test]
+    fn equal_rows_hash_equally() {
+        let mut array = OptionArray::new(ListArray::<u8>::new());
+        for value in [Some("x"), None, Some("x"), Some("y"), None] {
+            array.push(value.map(Vec::from));
+        }
+        let state = RandomState::new();
+        let hashes = hash(&array, &state);
+        assert_eq!(hashes[0], hashes[2]);
+        assert_eq!(hashes[1], hashes[4]);
+        assert_ne!(hashes[0], hashes[3]);
+
+        #[cfg(feature = "rayon")]
+        assert_eq!(super::par_hash(&array, &state), hashes);
+    }
+}
@@ -0,0 +1,68 @@
+//! Kernels over arrays.
+//!
+//! With the `rayon` feature every kernel also has a `par_` version. Parallel work is split into
+//! chunks of [`PAR_CHUNK`] rows whose boundaries fall on 64-bit words of the validity bitmap, so
+//! per-chunk bitmaps can be joined without shifting.
+
+pub mod aggregate;
+pub mod arithmetic;
+pub mod cast;
+pub mod dictionary;
+pub mod dynamic;
+pub mod hash;
+pub mod select;
+pub mod sort;
+pub mod temporal;
+
+#[cfg(feature = "rayon")]
+use std::ops::Range;
+
+pub use aggregate::{max, max_valid, min, min_valid, sum, sum_valid};
+#[cfg(feature = "rayon")]
+pub use aggregate::{par_max, par_max_valid, par_min, par_min_valid, par_sum, par_sum_valid};
+pub use cast::{cast, Cast, CastArray, CastMode};
+#[cfg(feature = "rayon")]
+pub use dictionary::par_encode;
+pub use dictionary::{eq_ids, unify_dictionaries, UnifiedDictionary};
+pub use hash::hash;
+#[cfg(feature = "rayon")]
+pub use hash::par_hash;
+pub use select::Select;
+pub use sort::{argsort, sort};
+#[cfg(feature = "rayon")]
+pub use sort::{par_argsort, par_sort};
+
+/// Rows handled by one parallel task; a multiple of 64.
+#[cfg(feature = "rayon")]
+pub const PAR_CHUNK: usize = 1 << 14;
+
+/// Splits `0..len` into ranges of at most [`PAR_CHUNK`] rows. Every boundary but the first is
+/// word aligned in a bitmap whose first row sits `bit_offset` bits into its first byte.
+#[cfg(feature = "rayon")]
+pub(crate) fn aligned_chunks(len: usize, bit_offset: usize) -> Vec<Range<usize>> {
+    let mut chunks = Vec::with_capacity(len / PAR_CHUNK + 2);
+    let mut start = 0;
+    while start < len {
+        let end = ((bit_offset + start) / PAR_CHUNK + 1) * PAR_CHUNK - bit_offset;
+        let end = end.min(len);
+        chunks.push(start..end);
+        start = end;
+    }
+    chunks
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod tests {
+    use super::{aligned_chunks, PAR_CHUNK};
+
+    #[test]
+    fn chunks_are_aligned() {
+        assert!(aligned_chunks(0, 0).is_empty());
+        assert_eq!(aligned_chunks(10, 3), vec![0..10]);
+        let chunks = aligned_chunks(3 * PAR_CHUNK, 5);
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(chunks[0], 0..PAR_CHUNK - 5);
+        assert_eq!(chunks[3].end, 3 * PAR_CHUNK);
+        assert!(chunks[1..].iter().all(|range| (range.start + 5) % 64 == 0));
+    }
+}
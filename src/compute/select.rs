@@ -0,0 +1,291 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+#[cfg(feature = "rayon")]
+use super::{aligned_chunks, PAR_CHUNK};
+use crate::{
+    array::{
+        id::IdArray,
+        idvec::IdVec,
+        list::{ConstSizeListArray, ListArray, OptionListArray},
+        option::OptionArray,
+        primitive::PrimitiveArray,
+        Array,
+    },
+    bitvec::{BitSlice, BitVec},
+    primitive::Primitive,
+};
+
+/// Indices of the set bits of `mask`.
+pub fn mask_indices(mask: &BitSlice<'_>) -> Vec<usize> {
+    mask.clone()
+        .into_iter()
+        .enumerate()
+        .filter_map(|(row, bit)| bit.then_some(row))
+        .collect()
+}
+
+#[cfg(feature = "rayon")]
+pub fn par_mask_indices(mask: &BitSlice<'_>) -> Vec<usize> {
+    aligned_chunks(mask.len(), mask.offset())
+        .into_par_iter()
+        .flat_map_iter(|range| {
+            let start = range.start;
+            mask_indices(&mask.slice(range))
+                .into_iter()
+                .map(move |row| row + start)
+        })
+        .collect()
+}
+
+/// Gathers rows into a new array of the same type.
+///
+/// Indices must be in bounds; `filter` keeps the rows whose bit is set in the mask.
+pub trait Select: Sized {
+    fn take(&self, indices: &[usize]) -> Self;
+
+    #[inline]
+    fn filter(&self, mask: &BitSlice<'_>) -> Self {
+        self.take(&mask_indices(mask))
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_take(&self, indices: &[usize]) -> Self;
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    fn par_filter(&self, mask: &BitSlice<'_>) -> Self {
+        self.par_take(&par_mask_indices(mask))
+    }
+}
+
+fn take_bits(bits: &BitVec, indices: &[usize], width: usize) -> BitVec {
+    let mut taken = BitVec::with_capacity(indices.len() * width);
+    for &index in indices {
+        taken.extend(bits.slice(index * width..(index + 1) * width));
+    }
+    taken
+}
+
+/// Each chunk but the last holds a multiple of 64 bits, so the chunks are appended bytewise.
+#[cfg(feature = "rayon")]
+fn par_take_bits(bits: &BitVec, indices: &[usize], width: usize) -> BitVec {
+    let chunks = indices
+        .par_chunks(PAR_CHUNK)
+        .map(|chunk| take_bits(bits, chunk, width))
+        .collect::<Vec<_>>();
+    let mut taken = BitVec::with_capacity(indices.len() * width);
+    for chunk in chunks {
+        taken.append(chunk);
+    }
+    taken
+}
+
+impl<P: Primitive> Select for PrimitiveArray<P> {
+    #[inline]
+    fn take(&self, indices: &[usize]) -> Self {
+        Self {
+            data: indices.iter().map(|&index| self.data[index]).collect(),
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    fn par_take(&self, indices: &[usize]) -> Self {
+        Self {
+            data: indices.par_iter().map(|&index| self.data[index]).collect(),
+        }
+    }
+}
+
+impl<P: Primitive> Select for ListArray<P> {
+    fn take(&self, indices: &[usize]) -> Self {
+        let mut taken = Self::new();
+        taken.offsets.reserve(indices.len());
+        for &index in indices {
+            taken.data.extend_from_slice(self.get(index).unwrap());
+            taken.offsets.push(taken.data.len());
+        }
+        taken
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_take(&self, indices: &[usize]) -> Self {
+        let chunks = indices
+            .par_chunks(PAR_CHUNK)
+            .map(|chunk| self.take(chunk))
+            .collect::<Vec<_>>();
+        let mut taken = Self::new();
+        taken.offsets.reserve(indices.len());
+        for chunk in chunks {
+            let start = taken.data.len();
+            taken.data.extend(chunk.data);
+            taken
+                .offsets
+                .extend(chunk.offsets.into_iter().map(|end| end + start));
+        }
+        taken
+    }
+}
+
+impl<P: Primitive, const SIZE: usize> Select for ConstSizeListArray<P, SIZE> {
+    fn take(&self, indices: &[usize]) -> Self {
+        let mut data = Vec::with_capacity(indices.len() * SIZE);
+        for &index in indices {
+            data.extend_from_slice(&self.data[index * SIZE..(index + 1) * SIZE]);
+        }
+        Self { data }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_take(&self, indices: &[usize]) -> Self {
+        Self {
+            data: indices
+                .par_iter()
+                .flat_map_iter(|&index| self.data[index * SIZE..(index + 1) * SIZE].iter().copied())
+                .collect(),
+        }
+    }
+}
+
+impl<P: Primitive> Select for OptionListArray<P> {
+    fn take(&self, indices: &[usize]) -> Self {
+        let size = self.list_size;
+        let mut data = Vec::with_capacity(indices.len() * size);
+        for &index in indices {
+            data.extend_from_slice(&self.data[index * size..(index + 1) * size]);
+        }
+        Self {
+            validity: take_bits(&self.validity, indices, size),
+            data,
+            list_size: size,
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_take(&self, indices: &[usize]) -> Self {
+        let size = self.list_size;
+        Self {
+            validity: par_take_bits(&self.validity, indices, size),
+            data: indices
+                .par_iter()
+                .flat_map_iter(|&index| self.data[index * size..(index + 1) * size].iter().copied())
+                .collect(),
+            list_size: size,
+        }
+    }
+}
+
+impl<A: Select> Select for OptionArray<A> {
+    #[inline]
+    fn take(&self, indices: &[usize]) -> Self {
+        Self {
+            validity: take_bits(&self.validity, indices, 1),
+            data: self.data.take(indices),
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    fn par_take(&self, indices: &[usize]) -> Self {
+        Self {
+            validity: par_take_bits(&self.validity, indices, 1),
+            data: self.data.par_take(indices),
+        }
+    }
+}
+
+/// The dictionary is cloned as is; only the ids are gathered.
+impl<A: Array + Clone, S: Clone> Select for IdArray<A, S> {
+    #[inline]
+    fn take(&self, indices: &[usize]) -> Self {
+        let mut ids = IdVec::with_width(self.data.width(), indices.len());
+        ids.extend(indices.iter().map(|&index| self.data.get(index).unwrap()));
+        Self::from_parts(self.values.clone(), ids, self.sorted, self.keep_sorted)
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    fn par_take(&self, indices: &[usize]) -> Self {
+        let ids = &self.data;
+        let taken = indices
+            .par_iter()
+            .map(|&index| ids.get(index).unwrap())
+            .collect::<Vec<_>>();
+        Self::from_parts(
+            self.values.clone(),
+            IdVec::from(taken),
+            self.sorted,
+            self.keep_sorted,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Select;
+    use crate::{
+        array::{list::ListArray, option::OptionArray, Array, OptionListArray},
+        bitvec::BitVec,
+        scalar::list::OptionList,
+    };
+
+    #[test]
+    fn take_and_filter() {
+        let mut array = OptionArray::new(ListArray::<u8>::new());
+        for value in [Some("a"), None, Some("bc"), Some("")] {
+            array.push(value.map(Vec::from));
+        }
+        let taken = array.take(&[2, 1, 2]);
+        assert_eq!(taken.len(), 3);
+        assert_eq!(taken.get(0), Some(Some(&b"bc"[..])));
+        assert_eq!(taken.get(1), Some(None));
+
+        let mask = BitVec::from([true, false, false, true]);
+        let filtered = array.filter(&mask.as_slice());
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(filtered.get(1), Some(Some(&b""[..])));
+
+        let mut lists = OptionListArray::new(2);
+        lists.push(OptionList::from([Some(1), None]));
+        lists.push(OptionList::from([None, Some(4)]));
+        let taken = lists.take(&[1, 1, 0]);
+        assert_eq!(taken.get(1).unwrap().get(1), Some(Some(&4)));
+        assert_eq!(taken.get(2).unwrap().get(1), Some(None));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_take_and_filter() {
+        use super::{mask_indices, par_mask_indices};
+        use crate::array::primitive::PrimitiveArray;
+
+        let mut array = OptionArray::new(PrimitiveArray::new());
+        let mut mask = BitVec::default();
+        for row in 0..70_000u32 {
+            array.push((row % 5 != 0).then_some(row));
+            mask.push(row % 3 == 0);
+        }
+        let mask = mask.slice(3..69_995);
+        assert_eq!(par_mask_indices(&mask), mask_indices(&mask));
+
+        let serial = array.filter(&mask);
+        let parallel = array.par_filter(&mask);
+        assert_eq!(parallel.len(), serial.len());
+        assert!((0..serial.len()).all(|row| parallel.get(row) == serial.get(row)));
+
+        let indices = (0..70_000).rev().step_by(3).collect::<Vec<_>>();
+        let mut lists = OptionListArray::new(3);
+        for row in 0..70_000 {
+            lists.push(OptionList::from([
+                Some(row),
+                None,
+                (row % 2 == 0).then_some(row),
+            ]));
+        }
+        let serial = lists.take(&indices);
+        let parallel = lists.par_take(&indices);
+        assert_eq!(parallel.validity, serial.validity);
+        assert_eq!(parallel.data, serial.data);
+    }
+}
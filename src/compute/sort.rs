@@ -0,0 +1,92 @@
+use std::cmp::Ordering;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use super::select::Select;
+use crate::array::Array;
+
+/// Total order over partially ordered values: unordered values such as NaN sort last.
+#[inline]
+pub(crate) fn compare<T: PartialOrd>(lhs: &T, rhs: &T) -> Ordering {
+    #[allow(clippy::eq_op)]
+    lhs.partial_cmp(rhs)
+        .unwrap_or_else(|| (lhs != lhs).cmp(&(rhs != rhs)))
+}
+
+/// Row indices in ascending order of their values; equal values keep their order.
+pub fn argsort<A: Array>(array: &A) -> Vec<usize>
+where
+    for<'a> A::ItemRef<'a>: PartialOrd,
+{
+    let mut indices = (0..array.len()).collect::<Vec<_>>();
+    indices.sort_by(|&lhs, &rhs| unsafe {
+        compare(&array.get_unchecked(lhs), &array.get_unchecked(rhs))
+    });
+    indices
+}
+
+pub fn sort<A: Array + Select>(array: &A) -> A
+where
+    for<'a> A::ItemRef<'a>: PartialOrd,
+{
+    array.take(&argsort(array))
+}
+
+#[cfg(feature = "rayon")]
+pub fn par_argsort<A: Array + Sync>(array: &A) -> Vec<usize>
+where
+    for<'a> A::ItemRef<'a>: PartialOrd,
+{
+    let mut indices = (0..array.len()).collect::<Vec<_>>();
+    indices.par_sort_by(|&lhs, &rhs| unsafe {
+        compare(&array.get_unchecked(lhs), &array.get_unchecked(rhs))
+    });
+    indices
+}
+
+#[cfg(feature = "rayon")]
+pub fn par_sort<A: Array + Select + Sync>(array: &A) -> A
+where
+    for<'a> A::ItemRef<'a>: PartialOrd,
+{
+    array.par_take(&par_argsort(array))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{argsort, sort};
+    use crate::array::{id::IdArray, list::ListArray, primitive::PrimitiveArray, Array};
+
+    #[test]
+    fn sort_arrays() {
+        let mut floats = PrimitiveArray::new();
+        for value in [2.5, f32::NAN, -1.0, 2.5, 0.0] {
+            floats.push(value);
+        }
+        assert_eq!(argsort(&floats), [2, 4, 0, 3, 1]);
+
+        let mut ids = IdArray::new(ListArray::<u8>::new());
+        for value in [Some("b"), None, Some("a"), Some("b")] {
+            ids.push(value.map(Vec::from));
+        }
+        let sorted = sort(&ids);
+        assert_eq!(sorted.get(0), Some(None));
+        assert_eq!(sorted.get(1), Some(Some(&b"a"[..])));
+        assert_eq!(sorted.get(3), Some(Some(&b"b"[..])));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_sort() {
+        use super::{par_argsort, par_sort};
+
+        let mut array = PrimitiveArray::new();
+        for row in 0..50_000u64 {
+            array.push((row * 7919) % 1_000);
+        }
+        assert_eq!(par_argsort(&array), argsort(&array));
+        let sorted = par_sort(&array);
+        assert!(sorted.as_slice().windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{
+    array::primitive::PrimitiveArray,
+    primitive::temporal::{DatePart, Duration, TimeUnit, TimeZone, Timestamp},
+    scalar::list::{OptionList, OptionSlice},
+};
+
+#[inline]
+fn map_valid<P, Q: Default>(slice: &OptionSlice<'_, P>, f: impl Fn(&P) -> Q) -> OptionList<Q> {
+    OptionList {
+        validity: slice.validity.clone().to_vec(),
+        data: slice.data.iter().map(f).collect(),
+    }
+}
+
+/// `part` of every timestamp, in local time of `zone`.
+pub fn extract<U: TimeUnit>(
+    array: &PrimitiveArray<Timestamp<U>>,
+    part: DatePart,
+    zone: TimeZone,
+) -> PrimitiveArray<i64> {
+    PrimitiveArray {
+        data: array.data.iter().map(|ts| ts.get(part, zone)).collect(),
+    }
+}
+
+pub fn extract_valid<U: TimeUnit>(
+    slice: &OptionSlice<'_, Timestamp<U>>,
+    part: DatePart,
+    zone: TimeZone,
+) -> OptionList<i64> {
+    map_valid(slice, |ts| ts.get(part, zone))
+}
+
+pub fn truncate<U: TimeUnit>(
+    array: &PrimitiveArray<Timestamp<U>>,
+    part: DatePart,
+    zone: TimeZone,
+) -> PrimitiveArray<Timestamp<U>> {
+    PrimitiveArray {
+        data: array
+            .data
+            .iter()
+            .map(|ts| ts.truncate(part, zone))
+            .collect(),
+    }
+}
+
+pub fn truncate_valid<U: TimeUnit>(
+    slice: &OptionSlice<'_, Timestamp<U>>,
+    part: DatePart,
+    zone: TimeZone,
+) -> OptionList<Timestamp<U>> {
+    map_valid(slice, |ts| ts.truncate(part, zone))
+}
+
+/// Shifts every timestamp by the duration in the same row.
+pub fn add_duration<U: TimeUnit>(
+    timestamps: &PrimitiveArray<Timestamp<U>>,
+    durations: &PrimitiveArray<Duration<U>>,
+) -> PrimitiveArray<Timestamp<U>> {
+    PrimitiveArray {
+        data: timestamps
+            .data
+            .iter()
+            .zip(&durations.data)
+            .map(|(&ts, &duration)| ts + duration)
+            .collect(),
+    }
+}
+
+/// A row is null if either input is null.
+pub fn add_duration_valid<U: TimeUnit>(
+    timestamps: &OptionSlice<'_, Timestamp<U>>,
+    durations: &OptionSlice<'_, Duration<U>>,
+) -> OptionList<Timestamp<U>> {
+    OptionList {
+        validity: timestamps.validity.clone() & durations.validity.clone(),
+        data: timestamps
+            .data
+            .iter()
+            .zip(durations.data)
+            .map(|(&ts, &duration)| ts + duration)
+            .collect(),
+    }
+}
+
+/// Time elapsed from `rhs` to `lhs` in every row.
+pub fn difference<U: TimeUnit>(
+    lhs: &PrimitiveArray<Timestamp<U>>,
+    rhs: &PrimitiveArray<Timestamp<U>>,
+) -> PrimitiveArray<Duration<U>> {
+    PrimitiveArray {
+        data: lhs
+            .data
+            .iter()
+            .zip(&rhs.data)
+            .map(|(&lhs, &rhs)| lhs - rhs)
+            .collect(),
+    }
+}
+
+pub fn difference_valid<U: TimeUnit>(
+    lhs: &OptionSlice<'_, Timestamp<U>>,
+    rhs: &OptionSlice<'_, Timestamp<U>>,
+) -> OptionList<Duration<U>> {
+    OptionList {
+        validity: lhs.validity.clone() & rhs.validity.clone(),
+        data: lhs
+            .data
+            .iter()
+            .zip(rhs.data)
+            .map(|(&lhs, &rhs)| lhs - rhs)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_duration, difference_valid, extract, truncate_valid};
+    use crate::{
+        array::{primitive::PrimitiveArray, Array, OptionListArray},
+        primitive::temporal::{DatePart, Duration, Second, TimeZone, Timestamp},
+        scalar::list::OptionList,
+    };
+
+    #[test]
+    fn temporal_kernels() {
+        let start = Timestamp::<Second>::from_ymd_hms(2024, 6, 1, 23, 30, 0).unwrap();
+        let mut timestamps = PrimitiveArray::new();
+        let mut durations = PrimitiveArray::new();
+        for hour in 0..3 {
+            timestamps.push(start);
+            durations.push(Duration::from_seconds(hour * 3_600).unwrap());
+        }
+        let shifted = add_duration(&timestamps, &durations);
+        let days = extract(&shifted, DatePart::Day, TimeZone::UTC);
+        assert_eq!(days.as_slice(), &[1, 2, 2]);
+        let hours = extract(&shifted, DatePart::Hour, "-01:00".parse().unwrap());
+        assert_eq!(hours.as_slice(), &[22, 23, 0]);
+
+        // Per-row deltas between consecutive samples, as in a rate computation.
+        let mut samples = OptionListArray::new(4);
+        let at = |minute: i64| Some(start + Duration::from_seconds(minute * 60).unwrap());
+        samples.push(OptionList::from([at(0), at(5), None, at(20)]));
+        let row = samples.get(0).unwrap();
+        let deltas = difference_valid(&row.slice(1..4), &row.slice(0..3));
+        assert_eq!(deltas.get(0), Some(&Duration::from_seconds(300).unwrap()));
+        assert_eq!(deltas.get(1), None);
+        assert_eq!(deltas.get(2), None);
+
+        let hours = truncate_valid(&row, DatePart::Hour, TimeZone::UTC);
+        assert_eq!(hours.get(3).unwrap().to_string(), "2024-06-01T23:00:00Z");
+    }
+}
@@ -0,0 +1,377 @@
+//! Runtime descriptions of array types and values.
+
+use std::fmt;
+
+use crate::primitive::{
+    bf16, f16,
+    temporal::{TimeUnit, TimeUnitType},
+    Date32, Decimal128, Decimal64, Duration, NumericPrimitive, Primitive, PrimitiveType, Timestamp,
+    Total,
+};
+
+/// The type tree of an array, mirroring how the concrete array types nest.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DataType {
+    Boolean,
+    Numeric(PrimitiveType),
+    Decimal64 {
+        precision: u8,
+        scale: u8,
+    },
+    Decimal128 {
+        precision: u8,
+        scale: u8,
+    },
+    Date32,
+    /// No [`TimeZone`](crate::primitive::TimeZone): a data type follows from the Rust type of
+    /// the array, and the zone is column metadata passed to the calendar kernels instead.
+    Timestamp(TimeUnitType),
+    Duration(TimeUnitType),
+    /// `ListArray`: lists of any length.
+    List(Box<DataType>),
+    /// `ConstSizeListArray`: lists of exactly the given length.
+    ConstSizeList(Box<DataType>, usize),
+    /// `OptionListArray`: lists of the given length with nullable elements.
+    OptionList(Box<DataType>, usize),
+    /// `OptionArray`: nullable values.
+    Option(Box<DataType>),
+    /// `IdArray`: ids into a dictionary of distinct values.
+    Id(Box<DataType>),
+}
+
+impl DataType {
+    #[inline]
+    pub fn is_nested(&self) -> bool {
+        !self.is_primitive()
+    }
+
+    #[inline]
+    pub fn is_primitive(&self) -> bool {
+        self.inner().is_none()
+    }
+
+    /// The type one level down, `None` for primitives.
+    #[inline]
+    pub fn inner(&self) -> Option<&DataType> {
+        match self {
+            DataType::List(inner)
+            | DataType::ConstSizeList(inner, _)
+            | DataType::OptionList(inner, _)
+            | DataType::Option(inner)
+            | DataType::Id(inner) => Some(inner),
+            _ => None,
+        }
+    }
+
+    /// The primitive at the bottom of the type tree.
+    #[inline]
+    pub fn leaf(&self) -> &DataType {
+        let mut data_type = self;
+        while let Some(inner) = data_type.inner() {
+            data_type = inner;
+        }
+        data_type
+    }
+
+    /// Whether `get` can return [`ScalarValue::Null`].
+    #[inline]
+    pub fn is_nullable(&self) -> bool {
+        matches!(self, DataType::Option(_) | DataType::Id(_))
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataType::Boolean => f.write_str("bool"),
+            DataType::Numeric(kind) => write!(f, "{}", format!("{kind:?}").to_lowercase()),
+            DataType::Decimal64 { precision, scale } => {
+                write!(f, "decimal64({precision}, {scale})")
+            }
+            DataType::Decimal128 { precision, scale } => {
+                write!(f, "decimal128({precision}, {scale})")
+            }
+            DataType::Date32 => f.write_str("date32"),
+            DataType::Timestamp(unit) => write!(f, "timestamp[{}]", unit.suffix()),
+            DataType::Duration(unit) => write!(f, "duration[{}]", unit.suffix()),
+            DataType::List(inner) => write!(f, "list<{inner}>"),
+            DataType::ConstSizeList(inner, size) => write!(f, "list<{inner}; {size}>"),
+            DataType::OptionList(inner, size) => write!(f, "list<option<{inner}>; {size}>"),
+            DataType::Option(inner) => write!(f, "option<{inner}>"),
+            DataType::Id(inner) => write!(f, "id<{inner}>"),
+        }
+    }
+}
+
+/// One value of any [`DataType`], as returned by `DynArray::get`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScalarValue {
+    Null,
+    Boolean(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Usize(usize),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    Isize(isize),
+    F16(f16),
+    BF16(bf16),
+    F32(f32),
+    F64(f64),
+    /// Raw value, precision and scale.
+    Decimal64(i64, u8, u8),
+    Decimal128(i128, u8, u8),
+    Date32(Date32),
+    Timestamp(i64, TimeUnitType),
+    Duration(i64, TimeUnitType),
+    List(Vec<ScalarValue>),
+}
+
+impl ScalarValue {
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        matches!(self, ScalarValue::Null)
+    }
+
+    /// Numeric values widened to `f64`, `None` for anything else.
+    pub fn to_f64(&self) -> Option<f64> {
+        Some(match *self {
+            ScalarValue::U8(v) => v as f64,
+            ScalarValue::U16(v) => v as f64,
+            ScalarValue::U32(v) => v as f64,
+            ScalarValue::U64(v) => v as f64,
+            ScalarValue::U128(v) => v as f64,
+            ScalarValue::Usize(v) => v as f64,
+            ScalarValue::I8(v) => v as f64,
+            ScalarValue::I16(v) => v as f64,
+            ScalarValue::I32(v) => v as f64,
+            ScalarValue::I64(v) => v as f64,
+            ScalarValue::I128(v) => v as f64,
+            ScalarValue::Isize(v) => v as f64,
+            ScalarValue::F16(v) => v.to_f64(),
+            ScalarValue::BF16(v) => v.to_f64(),
+            ScalarValue::F32(v) => v as f64,
+            ScalarValue::F64(v) => v,
+            _ => return None,
+        })
+    }
+}
+
+/// A primitive whose [`DataType`] is known, so arrays of it can be type-erased.
+pub trait TypedPrimitive: Primitive + fmt::Debug {
+    const DATA_TYPE: DataType;
+
+    fn into_scalar(self) -> ScalarValue;
+}
+
+macro_rules! typed_primitive {
+    ($type:ty, $variant:ident) => {
+        impl TypedPrimitive for $type {
+            const DATA_TYPE: DataType = DataType::Numeric(PrimitiveType::$variant);
+
+            #[inline]
+            fn into_scalar(self) -> ScalarValue {
+                ScalarValue::$variant(self)
+            }
+        }
+    };
+}
+
+typed_primitive!(u8, U8);
+typed_primitive!(u16, U16);
+typed_primitive!(u32, U32);
+typed_primitive!(u64, U64);
+typed_primitive!(u128, U128);
+typed_primitive!(usize, Usize);
+typed_primitive!(i8, I8);
+typed_primitive!(i16, I16);
+typed_primitive!(i32, I32);
+typed_primitive!(i64, I64);
+typed_primitive!(i128, I128);
+typed_primitive!(isize, Isize);
+typed_primitive!(f16, F16);
+typed_primitive!(bf16, BF16);
+typed_primitive!(f32, F32);
+typed_primitive!(f64, F64);
+
+impl TypedPrimitive for bool {
+    const DATA_TYPE: DataType = DataType::Boolean;
+
+    #[inline]
+    fn into_scalar(self) -> ScalarValue {
+        ScalarValue::Boolean(self)
+    }
+}
+
+impl TypedPrimitive for Date32 {
+    const DATA_TYPE: DataType = DataType::Date32;
+
+    #[inline]
+    fn into_scalar(self) -> ScalarValue {
+        ScalarValue::Date32(self)
+    }
+}
+
+impl<const P: u8, const S: u8> TypedPrimitive for Decimal64<P, S> {
+    const DATA_TYPE: DataType = DataType::Decimal64 {
+        precision: P,
+        scale: S,
+    };
+
+    #[inline]
+    fn into_scalar(self) -> ScalarValue {
+        ScalarValue::Decimal64(self.raw(), P, S)
+    }
+}
+
+impl<const P: u8, const S: u8> TypedPrimitive for Decimal128<P, S> {
+    const DATA_TYPE: DataType = DataType::Decimal128 {
+        precision: P,
+        scale: S,
+    };
+
+    #[inline]
+    fn into_scalar(self) -> ScalarValue {
+        ScalarValue::Decimal128(self.raw(), P, S)
+    }
+}
+
+impl<U: TimeUnit> TypedPrimitive for Timestamp<U> {
+    const DATA_TYPE: DataType = DataType::Timestamp(U::TYPE);
+
+    #[inline]
+    fn into_scalar(self) -> ScalarValue {
+        ScalarValue::Timestamp(self.raw(), U::TYPE)
+    }
+}
+
+impl<U: TimeUnit> TypedPrimitive for Duration<U> {
+    const DATA_TYPE: DataType = DataType::Duration(U::TYPE);
+
+    #[inline]
+    fn into_scalar(self) -> ScalarValue {
+        ScalarValue::Duration(self.raw(), U::TYPE)
+    }
+}
+
+/// Shares the data type of the wrapped value.
+impl<P: TypedPrimitive + NumericPrimitive> TypedPrimitive for Total<P> {
+    const DATA_TYPE: DataType = P::DATA_TYPE;
+
+    #[inline]
+    fn into_scalar(self) -> ScalarValue {
+        self.0.into_scalar()
+    }
+}
+
+/// Evaluates `$body` with `$T` aliased to the Rust type of a [`PrimitiveType`]. With a second
+/// `float =>` body, float types evaluate that one instead, for code that needs `Eq` or `Hash`.
+macro_rules! with_numeric {
+    ($kind:expr, $T:ident => $body:expr) => {
+        $crate::datatype::with_numeric!($kind, $T => $body, float => $body)
+    };
+    ($kind:expr, $T:ident => $int:expr, float => $float:expr) => {
+        match $kind {
+            $crate::primitive::PrimitiveType::U8 => {
+                type $T = u8;
+                $int
+            }
+            $crate::primitive::PrimitiveType::U16 => {
+                type $T = u16;
+                $int
+            }
+            $crate::primitive::PrimitiveType::U32 => {
+                type $T = u32;
+                $int
+            }
+            $crate::primitive::PrimitiveType::U64 => {
+                type $T = u64;
+                $int
+            }
+            $crate::primitive::PrimitiveType::U128 => {
+                type $T = u128;
+                $int
+            }
+            $crate::primitive::PrimitiveType::Usize => {
+                type $T = usize;
+                $int
+            }
+            $crate::primitive::PrimitiveType::I8 => {
+                type $T = i8;
+                $int
+            }
+            $crate::primitive::PrimitiveType::I16 => {
+                type $T = i16;
+                $int
+            }
+            $crate::primitive::PrimitiveType::I32 => {
+                type $T = i32;
+                $int
+            }
+            $crate::primitive::PrimitiveType::I64 => {
+                type $T = i64;
+                $int
+            }
+            $crate::primitive::PrimitiveType::I128 => {
+                type $T = i128;
+                $int
+            }
+            $crate::primitive::PrimitiveType::Isize => {
+                type $T = isize;
+                $int
+            }
+            $crate::primitive::PrimitiveType::F16 => {
+                type $T = $crate::primitive::f16;
+                $float
+            }
+            $crate::primitive::PrimitiveType::BF16 => {
+                type $T = $crate::primitive::bf16;
+                $float
+            }
+            $crate::primitive::PrimitiveType::F32 => {
+                type $T = f32;
+                $float
+            }
+            $crate::primitive::PrimitiveType::F64 => {
+                type $T = f64;
+                $float
+            }
+        }
+    };
+}
+
+pub(crate) use with_numeric;
+
+#[cfg(test)]
+mod tests {
+    use super::{DataType, ScalarValue, TypedPrimitive};
+    use crate::primitive::{
+        temporal::{Millisecond, TimeUnit},
+        Decimal64, PrimitiveType, Timestamp,
+    };
+
+    #[test]
+    fn type_tree() {
+        let data_type = DataType::Option(Box::new(DataType::OptionList(
+            Box::new(DataType::Numeric(PrimitiveType::BF16)),
+            4,
+        )));
+        assert_eq!(data_type.to_string(), "option<list<option<bf16>; 4>>");
+        assert_eq!(data_type.leaf(), &DataType::Numeric(PrimitiveType::BF16));
+        assert!(data_type.is_nested() && data_type.is_nullable());
+        assert_eq!(
+            Decimal64::<10, 2>::DATA_TYPE.to_string(),
+            "decimal64(10, 2)"
+        );
+        assert_eq!(
+            Timestamp::<Millisecond>::from_raw(5).into_scalar(),
+            ScalarValue::Timestamp(5, Millisecond::TYPE)
+        );
+    }
+}
@@ -0,0 +1,5 @@
+mod reader;
+mod writer;
+
+pub use reader::{Reader, ReaderOptions};
+pub use writer::{Writer, WriterOptions};
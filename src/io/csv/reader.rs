@@ -0,0 +1,429 @@
+use std::{collections::VecDeque, io::BufRead, mem, str::FromStr};
+
+use hashbrown::HashSet;
+
+use crate::{
+    array::Array,
+    io::{
+        json::{push_value, Value},
+        Column, ColumnType, Error, Result,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct ReaderOptions {
+    pub delimiter: u8,
+    pub quote: u8,
+    pub has_header: bool,
+    /// Unquoted fields equal to one of these tokens are read as null.
+    pub null_tokens: Vec<Vec<u8>>,
+    pub batch_size: usize,
+    /// Number of records sampled for schema inference.
+    pub infer_rows: usize,
+    /// Text columns whose distinct / non-null ratio in the sample is at most this value are
+    /// dictionary encoded.
+    pub dictionary_ratio: f64,
+    /// Skips inference entirely.
+    pub schema: Option<Vec<(String, ColumnType)>>,
+    /// Replaces the inferred type of the named columns.
+    pub overrides: Vec<(String, ColumnType)>,
+}
+
+impl Default for ReaderOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            has_header: true,
+            null_tokens: vec![Vec::new()],
+            batch_size: 8192,
+            infer_rows: 1024,
+            dictionary_ratio: 0.5,
+            schema: None,
+            overrides: Vec::new(),
+        }
+    }
+}
+
+/// A field and whether it was quoted, which exempts it from the null tokens.
+type Field = (Vec<u8>, bool);
+
+type Record = (usize, Vec<Field>);
+
+pub struct Reader<R> {
+    inner: R,
+    options: ReaderOptions,
+    schema: Vec<(String, ColumnType)>,
+    sample: VecDeque<Record>,
+    line: usize,
+}
+
+impl<R: BufRead> Reader<R> {
+    pub fn new(inner: R, options: ReaderOptions) -> Result<Self> {
+        let mut this = Self {
+            inner,
+            options,
+            schema: Vec::new(),
+            sample: VecDeque::new(),
+            line: 0,
+        };
+
+        let header = if this.options.has_header {
+            this.read_record()?.map(|(_, fields)| {
+                fields
+                    .into_iter()
+                    .map(|(name, _)| String::from_utf8_lossy(&name).into_owned())
+                    .collect::<Vec<_>>()
+            })
+        } else {
+            None
+        };
+
+        this.schema = match this.options.schema.take() {
+            Some(schema) => {
+                if let Some(header) = &header {
+                    if header.len() != schema.len() {
+                        return Err(Error::Schema(format!(
+                            "header has {} columns but schema has {}",
+                            header.len(),
+                            schema.len()
+                        )));
+                    }
+                }
+                schema
+            }
+            None => this.infer(header)?,
+        };
+
+        for (name, column_type) in &this.options.overrides {
+            match this.schema.iter_mut().find(|(n, _)| n == name) {
+                Some((_, t)) => *t = *column_type,
+                None => return Err(Error::Schema(format!("unknown column {name}"))),
+            }
+        }
+
+        Ok(this)
+    }
+
+    #[inline]
+    pub fn schema(&self) -> &[(String, ColumnType)] {
+        &self.schema
+    }
+
+    pub fn next_batch(&mut self) -> Result<Option<Vec<Column>>> {
+        let mut columns = self
+            .schema
+            .iter()
+            .map(|(_, column_type)| Column::new(*column_type))
+            .collect::<Vec<_>>();
+
+        let mut rows = 0;
+        while rows < self.options.batch_size {
+            let (line, fields) = match self.sample.pop_front() {
+                Some(record) => record,
+                None => match self.read_record()? {
+                    Some(record) => record,
+                    None => break,
+                },
+            };
+            if fields.len() != columns.len() {
+                return Err(Error::Parse {
+                    line,
+                    column: fields.len().min(columns.len()),
+                    message: format!("expected {} fields, found {}", columns.len(), fields.len()),
+                });
+            }
+            for (id, (column, (field, quoted))) in columns.iter_mut().zip(fields).enumerate() {
+                if !quoted && self.options.null_tokens.contains(&field) {
+                    column.push_null();
+                } else {
+                    push_field(column, field).map_err(|message| Error::Parse {
+                        line,
+                        column: id,
+                        message,
+                    })?;
+                }
+            }
+            rows += 1;
+        }
+
+        Ok(if rows == 0 { None } else { Some(columns) })
+    }
+
+    fn infer(&mut self, header: Option<Vec<String>>) -> Result<Vec<(String, ColumnType)>> {
+        while self.sample.len() < self.options.infer_rows {
+            match self.read_record()? {
+                Some(record) => self.sample.push_back(record),
+                None => break,
+            }
+        }
+
+        let names = match header {
+            Some(names) => names,
+            None => {
+                let width = self.sample.front().map_or(0, |(_, fields)| fields.len());
+                (0..width).map(|id| format!("column_{id}")).collect()
+            }
+        };
+
+        let schema = names
+            .into_iter()
+            .enumerate()
+            .map(|(id, name)| {
+                let mut inferred = None;
+                let mut distinct = HashSet::new();
+                let mut count = 0;
+                let values = self
+                    .sample
+                    .iter()
+                    .filter_map(|(_, fields)| fields.get(id))
+                    .filter(|(field, quoted)| *quoted || !self.options.null_tokens.contains(field))
+                    .map(|(field, _)| field);
+                for value in values {
+                    inferred = Some(merge(inferred, infer_value(value)));
+                    distinct.insert(value.as_slice());
+                    count += 1;
+                }
+                let column_type = match inferred.unwrap_or(ColumnType::Utf8) {
+                    ColumnType::Utf8
+                        if count > 0
+                            && distinct.len() as f64 / count as f64
+                                <= self.options.dictionary_ratio =>
+                    {
+                        ColumnType::Dictionary
+                    }
+                    column_type => column_type,
+                };
+                (name, column_type)
+            })
+            .collect();
+        Ok(schema)
+    }
+
+    fn read_record(&mut self) -> Result<Option<Record>> {
+        let mut fields = Vec::new();
+        let mut field = Vec::new();
+        let mut quoted = false;
+        let mut in_quotes = false;
+        let mut line = Vec::new();
+        let mut start = self.line + 1;
+
+        loop {
+            line.clear();
+            if self.inner.read_until(b'\n', &mut line)? == 0 {
+                if in_quotes {
+                    return Err(Error::Parse {
+                        line: start,
+                        column: fields.len(),
+                        message: "unterminated quoted field".into(),
+                    });
+                }
+                if fields.is_empty() && field.is_empty() && !quoted {
+                    return Ok(None);
+                }
+                fields.push((field, quoted));
+                return Ok(Some((start, fields)));
+            }
+            self.line += 1;
+            if !in_quotes && (line == b"\n" || line == b"\r\n") {
+                start = self.line + 1;
+                continue;
+            }
+
+            let mut bytes = line.iter().copied().peekable();
+            while let Some(byte) = bytes.next() {
+                if in_quotes {
+                    if byte != self.options.quote {
+                        field.push(byte);
+                    } else if bytes.peek() == Some(&self.options.quote) {
+                        field.push(byte);
+                        bytes.next();
+                    } else {
+                        in_quotes = false;
+                    }
+                } else if byte == self.options.quote {
+                    in_quotes = true;
+                    quoted = true;
+                } else if byte == self.options.delimiter {
+                    fields.push((mem::take(&mut field), mem::take(&mut quoted)));
+                } else if byte == b'\r' && bytes.peek() == Some(&b'\n') {
+                    // the following newline ends the record
+                } else if byte == b'\n' {
+                    fields.push((mem::take(&mut field), mem::take(&mut quoted)));
+                    return Ok(Some((start, fields)));
+                } else {
+                    field.push(byte);
+                }
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = Result<Vec<Column>>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_batch().transpose()
+    }
+}
+
+#[inline]
+fn parse_bool(value: &[u8]) -> Option<bool> {
+    if value.eq_ignore_ascii_case(b"true") {
+        Some(true)
+    } else if value.eq_ignore_ascii_case(b"false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[inline]
+fn parse<T: FromStr>(value: &[u8]) -> Option<T> {
+    std::str::from_utf8(value).ok()?.parse().ok()
+}
+
+fn infer_value(value: &[u8]) -> ColumnType {
+    if parse_bool(value).is_some() {
+        ColumnType::Boolean
+    } else if parse::<i64>(value).is_some() {
+        ColumnType::Int64
+    } else if parse::<f64>(value).is_some() {
+        ColumnType::Float64
+    } else {
+        ColumnType::Utf8
+    }
+}
+
+fn merge(lhs: Option<ColumnType>, rhs: ColumnType) -> ColumnType {
+    match (lhs, rhs) {
+        (None, rhs) => rhs,
+        (Some(lhs), rhs) if lhs == rhs => lhs,
+        (
+            Some(ColumnType::Int64 | ColumnType::Float64),
+            ColumnType::Int64 | ColumnType::Float64,
+        ) => ColumnType::Float64,
+        _ => ColumnType::Utf8,
+    }
+}
+
+fn push_field(column: &mut Column, field: Vec<u8>) -> std::result::Result<(), String> {
+    match column {
+        Column::Boolean(array) => array.push(Some(
+            parse_bool(&field).ok_or_else(|| invalid("boolean", &field))?,
+        )),
+        Column::Int64(array) => array.push(Some(
+            parse(&field).ok_or_else(|| invalid("integer", &field))?,
+        )),
+        Column::Float64(array) => {
+            array.push(Some(parse(&field).ok_or_else(|| invalid("float", &field))?))
+        }
+        Column::Utf8(array) => array.push(Some(field)),
+        Column::Dictionary(array) => array.push(Some(field)),
+        // lists are written as JSON arrays
+        _ => return push_value(column, Value::parse(&field)?),
+    }
+    Ok(())
+}
+
+#[inline]
+fn invalid(kind: &str, field: &[u8]) -> String {
+    format!("invalid {kind} {:?}", String::from_utf8_lossy(field))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Reader, ReaderOptions};
+    use crate::{
+        array::Array,
+        io::{Column, ColumnType},
+    };
+
+    const DATA: &str = concat!(
+        "id,score,ok,city,note\n",
+        "1,0.5,true,paris,\"hello, world\"\n",
+        "2,,false,paris,\"say \"\"hi\"\"\"\n",
+        "3,2,TRUE,berlin,\"multi\r\nline\"\n",
+        "4,1.5,false,paris,plain\r\n",
+    );
+
+    #[test]
+    fn infer_schema() {
+        let mut reader = Reader::new(DATA.as_bytes(), ReaderOptions::default()).unwrap();
+        assert_eq!(
+            reader.schema(),
+            &[
+                ("id".into(), ColumnType::Int64),
+                ("score".into(), ColumnType::Float64),
+                ("ok".into(), ColumnType::Boolean),
+                ("city".into(), ColumnType::Dictionary),
+                ("note".into(), ColumnType::Utf8),
+            ]
+        );
+
+        let columns = reader.next_batch().unwrap().unwrap();
+        assert!(reader.next_batch().unwrap().is_none());
+        match &columns[1] {
+            Column::Float64(array) => {
+                assert_eq!(array.get(0), Some(Some(&0.5)));
+                assert_eq!(array.get(1), Some(None));
+                assert_eq!(array.get(2), Some(Some(&2.0)));
+            }
+            _ => unreachable!(),
+        }
+        match &columns[3] {
+            Column::Dictionary(array) => {
+                assert_eq!(array.get(2), Some(Some("berlin".as_ref())));
+                assert_eq!(array.lookup_id("paris".as_ref()), Some(1));
+            }
+            _ => unreachable!(),
+        }
+        match &columns[4] {
+            Column::Utf8(array) => {
+                assert_eq!(array.get(0), Some(Some("hello, world".as_ref())));
+                assert_eq!(array.get(1), Some(Some("say \"hi\"".as_ref())));
+                assert_eq!(array.get(2), Some(Some("multi\r\nline".as_ref())));
+                assert_eq!(array.get(3), Some(Some("plain".as_ref())));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn overrides_and_batches() {
+        let options = ReaderOptions {
+            has_header: false,
+            null_tokens: vec![b"NA".to_vec()],
+            batch_size: 2,
+            overrides: vec![("column_0".into(), ColumnType::Float64)],
+            ..Default::default()
+        };
+        let reader = Reader::new("1,a\n2,NA\n\n3,\"NA\"\n".as_bytes(), options).unwrap();
+        assert_eq!(reader.schema()[0].1, ColumnType::Float64);
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0][1].len(), 2);
+        assert_eq!(batches[1][0].len(), 1);
+        match &batches[0][1] {
+            Column::Utf8(array) => assert_eq!(array.get(1), Some(None)),
+            _ => unreachable!(),
+        }
+        match &batches[1][1] {
+            Column::Utf8(array) => assert_eq!(array.get(0), Some(Some("NA".as_ref()))),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn parse_error() {
+        let options = ReaderOptions {
+            schema: Some(vec![("a".into(), ColumnType::Int64)]),
+            ..Default::default()
+        };
+        let mut reader = Reader::new("a\n1\nx\n".as_bytes(), options).unwrap();
+        let err = reader.next_batch().unwrap_err();
+        assert!(err.to_string().contains("line 3"));
+    }
+}
@@ -0,0 +1,227 @@
+use std::io::Write;
+
+use crate::{
+    batch::RecordBatch,
+    io::{
+        encode::{batch_columns, check_lengths, EncodeColumn},
+        Error, Result,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct WriterOptions {
+    pub delimiter: u8,
+    pub quote: u8,
+    pub has_header: bool,
+    /// Text written for null values.
+    pub null: Vec<u8>,
+}
+
+impl Default for WriterOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            has_header: true,
+            null: Vec::new(),
+        }
+    }
+}
+
+pub struct Writer<W> {
+    inner: W,
+    options: WriterOptions,
+    header_written: bool,
+    buffer: Vec<u8>,
+    field: Vec<u8>,
+}
+
+impl<W: Write> Writer<W> {
+    #[inline]
+    pub fn new(inner: W, options: WriterOptions) -> Self {
+        Self {
+            inner,
+            options,
+            header_written: false,
+            buffer: Vec::new(),
+            field: Vec::new(),
+        }
+    }
+
+    /// Writes all rows of the batch under its field names; see [`DynArray::as_encode`] for the
+    /// column types that can be written.
+    ///
+    /// [`DynArray::as_encode`]: crate::array::DynArray::as_encode
+    #[inline]
+    pub fn write_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.write(&batch_columns(batch)?)
+    }
+
+    /// Writes all rows of the columns, preceded by the header on the first call.
+    pub fn write(&mut self, columns: &[(&str, &dyn EncodeColumn)]) -> Result<()> {
+        let rows = check_lengths(columns)?;
+
+        self.buffer.clear();
+        if self.options.has_header && !self.header_written {
+            for (id, (name, _)) in columns.iter().enumerate() {
+                if id != 0 {
+                    self.buffer.push(self.options.delimiter);
+                }
+                self.field.clear();
+                self.field.extend_from_slice(name.as_bytes());
+                self.write_field(false);
+            }
+            self.buffer.push(b'\n');
+        }
+        self.header_written = true;
+
+        for row in 0..rows {
+            for (id, (_, column)) in columns.iter().enumerate() {
+                if id != 0 {
+                    self.buffer.push(self.options.delimiter);
+                }
+                if column.is_null(row) {
+                    self.buffer.extend_from_slice(&self.options.null);
+                } else {
+                    self.field.clear();
+                    column.write_csv(row, &mut self.field)?;
+                    self.write_field(true);
+                }
+            }
+            self.buffer.push(b'\n');
+        }
+
+        self.inner.write_all(&self.buffer)?;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn flush(&mut self) -> Result<()> {
+        self.inner.flush().map_err(Error::from)
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Appends `self.field` to the buffer, quoting it when it contains special characters or
+    /// would otherwise read back as null.
+    fn write_field(&mut self, is_value: bool) {
+        let quote = self.options.quote;
+        let needs_quotes = self
+            .field
+            .iter()
+            .any(|&b| b == self.options.delimiter || b == quote || b == b'\n' || b == b'\r')
+            || (is_value && self.field == self.options.null);
+
+        if !needs_quotes {
+            self.buffer.extend_from_slice(&self.field);
+            return;
+        }
+        self.buffer.push(quote);
+        for &b in &self.field {
+            if b == quote {
+                self.buffer.push(quote);
+            }
+            self.buffer.push(b);
+        }
+        self.buffer.push(quote);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Writer, WriterOptions};
+    use crate::{
+        array::{
+            id::IdArray, list::ListArray, primitive::PrimitiveArray, Array, DynArray, OptionArray,
+        },
+        batch::RecordBatch,
+        io::{
+            csv::{Reader, ReaderOptions},
+            Column,
+        },
+        primitive::{Date32, Decimal64},
+    };
+
+    #[test]
+    fn write_and_read_back() {
+        let mut ids = PrimitiveArray::new();
+        let mut names = OptionArray::new(ListArray::<u8>::new());
+        let mut tags = IdArray::new(ListArray::<u8>::new());
+        for (id, name, tag) in [
+            (1, Some("plain"), Some("a")),
+            (2, Some("with, comma"), None),
+            (3, Some(""), Some("a")),
+            (4, None, Some("quote\"d")),
+        ] {
+            ids.push(id);
+            names.push(name.map(Vec::from));
+            tags.push(tag.map(Vec::from));
+        }
+
+        let mut writer = Writer::new(Vec::new(), WriterOptions::default());
+        writer
+            .write(&[("id", &ids), ("name", &names), ("tag", &tags)])
+            .unwrap();
+        let output = writer.into_inner();
+        assert_eq!(
+            String::from_utf8(output.clone()).unwrap(),
+            "id,name,tag\n1,plain,a\n2,\"with, comma\",\n3,\"\",a\n4,,\"quote\"\"d\"\n"
+        );
+
+        let mut reader = Reader::new(&output[..], ReaderOptions::default()).unwrap();
+        let columns = reader.next_batch().unwrap().unwrap();
+        assert_eq!(columns.len(), 3);
+        match &columns[0] {
+            Column::Int64(array) => {
+                assert!(array.iter().map(Option::unwrap).eq(ids.iter()));
+            }
+            _ => unreachable!(),
+        }
+        for (column, expected) in columns[1..].iter().zip([names.clone(), tags.decode()]) {
+            match column {
+                Column::Utf8(array) => assert!(array.iter().eq(expected.iter())),
+                _ => unreachable!(),
+            }
+        }
+
+        let batch = RecordBatch::from_columns([
+            ("id", DynArray::new(ids)),
+            ("name", DynArray::new(names)),
+            ("tag", DynArray::new(tags)),
+        ])
+        .unwrap();
+        let mut writer = Writer::new(Vec::new(), WriterOptions::default());
+        writer.write_batch(&batch).unwrap();
+        assert_eq!(writer.into_inner(), output);
+    }
+
+    #[test]
+    fn decimal_and_date_columns() {
+        let mut prices = PrimitiveArray::new();
+        for price in ["3.5", "-0.25"] {
+            prices.push(price.parse::<Decimal64<8, 2>>().unwrap());
+        }
+        let mut writer = Writer::new(Vec::new(), WriterOptions::default());
+        let mut days = PrimitiveArray::new();
+        days.push(Date32::from_ymd(2024, 3, 9).unwrap());
+        days.push(Date32::from_days(0));
+        writer.write(&[("price", &prices), ("day", &days)]).unwrap();
+        assert_eq!(
+            writer.into_inner(),
+            b"price,day\n3.50,2024-03-09\n-0.25,1970-01-01\n"
+        );
+    }
+
+    #[test]
+    fn length_mismatch() {
+        let mut lhs = PrimitiveArray::new();
+        lhs.push(1u32);
+        let rhs = PrimitiveArray::<u32>::new();
+        let mut writer = Writer::new(Vec::new(), WriterOptions::default());
+        assert!(writer.write(&[("lhs", &lhs), ("rhs", &rhs)]).is_err());
+    }
+}
@@ -0,0 +1,476 @@
+use std::{hash::Hash, io::Write};
+
+use super::{Column, Error, Result};
+use crate::{
+    array::{
+        id::IdArray, list::ListArray, option::OptionArray, primitive::PrimitiveArray, Array,
+        DynArray, OptionListArray,
+    },
+    batch::RecordBatch,
+    datatype::{with_numeric, DataType, TypedPrimitive},
+    primitive::{
+        bf16, f16,
+        temporal::{Microsecond, Millisecond, Nanosecond, Second, TimeUnit, TimeUnitType},
+        Date32, Decimal128, Decimal64, Duration, NumericPrimitive, Primitive, PrimitiveType,
+        Timestamp, Total,
+    },
+    scalar::list::OptionSlice,
+};
+
+pub trait TextPrimitive: Primitive {
+    fn write_text(&self, out: &mut Vec<u8>);
+
+    #[inline]
+    fn write_json(&self, out: &mut Vec<u8>) -> Result<()> {
+        self.write_text(out);
+        Ok(())
+    }
+
+    /// Lists are rendered as JSON arrays in both formats unless the primitive overrides this.
+    #[inline]
+    fn write_text_list(list: &[Self], out: &mut Vec<u8>) -> Result<()> {
+        Self::write_json_list(list, out)
+    }
+
+    #[inline]
+    fn write_json_list(list: &[Self], out: &mut Vec<u8>) -> Result<()> {
+        write_json_array(list.iter().map(Some), out)
+    }
+}
+
+impl TextPrimitive for bool {
+    #[inline]
+    fn write_text(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(if *self { b"true" } else { b"false" })
+    }
+}
+
+macro_rules! integer_text {
+    ($type:ty) => {
+        impl TextPrimitive for $type {
+            #[inline]
+            fn write_text(&self, out: &mut Vec<u8>) {
+                write!(out, "{}", self).unwrap();
+            }
+        }
+    };
+}
+
+macro_rules! float_text {
+    ($type:ty) => {
+        impl TextPrimitive for $type {
+            #[inline]
+            fn write_text(&self, out: &mut Vec<u8>) {
+                write!(out, "{}", self).unwrap();
+            }
+
+            #[inline]
+            fn write_json(&self, out: &mut Vec<u8>) -> Result<()> {
+                if self.is_finite() {
+                    self.write_text(out)
+                } else {
+                    out.extend_from_slice(b"null")
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+integer_text!(u16);
+integer_text!(u32);
+integer_text!(u64);
+integer_text!(u128);
+integer_text!(usize);
+integer_text!(i8);
+integer_text!(i16);
+integer_text!(i32);
+integer_text!(i64);
+integer_text!(i128);
+integer_text!(isize);
+float_text!(f32);
+float_text!(f64);
+float_text!(f16);
+float_text!(bf16);
+
+impl<P: TextPrimitive + NumericPrimitive> TextPrimitive for Total<P> {
+    #[inline]
+    fn write_text(&self, out: &mut Vec<u8>) {
+        self.0.write_text(out)
+    }
+
+    #[inline]
+    fn write_json(&self, out: &mut Vec<u8>) -> Result<()> {
+        self.0.write_json(out)
+    }
+}
+
+macro_rules! decimal_text {
+    ($name:ident) => {
+        /// Decimals are written with all digits of their scale, also as JSON numbers.
+        impl<const P: u8, const S: u8> TextPrimitive for $name<P, S> {
+            #[inline]
+            fn write_text(&self, out: &mut Vec<u8>) {
+                write!(out, "{}", self).unwrap();
+            }
+        }
+    };
+}
+
+decimal_text!(Decimal64);
+decimal_text!(Decimal128);
+
+macro_rules! temporal_text {
+    ($type:ty $(, $unit:ident)?) => {
+        /// Dates and times are JSON strings.
+        impl$(<$unit: TimeUnit>)? TextPrimitive for $type {
+            #[inline]
+            fn write_text(&self, out: &mut Vec<u8>) {
+                write!(out, "{}", self).unwrap();
+            }
+
+            #[inline]
+            fn write_json(&self, out: &mut Vec<u8>) -> Result<()> {
+                write_json_string(self.to_string().as_bytes(), out)
+            }
+        }
+    };
+}
+
+temporal_text!(Date32);
+temporal_text!(Timestamp<U>, U);
+temporal_text!(Duration<U>, U);
+
+/// Lists of bytes are strings.
+impl TextPrimitive for u8 {
+    #[inline]
+    fn write_text(&self, out: &mut Vec<u8>) {
+        write!(out, "{}", self).unwrap();
+    }
+
+    #[inline]
+    fn write_text_list(list: &[Self], out: &mut Vec<u8>) -> Result<()> {
+        out.extend_from_slice(list);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_json_list(list: &[Self], out: &mut Vec<u8>) -> Result<()> {
+        write_json_string(list, out)
+    }
+}
+
+/// Fails on bytes that are not UTF-8, which a JSON string cannot hold.
+pub fn write_json_string(bytes: &[u8], out: &mut Vec<u8>) -> Result<()> {
+    let text = std::str::from_utf8(bytes).map_err(|e| {
+        Error::Format(format!(
+            "cannot write a JSON string from invalid UTF-8: {e}"
+        ))
+    })?;
+    out.push(b'"');
+    for c in text.chars() {
+        match c {
+            '"' => out.extend_from_slice(b"\\\""),
+            '\\' => out.extend_from_slice(b"\\\\"),
+            '\n' => out.extend_from_slice(b"\\n"),
+            '\r' => out.extend_from_slice(b"\\r"),
+            '\t' => out.extend_from_slice(b"\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => {
+                let mut buf = [0; 4];
+                out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            }
+        }
+    }
+    out.push(b'"');
+    Ok(())
+}
+
+fn write_json_array<'a, P: TextPrimitive>(
+    items: impl Iterator<Item = Option<&'a P>>,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    out.push(b'[');
+    for (id, item) in items.enumerate() {
+        if id != 0 {
+            out.push(b',');
+        }
+        match item {
+            Some(value) => value.write_json(out)?,
+            None => out.extend_from_slice(b"null"),
+        }
+    }
+    out.push(b']');
+    Ok(())
+}
+
+/// Renders a single item of an array.
+pub trait Encode {
+    #[inline]
+    fn is_null(&self) -> bool {
+        false
+    }
+
+    /// Writes the unquoted CSV text of the item; quoting is left to the writer.
+    fn write_csv(&self, out: &mut Vec<u8>) -> Result<()>;
+
+    fn write_json(&self, out: &mut Vec<u8>) -> Result<()>;
+}
+
+impl<P: TextPrimitive> Encode for &P {
+    #[inline]
+    fn write_csv(&self, out: &mut Vec<u8>) -> Result<()> {
+        self.write_text(out);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_json(&self, out: &mut Vec<u8>) -> Result<()> {
+        TextPrimitive::write_json(*self, out)
+    }
+}
+
+impl<P: TextPrimitive> Encode for &[P] {
+    #[inline]
+    fn write_csv(&self, out: &mut Vec<u8>) -> Result<()> {
+        P::write_text_list(self, out)
+    }
+
+    #[inline]
+    fn write_json(&self, out: &mut Vec<u8>) -> Result<()> {
+        P::write_json_list(self, out)
+    }
+}
+
+impl<P: TextPrimitive, const SIZE: usize> Encode for &[P; SIZE] {
+    #[inline]
+    fn write_csv(&self, out: &mut Vec<u8>) -> Result<()> {
+        self.write_json(out)
+    }
+
+    #[inline]
+    fn write_json(&self, out: &mut Vec<u8>) -> Result<()> {
+        write_json_array(self.iter().map(Some), out)
+    }
+}
+
+impl<P: TextPrimitive> Encode for OptionSlice<'_, P> {
+    #[inline]
+    fn write_csv(&self, out: &mut Vec<u8>) -> Result<()> {
+        self.write_json(out)
+    }
+
+    #[inline]
+    fn write_json(&self, out: &mut Vec<u8>) -> Result<()> {
+        write_json_array((0..self.data.len()).map(|id| self.get(id).unwrap()), out)
+    }
+}
+
+impl<E: Encode> Encode for Option<E> {
+    #[inline]
+    fn is_null(&self) -> bool {
+        self.as_ref().is_none_or(E::is_null)
+    }
+
+    #[inline]
+    fn write_csv(&self, out: &mut Vec<u8>) -> Result<()> {
+        match self {
+            Some(item) => item.write_csv(out),
+            None => Ok(()),
+        }
+    }
+
+    #[inline]
+    fn write_json(&self, out: &mut Vec<u8>) -> Result<()> {
+        match self {
+            Some(item) => item.write_json(out),
+            None => {
+                out.extend_from_slice(b"null");
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Object safe view of an array used by the writers, so that columns of different types can be
+/// written together.
+pub trait EncodeColumn {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn is_null(&self, row: usize) -> bool;
+
+    fn write_csv(&self, row: usize, out: &mut Vec<u8>) -> Result<()>;
+
+    fn write_json(&self, row: usize, out: &mut Vec<u8>) -> Result<()>;
+}
+
+impl<A: Array> EncodeColumn for A
+where
+    for<'a> A::ItemRef<'a>: Encode,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        Array::len(self)
+    }
+
+    #[inline]
+    fn is_null(&self, row: usize) -> bool {
+        self.get(row).is_none_or(|item| item.is_null())
+    }
+
+    #[inline]
+    fn write_csv(&self, row: usize, out: &mut Vec<u8>) -> Result<()> {
+        match self.get(row) {
+            Some(item) => item.write_csv(out),
+            None => Ok(()),
+        }
+    }
+
+    #[inline]
+    fn write_json(&self, row: usize, out: &mut Vec<u8>) -> Result<()> {
+        match self.get(row) {
+            Some(item) => item.write_json(out),
+            None => {
+                out.extend_from_slice(b"null");
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Column {
+    #[inline]
+    pub fn as_encode(&self) -> &dyn EncodeColumn {
+        match self {
+            Column::Boolean(array) => array,
+            Column::Int64(array) => array,
+            Column::Float64(array) => array,
+            Column::Utf8(array) => array,
+            Column::Dictionary(array) => array,
+            Column::Int64List(array) => array,
+            Column::Float64List(array) => array,
+            Column::FixedInt64List(array) => array,
+            Column::FixedFloat64List(array) => array,
+        }
+    }
+}
+
+impl EncodeColumn for Column {
+    #[inline]
+    fn len(&self) -> usize {
+        Column::len(self)
+    }
+
+    #[inline]
+    fn is_null(&self, row: usize) -> bool {
+        self.as_encode().is_null(row)
+    }
+
+    #[inline]
+    fn write_csv(&self, row: usize, out: &mut Vec<u8>) -> Result<()> {
+        self.as_encode().write_csv(row, out)
+    }
+
+    #[inline]
+    fn write_json(&self, row: usize, out: &mut Vec<u8>) -> Result<()> {
+        self.as_encode().write_json(row, out)
+    }
+}
+
+impl DynArray {
+    /// The array as a writer column, found by its data type. `None` for decimals, const size
+    /// lists and dictionaries with a custom hasher, whose Rust types cannot be named from the data
+    /// type alone.
+    pub fn as_encode(&self) -> Option<&dyn EncodeColumn> {
+        let data_type = self.data_type();
+        match data_type.leaf() {
+            DataType::Boolean => encode_hashable::<bool>(self),
+            DataType::Date32 => encode_hashable::<Date32>(self),
+            DataType::Timestamp(unit) => match unit {
+                TimeUnitType::Second => encode_hashable::<Timestamp<Second>>(self),
+                TimeUnitType::Millisecond => encode_hashable::<Timestamp<Millisecond>>(self),
+                TimeUnitType::Microsecond => encode_hashable::<Timestamp<Microsecond>>(self),
+                TimeUnitType::Nanosecond => encode_hashable::<Timestamp<Nanosecond>>(self),
+            },
+            DataType::Duration(unit) => match unit {
+                TimeUnitType::Second => encode_hashable::<Duration<Second>>(self),
+                TimeUnitType::Millisecond => encode_hashable::<Duration<Millisecond>>(self),
+                TimeUnitType::Microsecond => encode_hashable::<Duration<Microsecond>>(self),
+                TimeUnitType::Nanosecond => encode_hashable::<Duration<Nanosecond>>(self),
+            },
+            DataType::Numeric(kind) => with_numeric!(
+                *kind,
+                T => encode_hashable::<T>(self),
+                float => match kind {
+                    PrimitiveType::F16 => encode_hashable::<f16>(self),
+                    PrimitiveType::BF16 => encode_hashable::<bf16>(self),
+                    _ => encode::<T>(self),
+                }
+            ),
+            _ => None,
+        }
+    }
+}
+
+fn encode_hashable<P: TypedPrimitive + TextPrimitive + Hash>(
+    array: &DynArray,
+) -> Option<&dyn EncodeColumn> {
+    match array.data_type() {
+        DataType::Id(inner) => match *inner {
+            DataType::List(_) => Some(array.downcast_ref::<IdArray<ListArray<P>>>()?),
+            _ => Some(array.downcast_ref::<IdArray<PrimitiveArray<P>>>()?),
+        },
+        _ => encode::<P>(array),
+    }
+}
+
+/// `array` has `P` as its leaf.
+fn encode<P: TypedPrimitive + TextPrimitive>(array: &DynArray) -> Option<&dyn EncodeColumn> {
+    match array.data_type() {
+        DataType::List(_) => Some(array.downcast_ref::<ListArray<P>>()?),
+        DataType::OptionList(..) => Some(array.downcast_ref::<OptionListArray<P>>()?),
+        DataType::Option(inner) => match *inner {
+            DataType::List(_) => Some(array.downcast_ref::<OptionArray<ListArray<P>>>()?),
+            DataType::OptionList(..) => {
+                Some(array.downcast_ref::<OptionArray<OptionListArray<P>>>()?)
+            }
+            _ => Some(array.downcast_ref::<OptionArray<PrimitiveArray<P>>>()?),
+        },
+        _ => Some(array.downcast_ref::<PrimitiveArray<P>>()?),
+    }
+}
+
+/// Pairs every column of `batch` with its name, failing on columns that cannot be written.
+pub(crate) fn batch_columns(batch: &RecordBatch) -> Result<Vec<(&str, &dyn EncodeColumn)>> {
+    batch
+        .schema()
+        .fields()
+        .iter()
+        .zip(batch.columns())
+        .map(|(field, column)| {
+            let encode = column.as_encode().ok_or_else(|| {
+                Error::Schema(format!(
+                    "column {} of type {} cannot be written",
+                    field.name,
+                    column.data_type()
+                ))
+            })?;
+            Ok((field.name.as_str(), encode))
+        })
+        .collect()
+}
+
+pub(crate) fn check_lengths(columns: &[(&str, &dyn EncodeColumn)]) -> Result<usize> {
+    let rows = columns.first().map_or(0, |(_, column)| column.len());
+    match columns.iter().find(|(_, column)| column.len() != rows) {
+        Some((name, column)) => Err(Error::Schema(format!(
+            "column {name} has {} rows, expected {rows}",
+            column.len()
+        ))),
+        None => Ok(rows),
+    }
+}
@@ -0,0 +1,8 @@
+mod reader;
+mod value;
+mod writer;
+
+pub(crate) use reader::push_value;
+pub use reader::{Reader, ReaderOptions};
+pub use value::Value;
+pub use writer::Writer;
@@ -0,0 +1,519 @@
+use std::io::BufRead;
+
+use hashbrown::{HashMap, HashSet};
+
+use super::value::Value;
+use crate::{
+    array::Array,
+    io::{Column, ColumnType, Error, Result},
+    scalar::list::OptionList,
+};
+
+#[derive(Debug, Clone)]
+pub struct ReaderOptions {
+    pub batch_size: usize,
+    /// String fields whose distinct / non-null ratio in the first batch they appear in is at most
+    /// this value are dictionary encoded.
+    pub dictionary_ratio: f64,
+}
+
+impl Default for ReaderOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            batch_size: 8192,
+            dictionary_ratio: 0.5,
+        }
+    }
+}
+
+/// Reads newline delimited JSON objects. Nested objects are flattened into columns named by their
+/// dotted path. The schema is inferred from every batch and merged into the schema of the previous
+/// ones, so fields may appear in later batches and numeric fields may widen. Fixed size lists
+/// widened to variable lists lose their element validity: a list holding a null becomes a null
+/// row. Fields that were only ever null are read as Utf8 until a value gives them a type.
+pub struct Reader<R> {
+    inner: R,
+    options: ReaderOptions,
+    schema: Vec<(String, ColumnType)>,
+    /// Whether each schema field has held a value, rather than only nulls.
+    typed: Vec<bool>,
+    line: usize,
+}
+
+type Record = (usize, Vec<(String, Value)>);
+
+impl<R: BufRead> Reader<R> {
+    #[inline]
+    pub fn new(inner: R, options: ReaderOptions) -> Self {
+        Self {
+            inner,
+            options,
+            schema: Vec::new(),
+            typed: Vec::new(),
+            line: 0,
+        }
+    }
+
+    /// The schema of all batches read so far.
+    #[inline]
+    pub fn schema(&self) -> &[(String, ColumnType)] {
+        &self.schema
+    }
+
+    pub fn next_batch(&mut self) -> Result<Option<Vec<Column>>> {
+        let mut records = Vec::new();
+        let mut line = Vec::new();
+        while records.len() < self.options.batch_size {
+            line.clear();
+            if self.inner.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+            self.line += 1;
+            if line.trim_ascii().is_empty() {
+                continue;
+            }
+            let error = |message| Error::Parse {
+                line: self.line,
+                column: 0,
+                message,
+            };
+            match Value::parse(&line).map_err(error)? {
+                Value::Object(fields) => {
+                    let mut flat = Vec::with_capacity(fields.len());
+                    flatten(None, fields, &mut flat);
+                    records.push((self.line, flat));
+                }
+                _ => return Err(error("expected an object".into())),
+            }
+        }
+        if records.is_empty() {
+            return Ok(None);
+        }
+
+        self.update_schema(&records);
+
+        let index = self
+            .schema
+            .iter()
+            .enumerate()
+            .map(|(id, (name, _))| (name.as_str(), id))
+            .collect::<HashMap<_, _>>();
+        let mut columns = self
+            .schema
+            .iter()
+            .map(|(_, column_type)| Column::new(*column_type))
+            .collect::<Vec<_>>();
+        let mut filled = vec![false; columns.len()];
+
+        for (line, fields) in records {
+            filled.fill(false);
+            for (path, value) in fields {
+                let id = index[path.as_str()];
+                if filled[id] {
+                    continue;
+                }
+                filled[id] = true;
+                push_value(&mut columns[id], value).map_err(|message| Error::Parse {
+                    line,
+                    column: id,
+                    message,
+                })?;
+            }
+            for (column, filled) in columns.iter_mut().zip(&filled) {
+                if !filled {
+                    column.push_null();
+                }
+            }
+        }
+
+        Ok(Some(columns))
+    }
+
+    fn update_schema(&mut self, records: &[Record]) {
+        let mut inferred = Vec::<(&str, Option<ColumnType>, HashSet<&[u8]>, usize)>::new();
+        let mut index = HashMap::<&str, usize>::new();
+        for (_, fields) in records {
+            for (path, value) in fields {
+                let id = *index.entry(path.as_str()).or_insert_with(|| {
+                    inferred.push((path.as_str(), None, HashSet::new(), 0));
+                    inferred.len() - 1
+                });
+                let (_, column_type, distinct, count) = &mut inferred[id];
+                if let Some(value_type) = infer_value(value) {
+                    *column_type = Some(match *column_type {
+                        Some(column_type) => merge(column_type, value_type),
+                        None => value_type,
+                    });
+                }
+                if let Value::String(s) = value {
+                    distinct.insert(s.as_slice());
+                    *count += 1;
+                }
+            }
+        }
+
+        for (path, column_type, distinct, count) in inferred {
+            let column_type = match column_type {
+                Some(ColumnType::Utf8)
+                    if count > 0
+                        && distinct.len() as f64 / count as f64
+                            <= self.options.dictionary_ratio =>
+                {
+                    Some(ColumnType::Dictionary)
+                }
+                column_type => column_type,
+            };
+            match self.schema.iter().position(|(name, _)| name == path) {
+                Some(id) => {
+                    let existing = &mut self.schema[id].1;
+                    match column_type {
+                        Some(column_type) if !self.typed[id] => {
+                            *existing = column_type;
+                            self.typed[id] = true;
+                        }
+                        Some(column_type)
+                            if !matches!(
+                                (*existing, column_type),
+                                (ColumnType::Dictionary, ColumnType::Utf8)
+                                    | (ColumnType::Utf8, ColumnType::Dictionary)
+                            ) =>
+                        {
+                            *existing = merge(*existing, column_type);
+                        }
+                        _ => {}
+                    }
+                }
+                None => {
+                    self.schema
+                        .push((path.to_string(), column_type.unwrap_or(ColumnType::Utf8)));
+                    self.typed.push(column_type.is_some());
+                }
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = Result<Vec<Column>>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_batch().transpose()
+    }
+}
+
+fn flatten(prefix: Option<&str>, fields: Vec<(String, Value)>, out: &mut Vec<(String, Value)>) {
+    for (key, value) in fields {
+        let path = match prefix {
+            Some(prefix) => format!("{prefix}.{key}"),
+            None => key,
+        };
+        match value {
+            Value::Object(fields) => flatten(Some(&path), fields, out),
+            value => out.push((path, value)),
+        }
+    }
+}
+
+fn infer_value(value: &Value) -> Option<ColumnType> {
+    Some(match value {
+        Value::Null => return None,
+        Value::Bool(_) => ColumnType::Boolean,
+        Value::Int(_) => ColumnType::Int64,
+        Value::Float(_) => ColumnType::Float64,
+        Value::String(_) | Value::Object(_) => ColumnType::Utf8,
+        Value::Array(values) if values.is_empty() => ColumnType::Int64List,
+        Value::Array(values) => {
+            let mut float = false;
+            for value in values {
+                match value {
+                    Value::Int(_) | Value::Null => {}
+                    Value::Float(_) => float = true,
+                    _ => return Some(ColumnType::Utf8),
+                }
+            }
+            if float {
+                ColumnType::FixedFloat64List(values.len())
+            } else {
+                ColumnType::FixedInt64List(values.len())
+            }
+        }
+    })
+}
+
+pub(crate) fn merge(lhs: ColumnType, rhs: ColumnType) -> ColumnType {
+    use ColumnType::*;
+
+    match (lhs, rhs) {
+        (lhs, rhs) if lhs == rhs => lhs,
+        (Int64 | Float64, Int64 | Float64) => Float64,
+        (FixedInt64List(l) | FixedFloat64List(l), FixedInt64List(r) | FixedFloat64List(r))
+            if l == r =>
+        {
+            FixedFloat64List(l)
+        }
+        (
+            Int64List | FixedInt64List(_) | Float64List | FixedFloat64List(_),
+            Int64List | FixedInt64List(_) | Float64List | FixedFloat64List(_),
+        ) => {
+            if matches!(lhs, Int64List | FixedInt64List(_))
+                && matches!(rhs, Int64List | FixedInt64List(_))
+            {
+                Int64List
+            } else {
+                Float64List
+            }
+        }
+        _ => Utf8,
+    }
+}
+
+#[inline]
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Int(i) => Some(*i as f64),
+        Value::Float(f) => Some(*f),
+        _ => None,
+    }
+}
+
+pub(crate) fn push_value(column: &mut Column, value: Value) -> std::result::Result<(), String> {
+    if value == Value::Null {
+        column.push_null();
+        return Ok(());
+    }
+    let mismatch = |value: &Value, column_type: ColumnType| {
+        let mut text = Vec::new();
+        let text = match value.write(&mut text) {
+            Ok(()) => String::from_utf8_lossy(&text).into_owned(),
+            Err(_) => format!("{value:?}"),
+        };
+        format!("cannot store {text} in a {column_type:?} column")
+    };
+
+    match (column, value) {
+        (Column::Boolean(array), Value::Bool(b)) => array.push(Some(b)),
+        (Column::Int64(array), Value::Int(i)) => array.push(Some(i)),
+        (Column::Float64(array), value @ (Value::Int(_) | Value::Float(_))) => {
+            array.push(as_f64(&value))
+        }
+        (Column::Utf8(array), Value::String(s)) => array.push(Some(s)),
+        (Column::Dictionary(array), Value::String(s)) => array.push(Some(s)),
+        (Column::Utf8(array), value) => {
+            let mut text = Vec::new();
+            value.write(&mut text).map_err(|e| e.to_string())?;
+            array.push(Some(text))
+        }
+        (Column::Dictionary(array), value) => {
+            let mut text = Vec::new();
+            value.write(&mut text).map_err(|e| e.to_string())?;
+            array.push(Some(text))
+        }
+        // Variable lists have no element validity, so a list holding a null is a null row.
+        (Column::Int64List(array), Value::Array(values)) if values.contains(&Value::Null) => {
+            array.push(None)
+        }
+        (Column::Float64List(array), Value::Array(values)) if values.contains(&Value::Null) => {
+            array.push(None)
+        }
+        (Column::Int64List(array), Value::Array(values)) => {
+            let list = values
+                .iter()
+                .map(|value| match value {
+                    Value::Int(i) => Ok(*i),
+                    value => Err(mismatch(value, ColumnType::Int64List)),
+                })
+                .collect::<std::result::Result<_, _>>()?;
+            array.push(Some(list))
+        }
+        (Column::Float64List(array), Value::Array(values)) => {
+            let list = values
+                .iter()
+                .map(|value| as_f64(value).ok_or_else(|| mismatch(value, ColumnType::Float64List)))
+                .collect::<std::result::Result<_, _>>()?;
+            array.push(Some(list))
+        }
+        (Column::FixedInt64List(array), Value::Array(values))
+            if values.len() == array.list_size() =>
+        {
+            let mut list = OptionList::with_capacity(values.len());
+            for value in &values {
+                match value {
+                    Value::Int(i) => list.push(Some(*i)),
+                    Value::Null => list.push(None),
+                    value => return Err(mismatch(value, ColumnType::Int64)),
+                }
+            }
+            array.push(list)
+        }
+        (Column::FixedFloat64List(array), Value::Array(values))
+            if values.len() == array.list_size() =>
+        {
+            let mut list = OptionList::with_capacity(values.len());
+            for value in &values {
+                match value {
+                    Value::Null => list.push(None),
+                    value => list.push(Some(
+                        as_f64(value).ok_or_else(|| mismatch(value, ColumnType::Float64))?,
+                    )),
+                }
+            }
+            array.push(list)
+        }
+        (column, value) => return Err(mismatch(&value, column.column_type())),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Reader, ReaderOptions};
+    use crate::{
+        array::Array,
+        io::{Column, ColumnType},
+    };
+
+    #[test]
+    fn nested_schema() {
+        let input = concat!(
+            r#"{"id": 1, "user": {"name": "a", "tags": [1, 2]}, "pos": [0.5, 1]}"#,
+            "\n",
+            r#"{"id": 2, "user": {"name": "a", "tags": [3]}, "pos": [1, null]}"#,
+            "\n\n",
+            r#"{"id": 3, "user": {"name": "a"}, "pos": null}"#,
+            "\n",
+        );
+        let mut reader = Reader::new(input.as_bytes(), ReaderOptions::default());
+        let columns = reader.next_batch().unwrap().unwrap();
+        assert_eq!(
+            reader.schema(),
+            &[
+                ("id".into(), ColumnType::Int64),
+                ("user.name".into(), ColumnType::Dictionary),
+                ("user.tags".into(), ColumnType::Int64List),
+                ("pos".into(), ColumnType::FixedFloat64List(2)),
+            ]
+        );
+        match &columns[2] {
+            Column::Int64List(array) => {
+                assert_eq!(array.get(0), Some(Some(&[1, 2][..])));
+                assert_eq!(array.get(1), Some(Some(&[3][..])));
+                assert_eq!(array.get(2), Some(None));
+            }
+            _ => unreachable!(),
+        }
+        match &columns[3] {
+            Column::FixedFloat64List(array) => {
+                assert_eq!(array.get(0).unwrap().get(1), Some(Some(&1.0)));
+                assert_eq!(array.get(1).unwrap().get(1), Some(None));
+                assert_eq!(array.get(2).unwrap().get(0), Some(None));
+                assert!(array.to_const_size::<2>().is_none());
+            }
+            _ => unreachable!(),
+        }
+        assert!(reader.next_batch().unwrap().is_none());
+    }
+
+    #[test]
+    fn schema_evolution() {
+        let input = concat!(
+            r#"{"a": 1}"#,
+            "\n",
+            r#"{"a": 2}"#,
+            "\n",
+            r#"{"a": 2.5, "b": true}"#,
+            "\n",
+            r#"{"b": false, "c": [1, 2]}"#,
+            "\n",
+        );
+        let options = ReaderOptions {
+            batch_size: 2,
+            ..Default::default()
+        };
+        let mut reader = Reader::new(input.as_bytes(), options);
+        let first = reader.next_batch().unwrap().unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(reader.schema(), &[("a".into(), ColumnType::Int64)]);
+
+        let second = reader.next_batch().unwrap().unwrap();
+        assert_eq!(
+            reader.schema(),
+            &[
+                ("a".into(), ColumnType::Float64),
+                ("b".into(), ColumnType::Boolean),
+                ("c".into(), ColumnType::FixedInt64List(2)),
+            ]
+        );
+        match &second[0] {
+            Column::Float64(array) => {
+                assert_eq!(array.get(0), Some(Some(&2.5)));
+                assert_eq!(array.get(1), Some(None));
+            }
+            _ => unreachable!(),
+        }
+        match &second[2] {
+            Column::FixedInt64List(array) => {
+                let fixed = array.get(1).unwrap();
+                assert_eq!(fixed.get(1), Some(Some(&2)));
+            }
+            _ => unreachable!(),
+        }
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn null_fields_stay_untyped() {
+        let input = "{\"a\": null}\n{\"a\": 5}\n{\"a\": null}\n";
+        let options = ReaderOptions {
+            batch_size: 1,
+            ..Default::default()
+        };
+        let mut reader = Reader::new(input.as_bytes(), options);
+        let first = reader.next_batch().unwrap().unwrap();
+        assert_eq!(first[0].column_type(), ColumnType::Utf8);
+        for _ in 0..2 {
+            let batch = reader.next_batch().unwrap().unwrap();
+            assert_eq!(batch[0].column_type(), ColumnType::Int64);
+        }
+        assert_eq!(reader.schema(), &[("a".into(), ColumnType::Int64)]);
+    }
+
+    #[test]
+    fn const_size() {
+        let input = "{\"v\": [1, 2, 3]}\n{\"v\": [4, 5, 6]}\n";
+        let mut reader = Reader::new(input.as_bytes(), ReaderOptions::default());
+        let columns = reader.next_batch().unwrap().unwrap();
+        match &columns[0] {
+            Column::FixedInt64List(array) => {
+                let array = array.to_const_size::<3>().unwrap();
+                assert_eq!(array.get(1), Some(&[4, 5, 6]));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn widen_lists_with_nulls() {
+        let input = "{\"a\": [1, null]}\n{\"a\": [1, 2, 3]}\n{\"a\": [0.5, null, 2]}\n";
+        let mut reader = Reader::new(input.as_bytes(), ReaderOptions::default());
+        let columns = reader.next_batch().unwrap().unwrap();
+        assert_eq!(reader.schema(), &[("a".into(), ColumnType::Float64List)]);
+        match &columns[0] {
+            Column::Float64List(array) => {
+                assert_eq!(array.get(0), Some(None));
+                assert_eq!(array.get(1), Some(Some(&[1.0, 2.0, 3.0][..])));
+                assert_eq!(array.get(2), Some(None));
+            }
+            _ => unreachable!(),
+        }
+
+        let input = "{\"a\": [1, null]}\n{\"a\": [1, 2, 3]}\n";
+        let mut reader = Reader::new(input.as_bytes(), ReaderOptions::default());
+        let columns = reader.next_batch().unwrap().unwrap();
+        match &columns[0] {
+            Column::Int64List(array) => assert_eq!(array.get(0), Some(None)),
+            _ => unreachable!(),
+        }
+    }
+}
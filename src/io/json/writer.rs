@@ -0,0 +1,186 @@
+use std::io::Write;
+
+use crate::{
+    batch::RecordBatch,
+    io::{
+        encode::{batch_columns, check_lengths, write_json_string, EncodeColumn},
+        Error, Result,
+    },
+};
+
+/// Writes newline delimited JSON, one object per row.
+pub struct Writer<W> {
+    inner: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> Writer<W> {
+    #[inline]
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Writes all rows of the batch under its field names; see [`DynArray::as_encode`] for the
+    /// column types that can be written.
+    ///
+    /// [`DynArray::as_encode`]: crate::array::DynArray::as_encode
+    #[inline]
+    pub fn write_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.write(&batch_columns(batch)?)
+    }
+
+    pub fn write(&mut self, columns: &[(&str, &dyn EncodeColumn)]) -> Result<()> {
+        let rows = check_lengths(columns)?;
+
+        let mut keys = Vec::with_capacity(columns.len());
+        for (name, _) in columns {
+            let mut key = Vec::new();
+            write_json_string(name.as_bytes(), &mut key)?;
+            key.push(b':');
+            keys.push(key);
+        }
+
+        self.buffer.clear();
+        for row in 0..rows {
+            self.buffer.push(b'{');
+            for (id, ((_, column), key)) in columns.iter().zip(&keys).enumerate() {
+                if id != 0 {
+                    self.buffer.push(b',');
+                }
+                self.buffer.extend_from_slice(key);
+                if column.is_null(row) {
+                    self.buffer.extend_from_slice(b"null");
+                } else {
+                    column.write_json(row, &mut self.buffer)?;
+                }
+            }
+            self.buffer.extend_from_slice(b"}\n");
+        }
+
+        self.inner.write_all(&self.buffer)?;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn flush(&mut self) -> Result<()> {
+        self.inner.flush().map_err(Error::from)
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Writer;
+    use crate::{
+        array::{
+            id::IdArray,
+            list::{ConstSizeListArray, ListArray},
+            primitive::PrimitiveArray,
+            Array, DynArray, OptionArray, OptionListArray,
+        },
+        batch::RecordBatch,
+        io::Error,
+        primitive::{temporal::Second, Timestamp},
+        scalar::list::OptionList,
+    };
+
+    #[test]
+    fn write_rows() {
+        let mut names = OptionArray::new(ListArray::<u8>::new());
+        names.push(Some(Vec::from("line\n\"quoted\"")));
+        names.push(None);
+        let mut values = ListArray::<i32>::new();
+        values.push(vec![1, 2]);
+        values.push(vec![]);
+        let mut points = ConstSizeListArray::<f32, 2>::default();
+        points.push([0.5, f32::NAN]);
+        points.push([1.0, 2.0]);
+        let mut rates = OptionListArray::<u8>::new(2);
+        rates.push(OptionList::from(vec![Some(1), None]));
+        rates.push(OptionList::from(vec![None, Some(2)]));
+        let mut tags = IdArray::new(ListArray::<u8>::new());
+        tags.push(Some(Vec::from("x")));
+        tags.push(Some(Vec::from("x")));
+
+        let mut writer = Writer::new(Vec::new());
+        writer
+            .write(&[
+                ("name", &names),
+                ("values", &values),
+                ("points", &points),
+                ("rates", &rates),
+                ("tag", &tags),
+            ])
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(writer.into_inner()).unwrap(),
+            concat!(
+                r#"{"name":"line\n\"quoted\"","values":[1,2],"points":[0.5,null],"#,
+                r#""rates":[1,null],"tag":"x"}"#,
+                "\n",
+                r#"{"name":null,"values":[],"points":[1,2],"rates":[null,2],"tag":"x"}"#,
+                "\n",
+            )
+        );
+    }
+
+    #[test]
+    fn write_batches() {
+        let mut tags = IdArray::new(ListArray::<u8>::new());
+        tags.push(Some(Vec::from("x")));
+        tags.push(None);
+        let mut stamps = OptionArray::new(PrimitiveArray::new());
+        stamps.push(Some(Timestamp::<Second>::from_raw(60)));
+        stamps.push(None);
+        let mut values = OptionListArray::<f32>::new(2);
+        values.push(OptionList::from(vec![Some(0.5), None]));
+        values.push(OptionList::from(vec![None, Some(2.0)]));
+        let batch = RecordBatch::from_columns([
+            ("tag", DynArray::new(tags)),
+            ("at", DynArray::new(stamps)),
+            ("values", DynArray::new(values)),
+        ])
+        .unwrap();
+
+        let mut writer = Writer::new(Vec::new());
+        writer.write_batch(&batch).unwrap();
+        assert_eq!(
+            String::from_utf8(writer.into_inner()).unwrap(),
+            concat!(
+                r#"{"tag":"x","at":"1970-01-01T00:01:00Z","values":[0.5,null]}"#,
+                "\n",
+                r#"{"tag":null,"at":null,"values":[null,2]}"#,
+                "\n",
+            )
+        );
+
+        let points = ConstSizeListArray::<f32, 2>::default();
+        let batch = RecordBatch::from_columns([("points", DynArray::new(points))]).unwrap();
+        let error = Writer::new(Vec::new()).write_batch(&batch).unwrap_err();
+        assert!(matches!(error, Error::Schema(_)));
+    }
+
+    #[test]
+    fn reject_invalid_utf8() {
+        let mut names = ListArray::<u8>::new();
+        names.push(vec![b'o', b'k']);
+        names.push(vec![0xff, b'x']);
+        let mut flags = PrimitiveArray::<bool>::new();
+        flags.push(true);
+        flags.push(false);
+
+        let mut writer = Writer::new(Vec::new());
+        assert!(matches!(
+            writer.write(&[("flag", &flags), ("name", &names)]),
+            Err(Error::Format(_))
+        ));
+        assert!(writer.into_inner().is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+pub mod csv;
+pub mod encode;
+pub mod json;
+pub mod parquet;
+
+use std::fmt;
+
+use crate::array::{
+    id::IdArray, list::ListArray, primitive::PrimitiveArray, Array, OptionArray, OptionListArray,
+};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    Schema(String),
+    /// Malformed or unsupported binary data.
+    Format(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "io error: {e}"),
+            Error::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "parse error at line {line}, column {column}: {message}"),
+            Error::Schema(message) => write!(f, "schema error: {message}"),
+            Error::Format(message) => write!(f, "format error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Boolean,
+    Int64,
+    Float64,
+    Utf8,
+    Dictionary,
+    Int64List,
+    Float64List,
+    /// Lists that always hold the given number of elements.
+    FixedInt64List(usize),
+    FixedFloat64List(usize),
+}
+
+#[derive(Debug, Clone)]
+pub enum Column {
+    Boolean(OptionArray<PrimitiveArray<bool>>),
+    Int64(OptionArray<PrimitiveArray<i64>>),
+    Float64(OptionArray<PrimitiveArray<f64>>),
+    Utf8(OptionArray<ListArray<u8>>),
+    Dictionary(IdArray<ListArray<u8>>),
+    Int64List(OptionArray<ListArray<i64>>),
+    Float64List(OptionArray<ListArray<f64>>),
+    FixedInt64List(OptionListArray<i64>),
+    FixedFloat64List(OptionListArray<f64>),
+}
+
+impl Column {
+    pub fn new(column_type: ColumnType) -> Self {
+        match column_type {
+            ColumnType::Boolean => Column::Boolean(Default::default()),
+            ColumnType::Int64 => Column::Int64(Default::default()),
+            ColumnType::Float64 => Column::Float64(Default::default()),
+            ColumnType::Utf8 => Column::Utf8(Default::default()),
+            ColumnType::Dictionary => Column::Dictionary(IdArray::new(ListArray::new())),
+            ColumnType::Int64List => Column::Int64List(Default::default()),
+            ColumnType::Float64List => Column::Float64List(Default::default()),
+            ColumnType::FixedInt64List(size) => Column::FixedInt64List(OptionListArray::new(size)),
+            ColumnType::FixedFloat64List(size) => {
+                Column::FixedFloat64List(OptionListArray::new(size))
+            }
+        }
+    }
+
+    pub fn column_type(&self) -> ColumnType {
+        match self {
+            Column::Boolean(_) => ColumnType::Boolean,
+            Column::Int64(_) => ColumnType::Int64,
+            Column::Float64(_) => ColumnType::Float64,
+            Column::Utf8(_) => ColumnType::Utf8,
+            Column::Dictionary(_) => ColumnType::Dictionary,
+            Column::Int64List(_) => ColumnType::Int64List,
+            Column::Float64List(_) => ColumnType::Float64List,
+            Column::FixedInt64List(array) => ColumnType::FixedInt64List(array.list_size()),
+            Column::FixedFloat64List(array) => ColumnType::FixedFloat64List(array.list_size()),
+        }
+    }
+
+    pub fn push_null(&mut self) {
+        match self {
+            Column::Boolean(array) => array.push_zero(),
+            Column::Int64(array) => array.push_zero(),
+            Column::Float64(array) => array.push_zero(),
+            Column::Utf8(array) => array.push_zero(),
+            Column::Dictionary(array) => array.push_zero(),
+            Column::Int64List(array) => array.push_zero(),
+            Column::Float64List(array) => array.push_zero(),
+            Column::FixedInt64List(array) => array.push_zero(),
+            Column::FixedFloat64List(array) => array.push_zero(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Column::Boolean(array) => array.len(),
+            Column::Int64(array) => array.len(),
+            Column::Float64(array) => array.len(),
+            Column::Utf8(array) => array.len(),
+            Column::Dictionary(array) => array.len(),
+            Column::Int64List(array) => array.len(),
+            Column::Float64List(array) => array.len(),
+            Column::FixedInt64List(array) => array.len(),
+            Column::FixedFloat64List(array) => array.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
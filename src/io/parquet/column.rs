@@ -0,0 +1,747 @@
+use std::hash::Hash;
+
+use super::{
+    encoding::{PlainDecoder, PlainEncoder},
+    ConvertedType, Leaf, PhysicalType, Shape,
+};
+use crate::{
+    array::{
+        id::IdArray, idvec::IdVec, list::ListArray, primitive::PrimitiveArray, slotmap::SlotMap,
+        Array, OptionArray, OptionListArray,
+    },
+    bitvec::{BitSlice, BitVec},
+    io::{Column, Error, Result},
+    primitive::{bf16, f16, NumericPrimitive, Primitive, Total},
+};
+
+pub trait ParquetPrimitive: Primitive {
+    const PHYSICAL_TYPE: PhysicalType;
+    const CONVERTED_TYPE: Option<ConvertedType> = None;
+    /// Lists of the primitive are stored as `BYTE_ARRAY` values rather than repeated columns.
+    const BYTES: bool = false;
+
+    fn encode(self, encoder: &mut PlainEncoder);
+
+    fn decode(decoder: &mut PlainDecoder<'_>) -> Result<Self>;
+
+    #[inline]
+    fn as_bytes(_list: &[Self]) -> &[u8] {
+        unreachable!("only byte primitives are stored as byte arrays")
+    }
+
+    #[inline]
+    fn from_bytes(_bytes: &[u8]) -> Vec<Self> {
+        unreachable!("only byte primitives are stored as byte arrays")
+    }
+}
+
+macro_rules! parquet_native {
+    ($type:ty, $stored:ty, $physical:ident, $converted:expr) => {
+        impl ParquetPrimitive for $type {
+            const PHYSICAL_TYPE: PhysicalType = PhysicalType::$physical;
+            const CONVERTED_TYPE: Option<ConvertedType> = $converted;
+
+            #[inline]
+            fn encode(self, encoder: &mut PlainEncoder) {
+                encoder.push_bytes(&(self as $stored).to_le_bytes())
+            }
+
+            #[inline]
+            fn decode(decoder: &mut PlainDecoder<'_>) -> Result<Self> {
+                Ok(<$stored>::from_le_bytes(decoder.bytes()?) as $type)
+            }
+        }
+    };
+}
+
+parquet_native!(u16, i32, Int32, Some(ConvertedType::Uint16));
+parquet_native!(u32, i32, Int32, Some(ConvertedType::Uint32));
+parquet_native!(u64, i64, Int64, Some(ConvertedType::Uint64));
+parquet_native!(usize, i64, Int64, Some(ConvertedType::Uint64));
+parquet_native!(i8, i32, Int32, Some(ConvertedType::Int8));
+parquet_native!(i16, i32, Int32, Some(ConvertedType::Int16));
+parquet_native!(i32, i32, Int32, None);
+parquet_native!(i64, i64, Int64, None);
+parquet_native!(isize, i64, Int64, None);
+parquet_native!(f32, f32, Float, None);
+parquet_native!(f64, f64, Double, None);
+
+macro_rules! parquet_half {
+    ($type:ty) => {
+        /// Stored widened to `FLOAT`, which holds every half value exactly.
+        impl ParquetPrimitive for $type {
+            const PHYSICAL_TYPE: PhysicalType = PhysicalType::Float;
+
+            #[inline]
+            fn encode(self, encoder: &mut PlainEncoder) {
+                self.to_f32().encode(encoder)
+            }
+
+            #[inline]
+            fn decode(decoder: &mut PlainDecoder<'_>) -> Result<Self> {
+                f32::decode(decoder).map(Self::from_f32)
+            }
+        }
+    };
+}
+
+parquet_half!(f16);
+parquet_half!(bf16);
+
+impl<P: ParquetPrimitive + NumericPrimitive> ParquetPrimitive for Total<P> {
+    const PHYSICAL_TYPE: PhysicalType = P::PHYSICAL_TYPE;
+    const CONVERTED_TYPE: Option<ConvertedType> = P::CONVERTED_TYPE;
+
+    #[inline]
+    fn encode(self, encoder: &mut PlainEncoder) {
+        self.0.encode(encoder)
+    }
+
+    #[inline]
+    fn decode(decoder: &mut PlainDecoder<'_>) -> Result<Self> {
+        P::decode(decoder).map(Total)
+    }
+}
+
+impl ParquetPrimitive for bool {
+    const PHYSICAL_TYPE: PhysicalType = PhysicalType::Boolean;
+
+    #[inline]
+    fn encode(self, encoder: &mut PlainEncoder) {
+        encoder.push_bool(self)
+    }
+
+    #[inline]
+    fn decode(decoder: &mut PlainDecoder<'_>) -> Result<Self> {
+        decoder.bool()
+    }
+}
+
+impl ParquetPrimitive for u8 {
+    const PHYSICAL_TYPE: PhysicalType = PhysicalType::Int32;
+    const CONVERTED_TYPE: Option<ConvertedType> = Some(ConvertedType::Uint8);
+    const BYTES: bool = true;
+
+    #[inline]
+    fn encode(self, encoder: &mut PlainEncoder) {
+        encoder.push_bytes(&(self as i32).to_le_bytes())
+    }
+
+    #[inline]
+    fn decode(decoder: &mut PlainDecoder<'_>) -> Result<Self> {
+        Ok(i32::from_le_bytes(decoder.bytes()?) as u8)
+    }
+
+    #[inline]
+    fn as_bytes(list: &[Self]) -> &[u8] {
+        list
+    }
+
+    #[inline]
+    fn from_bytes(bytes: &[u8]) -> Vec<Self> {
+        bytes.to_vec()
+    }
+}
+
+/// Levels and values of one column chunk, ready to be written as pages.
+#[derive(Debug)]
+pub struct EncodedColumn {
+    pub(crate) leaf: Leaf,
+    pub(crate) num_rows: usize,
+    pub(crate) def_levels: Vec<u32>,
+    pub(crate) rep_levels: Vec<u32>,
+    pub(crate) values: PlainEncoder,
+    /// Entry count and plain values of the dictionary; `indices` then replace `values`.
+    pub(crate) dictionary: Option<(usize, PlainEncoder)>,
+    pub(crate) indices: Vec<u32>,
+    pub(crate) key_value: Vec<(String, String)>,
+}
+
+impl EncodedColumn {
+    #[inline]
+    fn new(leaf: Leaf, num_rows: usize) -> Self {
+        Self {
+            leaf,
+            num_rows,
+            def_levels: Vec::new(),
+            rep_levels: Vec::new(),
+            values: PlainEncoder::default(),
+            dictionary: None,
+            indices: Vec::new(),
+            key_value: Vec::new(),
+        }
+    }
+}
+
+pub trait ParquetWrite {
+    /// Rows cleared in `validity` are written as nulls.
+    fn encode(&self, validity: Option<&BitSlice<'_>>) -> Result<EncodedColumn>;
+}
+
+#[inline]
+fn is_valid(validity: Option<&BitSlice<'_>>, row: usize) -> bool {
+    validity.is_none_or(|validity| validity.get(row).unwrap_or(false))
+}
+
+fn encode_flat(
+    physical_type: PhysicalType,
+    converted_type: Option<ConvertedType>,
+    len: usize,
+    validity: Option<&BitSlice<'_>>,
+    mut encode: impl FnMut(usize, &mut PlainEncoder),
+) -> EncodedColumn {
+    let shape = match validity {
+        Some(_) => Shape::Optional,
+        None => Shape::Required,
+    };
+    let mut column = EncodedColumn::new(
+        Leaf {
+            physical_type,
+            converted_type,
+            shape,
+        },
+        len,
+    );
+    for row in 0..len {
+        let valid = is_valid(validity, row);
+        if validity.is_some() {
+            column.def_levels.push(valid as u32);
+        }
+        if valid {
+            encode(row, &mut column.values);
+        }
+    }
+    column
+}
+
+/// `rows` yields the length and validity of every list, `element` encodes the element at an
+/// offset into the flattened values and reports whether it is valid.
+fn encode_lists(
+    physical_type: PhysicalType,
+    converted_type: Option<ConvertedType>,
+    shape: Shape,
+    rows: impl ExactSizeIterator<Item = (usize, bool)>,
+    mut element: impl FnMut(usize, &mut PlainEncoder) -> bool,
+) -> EncodedColumn {
+    let (max_def, _) = shape.levels();
+    let empty_def = match shape {
+        Shape::List { optional, .. } => optional as u32,
+        _ => 0,
+    };
+    let mut column = EncodedColumn::new(
+        Leaf {
+            physical_type,
+            converted_type,
+            shape,
+        },
+        rows.len(),
+    );
+    let mut offset = 0;
+    for (len, valid) in rows {
+        if !valid || len == 0 {
+            column.def_levels.push(if valid { empty_def } else { 0 });
+            column.rep_levels.push(0);
+        } else {
+            for id in 0..len {
+                let element_valid = element(offset + id, &mut column.values);
+                column.def_levels.push(max_def - !element_valid as u32);
+                column.rep_levels.push((id != 0) as u32);
+            }
+        }
+        offset += len;
+    }
+    column
+}
+
+impl<P: ParquetPrimitive> ParquetWrite for PrimitiveArray<P> {
+    fn encode(&self, validity: Option<&BitSlice<'_>>) -> Result<EncodedColumn> {
+        Ok(encode_flat(
+            P::PHYSICAL_TYPE,
+            P::CONVERTED_TYPE,
+            self.len(),
+            validity,
+            |row, encoder| self.data[row].encode(encoder),
+        ))
+    }
+}
+
+impl<P: ParquetPrimitive> ParquetWrite for ListArray<P> {
+    fn encode(&self, validity: Option<&BitSlice<'_>>) -> Result<EncodedColumn> {
+        if P::BYTES {
+            return Ok(encode_flat(
+                PhysicalType::ByteArray,
+                None,
+                self.len(),
+                validity,
+                |row, encoder| encoder.push_byte_array(P::as_bytes(self.get(row).unwrap())),
+            ));
+        }
+        let rows =
+            (0..self.len()).map(|row| (self.get(row).unwrap().len(), is_valid(validity, row)));
+        Ok(encode_lists(
+            P::PHYSICAL_TYPE,
+            P::CONVERTED_TYPE,
+            Shape::List {
+                optional: validity.is_some(),
+                optional_element: false,
+            },
+            rows,
+            |offset, encoder| {
+                self.data[offset].encode(encoder);
+                true
+            },
+        ))
+    }
+}
+
+impl<P: ParquetPrimitive> ParquetWrite for OptionListArray<P> {
+    fn encode(&self, validity: Option<&BitSlice<'_>>) -> Result<EncodedColumn> {
+        let rows = (0..self.len()).map(|row| (self.list_size, is_valid(validity, row)));
+        let mut column = encode_lists(
+            P::PHYSICAL_TYPE,
+            P::CONVERTED_TYPE,
+            Shape::List {
+                optional: validity.is_some(),
+                optional_element: true,
+            },
+            rows,
+            |offset, encoder| {
+                let valid = self.validity.get(offset).unwrap_or(false);
+                if valid {
+                    self.data[offset].encode(encoder);
+                }
+                valid
+            },
+        );
+        column
+            .key_value
+            .push(("list_size".into(), self.list_size.to_string()));
+        Ok(column)
+    }
+}
+
+impl<A: ParquetWrite + Array> ParquetWrite for OptionArray<A> {
+    fn encode(&self, validity: Option<&BitSlice<'_>>) -> Result<EncodedColumn> {
+        match validity {
+            Some(outer) => {
+                let mut combined = BitVec::with_capacity(self.len());
+                combined.extend(
+                    (0..self.len()).map(|row| is_valid(Some(outer), row) && self.is_valid(row)),
+                );
+                self.data.encode(Some(&combined.as_slice()))
+            }
+            None => self.data.encode(Some(&self.validity())),
+        }
+    }
+}
+
+/// The dictionary page is written straight from the interned values, in id order.
+impl<A: ParquetWrite + Array, S> ParquetWrite for IdArray<A, S> {
+    fn encode(&self, validity: Option<&BitSlice<'_>>) -> Result<EncodedColumn> {
+        let values = self.values.data.encode(None)?;
+        if values.leaf.shape != Shape::Required {
+            return Err(Error::Schema(format!(
+                "dictionary values must be flat, found {:?}",
+                values.leaf.shape
+            )));
+        }
+        let mut column = EncodedColumn::new(
+            Leaf {
+                shape: Shape::Optional,
+                ..values.leaf
+            },
+            self.data.len(),
+        );
+        for (row, id) in self.data.iter().enumerate() {
+            let valid = id != 0 && is_valid(validity, row);
+            column.def_levels.push(valid as u32);
+            if valid {
+                column.indices.push((id - 1) as u32);
+            }
+        }
+        column.dictionary = Some((self.values.data.len(), values.values));
+        Ok(column)
+    }
+}
+
+impl ParquetWrite for Column {
+    fn encode(&self, validity: Option<&BitSlice<'_>>) -> Result<EncodedColumn> {
+        match self {
+            Column::Boolean(array) => array.encode(validity),
+            Column::Int64(array) => array.encode(validity),
+            Column::Float64(array) => array.encode(validity),
+            Column::Utf8(array) => array.encode(validity),
+            Column::Dictionary(array) => array.encode(validity),
+            Column::Int64List(array) => array.encode(validity),
+            Column::Float64List(array) => array.encode(validity),
+            Column::FixedInt64List(array) => array.encode(validity),
+            Column::FixedFloat64List(array) => array.encode(validity),
+        }
+    }
+}
+
+/// Levels and undecoded values of a column across all row groups.
+#[derive(Debug)]
+pub struct ColumnData {
+    pub(crate) name: String,
+    pub(crate) leaf: Leaf,
+    pub(crate) max_def: u32,
+    pub(crate) num_rows: usize,
+    pub(crate) key_value: Vec<(String, String)>,
+    pub(crate) def_levels: Vec<u32>,
+    pub(crate) rep_levels: Vec<u32>,
+    pub(crate) chunks: Vec<ValueChunk>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ValueChunk {
+    pub(crate) dictionary: Option<(usize, Vec<u8>)>,
+    pub(crate) pages: Vec<ValuePage>,
+}
+
+#[derive(Debug)]
+pub(crate) enum ValuePage {
+    Plain { count: usize, values: Vec<u8> },
+    Dictionary(Vec<u32>),
+}
+
+enum ListEvent<T> {
+    Element(Option<T>),
+    /// End of a row and whether the list is valid.
+    End(bool),
+}
+
+impl ColumnData {
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn leaf(&self) -> Leaf {
+        self.leaf
+    }
+
+    #[inline]
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    #[inline]
+    pub fn key_value(&self, key: &str) -> Option<&str> {
+        self.key_value
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Decodes the non-null values; dictionary entries are decoded once per row group.
+    pub fn values<T: Clone>(
+        &self,
+        mut decode: impl FnMut(&mut PlainDecoder<'_>) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        let mut out = Vec::new();
+        for chunk in &self.chunks {
+            let dictionary = match &chunk.dictionary {
+                Some((len, bytes)) => {
+                    let mut decoder = PlainDecoder::new(bytes);
+                    (0..*len)
+                        .map(|_| decode(&mut decoder))
+                        .collect::<Result<Vec<_>>>()?
+                }
+                None => Vec::new(),
+            };
+            for page in &chunk.pages {
+                match page {
+                    ValuePage::Plain { count, values } => {
+                        let mut decoder = PlainDecoder::new(values);
+                        for _ in 0..*count {
+                            out.push(decode(&mut decoder)?);
+                        }
+                    }
+                    ValuePage::Dictionary(indices) => {
+                        for &index in indices {
+                            let value = dictionary.get(index as usize).ok_or_else(|| {
+                                Error::Format(format!("dictionary index {index} out of range"))
+                            })?;
+                            out.push(value.clone());
+                        }
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    fn expect(&self, physical_type: PhysicalType, list: bool) -> Result<()> {
+        if self.leaf.physical_type != physical_type
+            || matches!(self.leaf.shape, Shape::List { .. }) != list
+        {
+            return Err(Error::Schema(format!(
+                "column {} is stored as {:?} {:?}, which does not match the array",
+                self.name, self.leaf.shape, self.leaf.physical_type
+            )));
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn push_validity(&self, validity: &mut Option<&mut BitVec>, valid: bool) -> Result<()> {
+        match validity {
+            Some(validity) => validity.push(valid),
+            None if !valid => {
+                return Err(Error::Schema(format!(
+                    "column {} contains nulls",
+                    self.name
+                )))
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn missing_values(&self) -> Error {
+        Error::Format(format!("column {} has fewer values than levels", self.name))
+    }
+
+    fn read_flat<T: Clone>(
+        &self,
+        physical_type: PhysicalType,
+        mut validity: Option<&mut BitVec>,
+        decode: impl FnMut(&mut PlainDecoder<'_>) -> Result<T>,
+        mut push: impl FnMut(Option<T>),
+    ) -> Result<()> {
+        self.expect(physical_type, false)?;
+        let mut values = self.values(decode)?.into_iter();
+        if self.max_def == 0 {
+            for value in values {
+                self.push_validity(&mut validity, true)?;
+                push(Some(value));
+            }
+            return Ok(());
+        }
+        for &def in &self.def_levels {
+            let valid = def == self.max_def;
+            self.push_validity(&mut validity, valid)?;
+            if valid {
+                push(Some(values.next().ok_or_else(|| self.missing_values())?));
+            } else {
+                push(None);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_lists<T: Clone>(
+        &self,
+        physical_type: PhysicalType,
+        decode: impl FnMut(&mut PlainDecoder<'_>) -> Result<T>,
+        mut event: impl FnMut(ListEvent<T>) -> Result<()>,
+    ) -> Result<()> {
+        self.expect(physical_type, true)?;
+        let optional_element = matches!(
+            self.leaf.shape,
+            Shape::List {
+                optional_element: true,
+                ..
+            }
+        );
+        let element_def = self.max_def - optional_element as u32;
+        let mut values = self.values(decode)?.into_iter();
+        let mut row_valid = None;
+        for (&def, &rep) in self.def_levels.iter().zip(&self.rep_levels) {
+            if rep == 0 {
+                if let Some(valid) = row_valid {
+                    event(ListEvent::End(valid))?;
+                }
+                row_valid = Some(def + 1 >= element_def);
+            }
+            if def == self.max_def {
+                let value = values.next().ok_or_else(|| self.missing_values())?;
+                event(ListEvent::Element(Some(value)))?;
+            } else if def >= element_def {
+                event(ListEvent::Element(None))?;
+            }
+        }
+        if let Some(valid) = row_valid {
+            event(ListEvent::End(valid))?;
+        }
+        Ok(())
+    }
+
+    /// Length of the first valid list, used when the writer did not record a list size.
+    fn first_list_len(&self) -> usize {
+        let element_def = match self.leaf.shape {
+            Shape::List {
+                optional_element, ..
+            } => self.max_def - optional_element as u32,
+            _ => self.max_def,
+        };
+        let mut levels = self.def_levels.iter().zip(&self.rep_levels).peekable();
+        while let Some((&def, _)) = levels.next() {
+            if def >= element_def {
+                let mut len = 1;
+                while let Some((_, 1..)) = levels.peek() {
+                    levels.next();
+                    len += 1;
+                }
+                return len;
+            }
+        }
+        0
+    }
+}
+
+pub trait ParquetRead: Sized {
+    /// Nulls are only accepted when `validity` is given; it receives one bit per row.
+    fn read(column: &ColumnData, validity: Option<&mut BitVec>) -> Result<Self>;
+}
+
+/// Arrays whose items are single parquet values, which lets `IdArray` intern dictionary pages.
+pub trait FlatArray: Array + Default {
+    fn physical_type() -> Option<PhysicalType>;
+
+    fn decode_item(decoder: &mut PlainDecoder<'_>) -> Result<Self::Item>;
+}
+
+impl<P: ParquetPrimitive> FlatArray for PrimitiveArray<P> {
+    #[inline]
+    fn physical_type() -> Option<PhysicalType> {
+        Some(P::PHYSICAL_TYPE)
+    }
+
+    #[inline]
+    fn decode_item(decoder: &mut PlainDecoder<'_>) -> Result<Self::Item> {
+        P::decode(decoder)
+    }
+}
+
+impl<P: ParquetPrimitive> FlatArray for ListArray<P> {
+    #[inline]
+    fn physical_type() -> Option<PhysicalType> {
+        P::BYTES.then_some(PhysicalType::ByteArray)
+    }
+
+    #[inline]
+    fn decode_item(decoder: &mut PlainDecoder<'_>) -> Result<Self::Item> {
+        decoder.byte_array().map(P::from_bytes)
+    }
+}
+
+impl<P: ParquetPrimitive> ParquetRead for PrimitiveArray<P> {
+    fn read(column: &ColumnData, validity: Option<&mut BitVec>) -> Result<Self> {
+        let mut array = Self::new();
+        column.read_flat(P::PHYSICAL_TYPE, validity, P::decode, |value| {
+            array.push(value.unwrap_or_default())
+        })?;
+        Ok(array)
+    }
+}
+
+impl<P: ParquetPrimitive> ParquetRead for ListArray<P> {
+    fn read(column: &ColumnData, mut validity: Option<&mut BitVec>) -> Result<Self> {
+        let mut array = Self::new();
+        if P::BYTES {
+            column.read_flat(
+                PhysicalType::ByteArray,
+                validity,
+                Self::decode_item,
+                |value| array.push(value.unwrap_or_default()),
+            )?;
+            return Ok(array);
+        }
+        column.read_lists(P::PHYSICAL_TYPE, P::decode, |event| {
+            match event {
+                ListEvent::Element(Some(value)) => array.data.push(value),
+                ListEvent::Element(None) => {
+                    return Err(Error::Schema(format!(
+                        "column {} contains null list elements",
+                        column.name
+                    )))
+                }
+                ListEvent::End(valid) => {
+                    column.push_validity(&mut validity, valid)?;
+                    array.offsets.push(array.data.len());
+                }
+            }
+            Ok(())
+        })?;
+        Ok(array)
+    }
+}
+
+impl<P: ParquetPrimitive> ParquetRead for OptionListArray<P> {
+    fn read(column: &ColumnData, mut validity: Option<&mut BitVec>) -> Result<Self> {
+        let list_size = match column.key_value("list_size") {
+            Some(size) => size
+                .parse()
+                .map_err(|_| Error::Format(format!("invalid list size {size:?}")))?,
+            None => column.first_list_len(),
+        };
+        let mut array = Self::new(list_size);
+        let mut len = 0;
+        column.read_lists(P::PHYSICAL_TYPE, P::decode, |event| {
+            match event {
+                ListEvent::Element(value) => {
+                    array.validity.push(value.is_some());
+                    array.data.push(value.unwrap_or_default());
+                    len += 1;
+                }
+                ListEvent::End(valid) => {
+                    column.push_validity(&mut validity, valid)?;
+                    if !valid {
+                        array.push_zero();
+                    } else if len != list_size {
+                        return Err(Error::Schema(format!(
+                            "column {} holds a list of {len} elements, expected {list_size}",
+                            column.name
+                        )));
+                    }
+                    len = 0;
+                }
+            }
+            Ok(())
+        })?;
+        Ok(array)
+    }
+}
+
+impl<A: ParquetRead + Array> ParquetRead for OptionArray<A> {
+    fn read(column: &ColumnData, validity: Option<&mut BitVec>) -> Result<Self> {
+        let mut inner = BitVec::default();
+        let data = A::read(column, Some(&mut inner))?;
+        if let Some(validity) = validity {
+            validity.extend(std::iter::repeat_n(true, data.len()));
+        }
+        Ok(OptionArray {
+            validity: inner,
+            data,
+        })
+    }
+}
+
+impl<A: FlatArray> ParquetRead for IdArray<A>
+where
+    for<'a, 'b> A::ItemRef<'a>: PartialEq<A::ItemRef<'b>>,
+    for<'a> A::ItemRef<'a>: Hash,
+{
+    fn read(column: &ColumnData, validity: Option<&mut BitVec>) -> Result<Self> {
+        let physical_type = A::physical_type().ok_or_else(|| {
+            Error::Schema(format!(
+                "column {} cannot be read into a dictionary of lists",
+                column.name
+            ))
+        })?;
+        let mut values = SlotMap::new(A::default());
+        let mut data = IdVec::new();
+        let mut scratch = BitVec::default();
+        column.read_flat(
+            physical_type,
+            Some(validity.unwrap_or(&mut scratch)),
+            |decoder| Ok(values.lookup_or_insert(A::decode_item(decoder)?)),
+            |id| data.push(id.unwrap_or(0)),
+        )?;
+        Ok(IdArray::from_parts(values, data, false, None))
+    }
+}
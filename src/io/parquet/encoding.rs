@@ -0,0 +1,247 @@
+use super::thrift::write_varint;
+use crate::io::{Error, Result};
+
+/// Number of bits needed to store `max`.
+#[inline]
+pub(crate) fn bit_width(max: u32) -> u8 {
+    (32 - max.leading_zeros()) as u8
+}
+
+#[inline]
+fn eof() -> Error {
+    Error::Format("unexpected end of page".into())
+}
+
+#[derive(Debug, Default)]
+pub struct PlainEncoder {
+    pub(crate) buffer: Vec<u8>,
+    bits: usize,
+}
+
+impl PlainEncoder {
+    /// Booleans are bit packed, so a page must not mix them with other values.
+    #[inline]
+    pub fn push_bool(&mut self, value: bool) {
+        if self.bits.is_multiple_of(8) {
+            self.buffer.push(0);
+        }
+        if value {
+            *self.buffer.last_mut().unwrap() |= 1 << (self.bits % 8);
+        }
+        self.bits += 1;
+    }
+
+    #[inline]
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    #[inline]
+    pub fn push_byte_array(&mut self, bytes: &[u8]) {
+        self.buffer
+            .extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        self.buffer.extend_from_slice(bytes);
+    }
+}
+
+pub struct PlainDecoder<'a> {
+    input: &'a [u8],
+    pos: usize,
+    bits: usize,
+}
+
+impl<'a> PlainDecoder<'a> {
+    #[inline]
+    pub(crate) fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            pos: 0,
+            bits: 0,
+        }
+    }
+
+    #[inline]
+    pub fn bool(&mut self) -> Result<bool> {
+        let byte = self.input.get(self.bits / 8).ok_or_else(eof)?;
+        let value = byte & (1 << (self.bits % 8)) != 0;
+        self.bits += 1;
+        Ok(value)
+    }
+
+    #[inline]
+    pub fn bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let bytes = self
+            .input
+            .get(self.pos..self.pos + N)
+            .ok_or_else(eof)?
+            .try_into()
+            .unwrap();
+        self.pos += N;
+        Ok(bytes)
+    }
+
+    #[inline]
+    pub fn byte_array(&mut self) -> Result<&'a [u8]> {
+        let len = u32::from_le_bytes(self.bytes()?) as usize;
+        let bytes = self.input.get(self.pos..self.pos + len).ok_or_else(eof)?;
+        self.pos += len;
+        Ok(bytes)
+    }
+}
+
+/// Encodes with the RLE / bit-packing hybrid: runs of at least 8 equal values are run length
+/// encoded, everything else is bit packed in groups of 8.
+pub(crate) fn encode_hybrid(values: &[u32], bit_width: u8, out: &mut Vec<u8>) {
+    let run_length = |start: usize| {
+        values[start..]
+            .iter()
+            .take_while(|&&v| v == values[start])
+            .count()
+    };
+    let value_bytes = (bit_width as usize).div_ceil(8);
+
+    let mut i = 0;
+    while i < values.len() {
+        let run = run_length(i);
+        if run >= 8 {
+            write_varint(out, (run as u64) << 1);
+            out.extend_from_slice(&values[i].to_le_bytes()[..value_bytes]);
+            i += run;
+            continue;
+        }
+
+        let start = i;
+        while i < values.len() && (i == start || run_length(i) < 8) {
+            i = (i + 8).min(values.len());
+        }
+        let groups = (i - start).div_ceil(8);
+        write_varint(out, (groups as u64) << 1 | 1);
+        let mut buffer = 0u64;
+        let mut filled = 0;
+        for id in 0..groups * 8 {
+            let value = values.get(start + id).filter(|_| start + id < i);
+            buffer |= (value.copied().unwrap_or(0) as u64) << filled;
+            filled += bit_width as usize;
+            while filled >= 8 {
+                out.push(buffer as u8);
+                buffer >>= 8;
+                filled -= 8;
+            }
+        }
+    }
+}
+
+/// Decodes `count` values and returns the number of bytes read.
+pub(crate) fn decode_hybrid(
+    input: &[u8],
+    bit_width: u8,
+    count: usize,
+    out: &mut Vec<u32>,
+) -> Result<usize> {
+    if bit_width > 32 {
+        return Err(Error::Format(format!("bit width {bit_width} exceeds 32")));
+    }
+    let value_bytes = (bit_width as usize).div_ceil(8);
+    let mask = if bit_width >= 32 {
+        u32::MAX
+    } else {
+        (1u32 << bit_width) - 1
+    };
+    let target = out.len() + count;
+    let mut pos = 0;
+
+    while out.len() < target {
+        let mut header = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = *input.get(pos).ok_or_else(eof)?;
+            pos += 1;
+            header |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+            if shift > 63 {
+                return Err(Error::Format("run header is longer than 10 bytes".into()));
+            }
+        }
+
+        if header & 1 == 0 {
+            let run = (header >> 1) as usize;
+            let mut bytes = [0; 4];
+            bytes[..value_bytes]
+                .copy_from_slice(input.get(pos..pos + value_bytes).ok_or_else(eof)?);
+            pos += value_bytes;
+            let value = u32::from_le_bytes(bytes);
+            out.extend(std::iter::repeat_n(value, run.min(target - out.len())));
+        } else {
+            let len = usize::try_from(header >> 1)
+                .ok()
+                .and_then(|groups| groups.checked_mul(8))
+                .ok_or_else(eof)?;
+            let packed = (len / 8)
+                .checked_mul(bit_width as usize)
+                .and_then(|size| input.get(pos..pos.checked_add(size)?))
+                .ok_or_else(eof)?;
+            pos += packed.len();
+            let mut buffer = 0u64;
+            let mut filled = 0;
+            let mut bytes = packed.iter();
+            for _ in 0..len {
+                while filled < bit_width as usize {
+                    buffer |= (*bytes.next().unwrap() as u64) << filled;
+                    filled += 8;
+                }
+                if out.len() < target {
+                    out.push(buffer as u32 & mask);
+                }
+                buffer >>= bit_width;
+                filled -= bit_width as usize;
+            }
+        }
+    }
+    Ok(pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bit_width, decode_hybrid, encode_hybrid};
+    use crate::io::Error;
+
+    #[test]
+    fn hybrid_round_trip() {
+        let values = (0..100)
+            .map(|i| if i < 30 { 5 } else { i % 7 })
+            .chain(std::iter::repeat_n(3, 20))
+            .chain([1, 2, 3])
+            .collect::<Vec<u32>>();
+        for width in [bit_width(7), 8, 13] {
+            let mut out = Vec::new();
+            encode_hybrid(&values, width, &mut out);
+            let mut decoded = Vec::new();
+            let read = decode_hybrid(&out, width, values.len(), &mut decoded).unwrap();
+            assert_eq!(read, out.len());
+            assert_eq!(decoded, values);
+        }
+    }
+
+    #[test]
+    fn corrupt_hybrid() {
+        let mut out = Vec::new();
+        let width = |width| decode_hybrid(&[2, 1, 0, 0, 0, 0], width, 1, &mut Vec::new());
+        assert!(width(32).is_ok());
+        assert!(matches!(width(33), Err(Error::Format(_))));
+        assert!(matches!(width(255), Err(Error::Format(_))));
+        let header = [0xff; 11];
+        assert!(matches!(
+            decode_hybrid(&header, 8, 1, &mut out),
+            Err(Error::Format(_))
+        ));
+        // A bit-packed run claiming close to `u64::MAX / 2` groups.
+        let huge = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+        assert!(matches!(
+            decode_hybrid(&huge, 8, 1, &mut out),
+            Err(Error::Format(_))
+        ));
+    }
+}
@@ -0,0 +1,328 @@
+//! The subset of `parquet.thrift` written and understood by this crate.
+
+use super::thrift::{CompactReader, CompactWriter, BINARY, I32, STRUCT};
+use crate::io::{Error, Result};
+
+pub(crate) const REQUIRED: i32 = 0;
+pub(crate) const OPTIONAL: i32 = 1;
+pub(crate) const REPEATED: i32 = 2;
+
+pub(crate) const ENCODING_PLAIN: i32 = 0;
+pub(crate) const ENCODING_PLAIN_DICTIONARY: i32 = 2;
+pub(crate) const ENCODING_RLE: i32 = 3;
+pub(crate) const ENCODING_RLE_DICTIONARY: i32 = 8;
+
+pub(crate) const PAGE_DATA: i32 = 0;
+pub(crate) const PAGE_DICTIONARY: i32 = 2;
+
+pub(crate) const CODEC_UNCOMPRESSED: i32 = 0;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SchemaElement {
+    pub(crate) physical_type: Option<i32>,
+    pub(crate) repetition: Option<i32>,
+    pub(crate) name: String,
+    pub(crate) num_children: Option<i32>,
+    pub(crate) converted_type: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ColumnMetaData {
+    pub(crate) physical_type: i32,
+    pub(crate) encodings: Vec<i32>,
+    pub(crate) path: Vec<String>,
+    pub(crate) codec: i32,
+    pub(crate) num_values: i64,
+    pub(crate) total_uncompressed_size: i64,
+    pub(crate) total_compressed_size: i64,
+    pub(crate) key_value: Vec<(String, String)>,
+    pub(crate) data_page_offset: i64,
+    pub(crate) dictionary_page_offset: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct RowGroup {
+    pub(crate) columns: Vec<ColumnMetaData>,
+    pub(crate) total_byte_size: i64,
+    pub(crate) num_rows: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct FileMetaData {
+    pub(crate) version: i32,
+    pub(crate) schema: Vec<SchemaElement>,
+    pub(crate) num_rows: i64,
+    pub(crate) row_groups: Vec<RowGroup>,
+    pub(crate) created_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct PageHeader {
+    pub(crate) page_type: i32,
+    pub(crate) uncompressed_page_size: i32,
+    pub(crate) compressed_page_size: i32,
+    pub(crate) num_values: i32,
+    pub(crate) encoding: i32,
+}
+
+fn key_values(writer: &mut CompactWriter<'_>, id: i16, key_value: &[(String, String)]) {
+    if key_value.is_empty() {
+        return;
+    }
+    writer.list(id, STRUCT, key_value.len());
+    for (key, value) in key_value {
+        writer.struct_begin(None);
+        writer.binary(1, key.as_bytes());
+        writer.binary(2, value.as_bytes());
+        writer.struct_end();
+    }
+}
+
+fn read_key_values(reader: &mut CompactReader<'_>) -> Result<Vec<(String, String)>> {
+    let (_, len) = reader.list()?;
+    let mut key_value = Vec::with_capacity(len.min(reader.remaining()));
+    for _ in 0..len {
+        let (mut key, mut value) = (String::new(), String::new());
+        reader.struct_begin();
+        while let Some((id, field_type)) = reader.field()? {
+            match id {
+                1 => key = reader.string()?,
+                2 => value = reader.string()?,
+                _ => reader.skip(field_type)?,
+            }
+        }
+        reader.struct_end();
+        key_value.push((key, value));
+    }
+    Ok(key_value)
+}
+
+impl FileMetaData {
+    pub(crate) fn write(&self, out: &mut Vec<u8>) {
+        let mut writer = CompactWriter::new(out);
+        writer.i32(1, self.version);
+        writer.list(2, STRUCT, self.schema.len());
+        for element in &self.schema {
+            writer.struct_begin(None);
+            if let Some(physical_type) = element.physical_type {
+                writer.i32(1, physical_type);
+            }
+            if let Some(repetition) = element.repetition {
+                writer.i32(3, repetition);
+            }
+            writer.binary(4, element.name.as_bytes());
+            if let Some(num_children) = element.num_children {
+                writer.i32(5, num_children);
+            }
+            if let Some(converted_type) = element.converted_type {
+                writer.i32(6, converted_type);
+            }
+            writer.struct_end();
+        }
+        writer.i64(3, self.num_rows);
+        writer.list(4, STRUCT, self.row_groups.len());
+        for row_group in &self.row_groups {
+            writer.struct_begin(None);
+            writer.list(1, STRUCT, row_group.columns.len());
+            for column in &row_group.columns {
+                writer.struct_begin(None);
+                writer.i64(
+                    2,
+                    column
+                        .dictionary_page_offset
+                        .unwrap_or(column.data_page_offset),
+                );
+                writer.struct_begin(Some(3));
+                writer.i32(1, column.physical_type);
+                writer.list(2, I32, column.encodings.len());
+                for encoding in &column.encodings {
+                    writer.raw_i32(*encoding);
+                }
+                writer.list(3, BINARY, column.path.len());
+                for name in &column.path {
+                    writer.raw_binary(name.as_bytes());
+                }
+                writer.i32(4, column.codec);
+                writer.i64(5, column.num_values);
+                writer.i64(6, column.total_uncompressed_size);
+                writer.i64(7, column.total_compressed_size);
+                key_values(&mut writer, 8, &column.key_value);
+                writer.i64(9, column.data_page_offset);
+                if let Some(offset) = column.dictionary_page_offset {
+                    writer.i64(11, offset);
+                }
+                writer.struct_end();
+                writer.struct_end();
+            }
+            writer.i64(2, row_group.total_byte_size);
+            writer.i64(3, row_group.num_rows);
+            writer.struct_end();
+        }
+        if let Some(created_by) = &self.created_by {
+            writer.binary(6, created_by.as_bytes());
+        }
+        writer.struct_end();
+    }
+
+    pub(crate) fn read(input: &[u8]) -> Result<Self> {
+        let mut reader = CompactReader::new(input);
+        let mut this = Self::default();
+        while let Some((id, field_type)) = reader.field()? {
+            match id {
+                1 => this.version = reader.i32()?,
+                2 => {
+                    let (_, len) = reader.list()?;
+                    for _ in 0..len {
+                        this.schema.push(read_schema_element(&mut reader)?);
+                    }
+                }
+                3 => this.num_rows = reader.i64()?,
+                4 => {
+                    let (_, len) = reader.list()?;
+                    for _ in 0..len {
+                        this.row_groups.push(read_row_group(&mut reader)?);
+                    }
+                }
+                6 => this.created_by = Some(reader.string()?),
+                _ => reader.skip(field_type)?,
+            }
+        }
+        Ok(this)
+    }
+}
+
+fn read_schema_element(reader: &mut CompactReader<'_>) -> Result<SchemaElement> {
+    let mut element = SchemaElement::default();
+    reader.struct_begin();
+    while let Some((id, field_type)) = reader.field()? {
+        match id {
+            1 => element.physical_type = Some(reader.i32()?),
+            3 => element.repetition = Some(reader.i32()?),
+            4 => element.name = reader.string()?,
+            5 => element.num_children = Some(reader.i32()?),
+            6 => element.converted_type = Some(reader.i32()?),
+            _ => reader.skip(field_type)?,
+        }
+    }
+    reader.struct_end();
+    Ok(element)
+}
+
+fn read_row_group(reader: &mut CompactReader<'_>) -> Result<RowGroup> {
+    let mut row_group = RowGroup::default();
+    reader.struct_begin();
+    while let Some((id, field_type)) = reader.field()? {
+        match id {
+            1 => {
+                let (_, len) = reader.list()?;
+                for _ in 0..len {
+                    row_group.columns.push(read_column_chunk(reader)?);
+                }
+            }
+            2 => row_group.total_byte_size = reader.i64()?,
+            3 => row_group.num_rows = reader.i64()?,
+            _ => reader.skip(field_type)?,
+        }
+    }
+    reader.struct_end();
+    Ok(row_group)
+}
+
+fn read_column_chunk(reader: &mut CompactReader<'_>) -> Result<ColumnMetaData> {
+    let mut column = None;
+    reader.struct_begin();
+    while let Some((id, field_type)) = reader.field()? {
+        match id {
+            1 => {
+                return Err(Error::Format(
+                    "external column chunks are not supported".into(),
+                ))
+            }
+            3 => column = Some(read_column_metadata(reader)?),
+            _ => reader.skip(field_type)?,
+        }
+    }
+    reader.struct_end();
+    column.ok_or_else(|| Error::Format("column chunk without metadata".into()))
+}
+
+fn read_column_metadata(reader: &mut CompactReader<'_>) -> Result<ColumnMetaData> {
+    let mut column = ColumnMetaData::default();
+    reader.struct_begin();
+    while let Some((id, field_type)) = reader.field()? {
+        match id {
+            1 => column.physical_type = reader.i32()?,
+            2 => {
+                let (_, len) = reader.list()?;
+                for _ in 0..len {
+                    column.encodings.push(reader.i32()?);
+                }
+            }
+            3 => {
+                let (_, len) = reader.list()?;
+                for _ in 0..len {
+                    column.path.push(reader.string()?);
+                }
+            }
+            4 => column.codec = reader.i32()?,
+            5 => column.num_values = reader.i64()?,
+            6 => column.total_uncompressed_size = reader.i64()?,
+            7 => column.total_compressed_size = reader.i64()?,
+            8 => column.key_value = read_key_values(reader)?,
+            9 => column.data_page_offset = reader.i64()?,
+            11 => column.dictionary_page_offset = Some(reader.i64()?),
+            _ => reader.skip(field_type)?,
+        }
+    }
+    reader.struct_end();
+    Ok(column)
+}
+
+impl PageHeader {
+    pub(crate) fn write(&self, out: &mut Vec<u8>) {
+        let mut writer = CompactWriter::new(out);
+        writer.i32(1, self.page_type);
+        writer.i32(2, self.uncompressed_page_size);
+        writer.i32(3, self.compressed_page_size);
+        if self.page_type == PAGE_DICTIONARY {
+            writer.struct_begin(Some(7));
+            writer.i32(1, self.num_values);
+            writer.i32(2, self.encoding);
+            writer.struct_end();
+        } else {
+            writer.struct_begin(Some(5));
+            writer.i32(1, self.num_values);
+            writer.i32(2, self.encoding);
+            writer.i32(3, ENCODING_RLE);
+            writer.i32(4, ENCODING_RLE);
+            writer.struct_end();
+        }
+        writer.struct_end();
+    }
+
+    /// Returns the header and its encoded length.
+    pub(crate) fn read(input: &[u8]) -> Result<(Self, usize)> {
+        let mut reader = CompactReader::new(input);
+        let mut this = Self::default();
+        while let Some((id, field_type)) = reader.field()? {
+            match id {
+                1 => this.page_type = reader.i32()?,
+                2 => this.uncompressed_page_size = reader.i32()?,
+                3 => this.compressed_page_size = reader.i32()?,
+                5 | 7 => {
+                    reader.struct_begin();
+                    while let Some((id, field_type)) = reader.field()? {
+                        match id {
+                            1 => this.num_values = reader.i32()?,
+                            2 => this.encoding = reader.i32()?,
+                            _ => reader.skip(field_type)?,
+                        }
+                    }
+                    reader.struct_end();
+                }
+                _ => reader.skip(field_type)?,
+            }
+        }
+        Ok((this, reader.position()))
+    }
+}
@@ -0,0 +1,100 @@
+mod column;
+mod encoding;
+mod metadata;
+mod reader;
+mod thrift;
+mod writer;
+
+pub use column::{
+    ColumnData, EncodedColumn, FlatArray, ParquetPrimitive, ParquetRead, ParquetWrite,
+};
+pub use encoding::{PlainDecoder, PlainEncoder};
+pub use reader::Reader;
+pub use writer::Writer;
+
+pub(crate) const MAGIC: &[u8; 4] = b"PAR1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhysicalType {
+    Boolean = 0,
+    Int32 = 1,
+    Int64 = 2,
+    Float = 4,
+    Double = 5,
+    ByteArray = 6,
+}
+
+impl PhysicalType {
+    pub(crate) fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(PhysicalType::Boolean),
+            1 => Some(PhysicalType::Int32),
+            2 => Some(PhysicalType::Int64),
+            4 => Some(PhysicalType::Float),
+            5 => Some(PhysicalType::Double),
+            6 => Some(PhysicalType::ByteArray),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertedType {
+    Utf8 = 0,
+    List = 3,
+    Uint8 = 11,
+    Uint16 = 12,
+    Uint32 = 13,
+    Uint64 = 14,
+    Int8 = 15,
+    Int16 = 16,
+}
+
+impl ConvertedType {
+    pub(crate) fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(ConvertedType::Utf8),
+            3 => Some(ConvertedType::List),
+            11 => Some(ConvertedType::Uint8),
+            12 => Some(ConvertedType::Uint16),
+            13 => Some(ConvertedType::Uint32),
+            14 => Some(ConvertedType::Uint64),
+            15 => Some(ConvertedType::Int8),
+            16 => Some(ConvertedType::Int16),
+            _ => None,
+        }
+    }
+}
+
+/// How a leaf column nests; lists use the standard three-level `LIST` layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Required,
+    Optional,
+    List {
+        optional: bool,
+        optional_element: bool,
+    },
+}
+
+impl Shape {
+    /// Maximum definition and repetition levels.
+    #[inline]
+    pub fn levels(&self) -> (u32, u32) {
+        match *self {
+            Shape::Required => (0, 0),
+            Shape::Optional => (1, 0),
+            Shape::List {
+                optional,
+                optional_element,
+            } => (1 + optional as u32 + optional_element as u32, 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Leaf {
+    pub physical_type: PhysicalType,
+    pub converted_type: Option<ConvertedType>,
+    pub shape: Shape,
+}
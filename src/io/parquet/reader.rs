@@ -0,0 +1,473 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use super::{
+    column::{ColumnData, ParquetRead, ValueChunk, ValuePage},
+    encoding::{bit_width, decode_hybrid},
+    metadata::{
+        ColumnMetaData, FileMetaData, PageHeader, SchemaElement, CODEC_UNCOMPRESSED,
+        ENCODING_PLAIN, ENCODING_PLAIN_DICTIONARY, ENCODING_RLE_DICTIONARY, OPTIONAL, PAGE_DATA,
+        PAGE_DICTIONARY, REPEATED, REQUIRED,
+    },
+    ConvertedType, Leaf, PhysicalType, Shape, MAGIC,
+};
+use crate::io::{Error, Result};
+
+#[derive(Debug)]
+struct LeafColumn {
+    name: String,
+    /// `None` for physical types or nesting the crate has no array for.
+    leaf: Option<Leaf>,
+    max_def: u32,
+    max_rep: u32,
+}
+
+pub struct Reader<R> {
+    inner: R,
+    metadata: FileMetaData,
+    columns: Vec<LeafColumn>,
+}
+
+impl<R: Read + Seek> Reader<R> {
+    pub fn new(mut inner: R) -> Result<Self> {
+        let len = inner.seek(SeekFrom::End(0))?;
+        if len < 12 {
+            return Err(Error::Format("file is too small to be parquet".into()));
+        }
+        let mut tail = [0; 8];
+        inner.seek(SeekFrom::End(-8))?;
+        inner.read_exact(&mut tail)?;
+        if &tail[4..] != MAGIC {
+            return Err(Error::Format("missing parquet magic".into()));
+        }
+        let footer_len = u32::from_le_bytes(tail[..4].try_into().unwrap()) as u64;
+        if footer_len + 12 > len {
+            return Err(Error::Format("footer length exceeds the file".into()));
+        }
+        let mut footer = vec![0; footer_len as usize];
+        inner.seek(SeekFrom::End(-8 - footer_len as i64))?;
+        inner.read_exact(&mut footer)?;
+        let metadata = FileMetaData::read(&footer)?;
+
+        let mut columns = Vec::new();
+        let root = metadata
+            .schema
+            .first()
+            .ok_or_else(|| Error::Format("empty schema".into()))?;
+        let mut pos = 1;
+        for _ in 0..root.num_children.unwrap_or(0) {
+            walk(&metadata.schema, &mut pos, &mut Vec::new(), &mut columns)?;
+        }
+        Ok(Self {
+            inner,
+            metadata,
+            columns,
+        })
+    }
+
+    #[inline]
+    pub fn num_rows(&self) -> usize {
+        self.metadata.num_rows as usize
+    }
+
+    #[inline]
+    pub fn num_row_groups(&self) -> usize {
+        self.metadata.row_groups.len()
+    }
+
+    /// Leaf columns in file order; the leaf is `None` if no array can read the column.
+    pub fn columns(&self) -> impl Iterator<Item = (&str, Option<Leaf>)> {
+        self.columns
+            .iter()
+            .map(|column| (column.name.as_str(), column.leaf))
+    }
+
+    pub fn column(&mut self, name: &str) -> Result<ColumnData> {
+        let index = self
+            .columns
+            .iter()
+            .position(|column| column.name == name)
+            .ok_or_else(|| Error::Schema(format!("unknown column {name}")))?;
+        let column = &self.columns[index];
+        let leaf = column.leaf.ok_or_else(|| {
+            Error::Schema(format!("column {name} uses an unsupported type or nesting"))
+        })?;
+        let mut data = ColumnData {
+            name: name.into(),
+            leaf,
+            max_def: column.max_def,
+            num_rows: self.num_rows(),
+            key_value: Vec::new(),
+            def_levels: Vec::new(),
+            rep_levels: Vec::new(),
+            chunks: Vec::new(),
+        };
+        let (max_def, max_rep) = (column.max_def, column.max_rep);
+        for row_group in &self.metadata.row_groups {
+            let chunk = row_group
+                .columns
+                .get(index)
+                .ok_or_else(|| Error::Format("row group is missing a column".into()))?;
+            if data.key_value.is_empty() {
+                data.key_value = chunk.key_value.clone();
+            }
+            let bytes = read_chunk(&mut self.inner, chunk)?;
+            let pages = read_pages(&bytes, chunk, max_def, max_rep, &mut data)?;
+            data.chunks.push(pages);
+        }
+        Ok(data)
+    }
+
+    #[inline]
+    pub fn read<A: ParquetRead>(&mut self, name: &str) -> Result<A> {
+        A::read(&self.column(name)?, None)
+    }
+}
+
+fn walk(
+    schema: &[SchemaElement],
+    pos: &mut usize,
+    path: &mut Vec<(String, i32)>,
+    out: &mut Vec<LeafColumn>,
+) -> Result<()> {
+    let element = schema
+        .get(*pos)
+        .ok_or_else(|| Error::Format("truncated schema".into()))?;
+    *pos += 1;
+    path.push((element.name.clone(), element.repetition.unwrap_or(REQUIRED)));
+    match element.num_children {
+        Some(children) if children > 0 => {
+            for _ in 0..children {
+                walk(schema, pos, path, out)?;
+            }
+        }
+        _ => out.push(leaf_column(element, path)),
+    }
+    path.pop();
+    Ok(())
+}
+
+fn leaf_column(element: &SchemaElement, path: &[(String, i32)]) -> LeafColumn {
+    let max_def = path.iter().filter(|(_, r)| *r != REQUIRED).count() as u32;
+    let max_rep = path.iter().filter(|(_, r)| *r == REPEATED).count() as u32;
+    let repeated = path.iter().position(|(_, r)| *r == REPEATED);
+    let name_len = match repeated {
+        Some(id) if id + 1 < path.len() => id,
+        _ => path.len(),
+    };
+    let name = path[..name_len]
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(".");
+
+    let shape = match (max_rep, repeated) {
+        (0, _) if max_def == 0 => Some(Shape::Required),
+        (0, _) => Some(Shape::Optional),
+        (1, Some(id)) => Some(Shape::List {
+            optional: path[..id].iter().any(|(_, r)| *r == OPTIONAL),
+            optional_element: path[path.len() - 1].1 == OPTIONAL,
+        }),
+        _ => None,
+    };
+    let physical_type = element.physical_type.and_then(PhysicalType::from_i32);
+    LeafColumn {
+        name,
+        leaf: shape.zip(physical_type).map(|(shape, physical_type)| Leaf {
+            physical_type,
+            converted_type: element.converted_type.and_then(ConvertedType::from_i32),
+            shape,
+        }),
+        max_def,
+        max_rep,
+    }
+}
+
+fn read_chunk<R: Read + Seek>(inner: &mut R, chunk: &ColumnMetaData) -> Result<Vec<u8>> {
+    if chunk.codec != CODEC_UNCOMPRESSED {
+        return Err(Error::Format(format!(
+            "compression codec {} is not supported",
+            chunk.codec
+        )));
+    }
+    let start = match chunk.dictionary_page_offset {
+        Some(offset) if offset > 0 => offset.min(chunk.data_page_offset),
+        _ => chunk.data_page_offset,
+    };
+    let file_len = inner.seek(SeekFrom::End(0))?;
+    let (start, len) = match (
+        u64::try_from(start),
+        u64::try_from(chunk.total_compressed_size),
+    ) {
+        (Ok(start), Ok(len)) if start.checked_add(len).is_some_and(|end| end <= file_len) => {
+            (start, len)
+        }
+        _ => {
+            return Err(Error::Format(format!(
+                "column chunk of {} bytes at offset {start} does not fit a file of {file_len} \
+                 bytes",
+                chunk.total_compressed_size
+            )))
+        }
+    };
+    let mut bytes = vec![0; len as usize];
+    inner.seek(SeekFrom::Start(start))?;
+    inner.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_levels(input: &[u8], max: u32, count: usize, out: &mut Vec<u32>) -> Result<usize> {
+    let len = input
+        .get(..4)
+        .map(|len| u32::from_le_bytes(len.try_into().unwrap()) as usize)
+        .ok_or_else(|| Error::Format("truncated levels".into()))?;
+    let levels = input
+        .get(4..4 + len)
+        .ok_or_else(|| Error::Format("truncated levels".into()))?;
+    decode_hybrid(levels, bit_width(max), count, out)?;
+    Ok(4 + len)
+}
+
+fn read_pages(
+    bytes: &[u8],
+    chunk: &ColumnMetaData,
+    max_def: u32,
+    max_rep: u32,
+    data: &mut ColumnData,
+) -> Result<ValueChunk> {
+    let mut values = ValueChunk::default();
+    let mut remaining = chunk.num_values as usize;
+    let mut pos = 0;
+    while remaining > 0 {
+        let (header, header_len) = PageHeader::read(&bytes[pos..])?;
+        pos += header_len;
+        let body = usize::try_from(header.compressed_page_size)
+            .ok()
+            .and_then(|size| bytes.get(pos..pos.checked_add(size)?))
+            .ok_or_else(|| Error::Format("page exceeds the column chunk".into()))?;
+        pos += body.len();
+        let count = header.num_values as usize;
+
+        match header.page_type {
+            PAGE_DICTIONARY => values.dictionary = Some((count, body.to_vec())),
+            PAGE_DATA => {
+                let mut offset = 0;
+                if max_rep > 0 {
+                    offset += read_levels(body, max_rep, count, &mut data.rep_levels)?;
+                }
+                let present = if max_def > 0 {
+                    let start = data.def_levels.len();
+                    offset += read_levels(&body[offset..], max_def, count, &mut data.def_levels)?;
+                    data.def_levels[start..]
+                        .iter()
+                        .filter(|&&def| def == max_def)
+                        .count()
+                } else {
+                    count
+                };
+                let body = &body[offset..];
+                values.pages.push(match header.encoding {
+                    ENCODING_PLAIN => ValuePage::Plain {
+                        count: present,
+                        values: body.to_vec(),
+                    },
+                    ENCODING_PLAIN_DICTIONARY | ENCODING_RLE_DICTIONARY => {
+                        let mut indices = Vec::with_capacity(present);
+                        if present > 0 {
+                            let width = *body
+                                .first()
+                                .ok_or_else(|| Error::Format("missing bit width".into()))?;
+                            decode_hybrid(&body[1..], width, present, &mut indices)?;
+                        }
+                        ValuePage::Dictionary(indices)
+                    }
+                    encoding => {
+                        return Err(Error::Format(format!(
+                            "encoding {encoding} is not supported"
+                        )))
+                    }
+                });
+                remaining = remaining.saturating_sub(count);
+            }
+            page_type => {
+                return Err(Error::Format(format!(
+                    "page type {page_type} is not supported"
+                )))
+            }
+        }
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::{read_chunk, Reader};
+    use crate::{
+        array::{
+            id::IdArray, list::ListArray, primitive::PrimitiveArray, Array, OptionArray,
+            OptionListArray,
+        },
+        io::{
+            parquet::{metadata::ColumnMetaData, PhysicalType, Shape, Writer},
+            Error,
+        },
+        scalar::list::OptionList,
+    };
+
+    #[test]
+    fn round_trip() {
+        let mut writer = Writer::new(Vec::new()).unwrap();
+        for group in 0..2 {
+            let mut ids = PrimitiveArray::<u32>::new();
+            let mut scores = OptionArray::new(PrimitiveArray::<f64>::new());
+            let mut flags = PrimitiveArray::<bool>::new();
+            let mut tags = ListArray::<i32>::new();
+            let mut names = ListArray::<u8>::new();
+            let mut points = OptionListArray::<i64>::new(2);
+            let mut cities = IdArray::new(ListArray::<u8>::new());
+            for row in 0..20 {
+                ids.push(group * 20 + row);
+                scores.push((row % 3 != 0).then_some(row as f64 / 2.0));
+                flags.push(row % 2 == 0);
+                tags.push((0..row as i32 % 4).collect());
+                names.push(format!("name {row}").into_bytes());
+                points.push(OptionList::from([
+                    Some(row as i64),
+                    (row % 5 != 0).then_some(-1),
+                ]));
+                cities.push(match row % 4 {
+                    0 => None,
+                    1 => Some(b"paris".to_vec()),
+                    _ => Some(b"oslo".to_vec()),
+                });
+            }
+            writer
+                .write(&[
+                    ("id", &ids),
+                    ("score", &scores),
+                    ("flag", &flags),
+                    ("tags", &tags),
+                    ("name", &names),
+                    ("point", &points),
+                    ("city", &cities),
+                ])
+                .unwrap();
+        }
+        let output = writer.finish().unwrap();
+
+        let mut reader = Reader::new(Cursor::new(output)).unwrap();
+        assert_eq!(reader.num_rows(), 40);
+        assert_eq!(reader.num_row_groups(), 2);
+        let leaves = reader.columns().collect::<Vec<_>>();
+        assert_eq!(leaves.len(), 7);
+        assert_eq!(leaves[3].0, "tags");
+        let tags_leaf = leaves[3].1.unwrap();
+        assert_eq!(tags_leaf.physical_type, PhysicalType::Int32);
+        assert!(matches!(tags_leaf.shape, Shape::List { .. }));
+
+        let ids = reader.read::<PrimitiveArray<u32>>("id").unwrap();
+        assert_eq!(ids.as_slice(), (0..40).collect::<Vec<_>>());
+        let scores = reader
+            .read::<OptionArray<PrimitiveArray<f64>>>("score")
+            .unwrap();
+        assert_eq!(scores.get(0), Some(None));
+        assert_eq!(scores.get(21), Some(Some(&0.5)));
+        assert_eq!(scores.null_count(), 14);
+        let flags = reader.read::<PrimitiveArray<bool>>("flag").unwrap();
+        assert_eq!(flags.get(22), Some(&true));
+        let tags = reader.read::<ListArray<i32>>("tags").unwrap();
+        assert_eq!(tags.get(0), Some(&[][..]));
+        assert_eq!(tags.get(23), Some(&[0, 1, 2][..]));
+        let names = reader.read::<ListArray<u8>>("name").unwrap();
+        assert_eq!(names.get(39), Some(&b"name 19"[..]));
+        let points = reader.read::<OptionListArray<i64>>("point").unwrap();
+        assert_eq!(points.len(), 40);
+        assert_eq!(points.get(25).unwrap().data, [5, 0]);
+        assert_eq!(points.get(25).unwrap().validity.get(1), Some(false));
+        assert_eq!(points.get(26).unwrap().data, [6, -1]);
+        let cities = reader.read::<IdArray<ListArray<u8>>>("city").unwrap();
+        assert_eq!(cities.len(), 40);
+        assert_eq!(cities.get(20), Some(None));
+        assert_eq!(cities.get(21), Some(Some(&b"paris"[..])));
+        assert_eq!(cities.get(38), Some(Some(&b"oslo"[..])));
+        assert_eq!(cities.values.data.len(), 2);
+    }
+
+    #[test]
+    fn nulls_and_mismatches() {
+        let mut scores = OptionArray::new(PrimitiveArray::<i64>::new());
+        let mut lists = OptionArray::new(ListArray::<f64>::new());
+        for row in 0..4 {
+            scores.push((row != 1).then_some(row));
+            lists.push((row != 2).then(|| vec![row as f64; row as usize]));
+        }
+        let mut writer = Writer::new(Vec::new()).unwrap();
+        writer
+            .write(&[("score", &scores), ("list", &lists)])
+            .unwrap();
+        assert!(writer.write(&[("score", &scores)]).is_err());
+        let output = writer.finish().unwrap();
+
+        let mut reader = Reader::new(Cursor::new(output)).unwrap();
+        assert!(reader.read::<PrimitiveArray<i64>>("score").is_err());
+        assert!(reader.read::<PrimitiveArray<f64>>("score").is_err());
+        assert!(reader.read::<PrimitiveArray<i64>>("missing").is_err());
+        let lists = reader.read::<OptionArray<ListArray<f64>>>("list").unwrap();
+        assert_eq!(lists.get(0), Some(Some(&[][..])));
+        assert_eq!(lists.get(2), Some(None));
+        assert_eq!(lists.get(3), Some(Some(&[3.0, 3.0, 3.0][..])));
+        let scores = reader
+            .read::<IdArray<PrimitiveArray<i64>>>("score")
+            .unwrap();
+        assert_eq!(scores.get(1), Some(None));
+        assert_eq!(scores.get(3), Some(Some(&3)));
+
+        assert!(Reader::new(Cursor::new(b"PAR1 not parquet".to_vec())).is_err());
+    }
+
+    #[test]
+    fn corrupt_chunk_sizes() {
+        let mut chunk = ColumnMetaData {
+            data_page_offset: 4,
+            total_compressed_size: 8,
+            ..Default::default()
+        };
+        let mut file = Cursor::new(vec![0u8; 16]);
+        assert_eq!(read_chunk(&mut file, &chunk).unwrap().len(), 8);
+        for size in [-1, 13, i64::MAX] {
+            chunk.total_compressed_size = size;
+            assert!(matches!(
+                read_chunk(&mut file, &chunk),
+                Err(Error::Format(_))
+            ));
+        }
+        chunk.total_compressed_size = 8;
+        chunk.data_page_offset = -4;
+        assert!(matches!(
+            read_chunk(&mut file, &chunk),
+            Err(Error::Format(_))
+        ));
+    }
+
+    #[test]
+    fn corrupt_footer() {
+        let open = |footer: &[u8]| {
+            let mut file = b"PAR1".to_vec();
+            file.extend_from_slice(footer);
+            file.extend_from_slice(&(footer.len() as u32).to_le_bytes());
+            file.extend_from_slice(b"PAR1");
+            Reader::new(Cursor::new(file))
+        };
+        let huge = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+        // An unknown binary field of length u64::MAX.
+        let binary = [&[0xf8][..], &huge, &[0x00]].concat();
+        // Field 32767 followed by a field id delta past i16::MAX.
+        let field_id = [0x08, 0xfe, 0xff, 0x03, 0x00, 0x18, 0x00, 0x00];
+        // Column key-value metadata claiming u64::MAX entries.
+        let key_value = [&[0x49, 0x1c, 0x19, 0x1c, 0x3c, 0x89, 0xfc][..], &huge].concat();
+        for footer in [&binary[..], &field_id, &key_value] {
+            assert!(matches!(open(footer), Err(Error::Format(_))));
+        }
+    }
+}
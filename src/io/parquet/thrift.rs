@@ -0,0 +1,335 @@
+//! Minimal thrift compact protocol, enough for the parquet footer and page headers.
+
+use crate::io::{Error, Result};
+
+pub(crate) const BOOL_TRUE: u8 = 1;
+pub(crate) const BOOL_FALSE: u8 = 2;
+pub(crate) const BYTE: u8 = 3;
+pub(crate) const I16: u8 = 4;
+pub(crate) const I32: u8 = 5;
+pub(crate) const I64: u8 = 6;
+pub(crate) const DOUBLE: u8 = 7;
+pub(crate) const BINARY: u8 = 8;
+pub(crate) const LIST: u8 = 9;
+pub(crate) const SET: u8 = 10;
+pub(crate) const MAP: u8 = 11;
+pub(crate) const STRUCT: u8 = 12;
+
+#[inline]
+pub(crate) fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+#[inline]
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+pub(crate) struct CompactWriter<'a> {
+    out: &'a mut Vec<u8>,
+    last: i16,
+    stack: Vec<i16>,
+}
+
+impl<'a> CompactWriter<'a> {
+    #[inline]
+    pub(crate) fn new(out: &'a mut Vec<u8>) -> Self {
+        Self {
+            out,
+            last: 0,
+            stack: Vec::new(),
+        }
+    }
+
+    fn field(&mut self, id: i16, field_type: u8) {
+        let delta = id - self.last;
+        if (1..=15).contains(&delta) {
+            self.out.push((delta as u8) << 4 | field_type);
+        } else {
+            self.out.push(field_type);
+            write_varint(self.out, zigzag(id as i64));
+        }
+        self.last = id;
+    }
+
+    #[inline]
+    pub(crate) fn i32(&mut self, id: i16, value: i32) {
+        self.field(id, I32);
+        write_varint(self.out, zigzag(value as i64));
+    }
+
+    #[inline]
+    pub(crate) fn i64(&mut self, id: i16, value: i64) {
+        self.field(id, I64);
+        write_varint(self.out, zigzag(value));
+    }
+
+    #[inline]
+    pub(crate) fn binary(&mut self, id: i16, value: &[u8]) {
+        self.field(id, BINARY);
+        self.raw_binary(value);
+    }
+
+    #[inline]
+    pub(crate) fn raw_binary(&mut self, value: &[u8]) {
+        write_varint(self.out, value.len() as u64);
+        self.out.extend_from_slice(value);
+    }
+
+    #[inline]
+    pub(crate) fn raw_i32(&mut self, value: i32) {
+        write_varint(self.out, zigzag(value as i64));
+    }
+
+    pub(crate) fn list(&mut self, id: i16, element_type: u8, len: usize) {
+        self.field(id, LIST);
+        if len < 15 {
+            self.out.push((len as u8) << 4 | element_type);
+        } else {
+            self.out.push(0xf0 | element_type);
+            write_varint(self.out, len as u64);
+        }
+    }
+
+    /// Starts a struct field; `id` is `None` for list elements.
+    #[inline]
+    pub(crate) fn struct_begin(&mut self, id: Option<i16>) {
+        if let Some(id) = id {
+            self.field(id, STRUCT);
+        }
+        self.stack.push(self.last);
+        self.last = 0;
+    }
+
+    #[inline]
+    pub(crate) fn struct_end(&mut self) {
+        self.out.push(0);
+        self.last = self.stack.pop().unwrap_or(0);
+    }
+}
+
+pub(crate) struct CompactReader<'a> {
+    input: &'a [u8],
+    pos: usize,
+    last: i16,
+    stack: Vec<i16>,
+}
+
+impl<'a> CompactReader<'a> {
+    #[inline]
+    pub(crate) fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            pos: 0,
+            last: 0,
+            stack: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Bytes left to read, which bounds how many list elements can still follow.
+    #[inline]
+    pub(crate) fn remaining(&self) -> usize {
+        self.input.len() - self.pos
+    }
+
+    #[inline]
+    fn byte(&mut self) -> Result<u8> {
+        let byte = *self
+            .input
+            .get(self.pos)
+            .ok_or_else(|| Error::Format("unexpected end of thrift data".into()))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            if shift >= 64 {
+                return Err(Error::Format("varint overflow".into()));
+            }
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    #[inline]
+    fn zigzag(&mut self) -> Result<i64> {
+        let value = self.varint()?;
+        Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+
+    /// Returns the next field id and type, or `None` at the end of the struct.
+    pub(crate) fn field(&mut self) -> Result<Option<(i16, u8)>> {
+        let header = self.byte()?;
+        if header == 0 {
+            return Ok(None);
+        }
+        let field_type = header & 0x0f;
+        let delta = (header >> 4) as i16;
+        let id = if delta == 0 {
+            self.zigzag()? as i16
+        } else {
+            self.last
+                .checked_add(delta)
+                .ok_or_else(|| Error::Format("thrift field id overflow".into()))?
+        };
+        self.last = id;
+        Ok(Some((id, field_type)))
+    }
+
+    #[inline]
+    pub(crate) fn i32(&mut self) -> Result<i32> {
+        Ok(self.zigzag()? as i32)
+    }
+
+    #[inline]
+    pub(crate) fn i64(&mut self) -> Result<i64> {
+        self.zigzag()
+    }
+
+    pub(crate) fn binary(&mut self) -> Result<&'a [u8]> {
+        let len = self.varint()? as usize;
+        let end = self
+            .pos
+            .checked_add(len)
+            .ok_or_else(|| Error::Format("thrift binary length overflow".into()))?;
+        let bytes = self
+            .input
+            .get(self.pos..end)
+            .ok_or_else(|| Error::Format("unexpected end of thrift data".into()))?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    #[inline]
+    pub(crate) fn string(&mut self) -> Result<String> {
+        String::from_utf8(self.binary()?.to_vec())
+            .map_err(|_| Error::Format("invalid utf-8 in thrift string".into()))
+    }
+
+    pub(crate) fn list(&mut self) -> Result<(u8, usize)> {
+        let header = self.byte()?;
+        let len = match header >> 4 {
+            15 => self.varint()? as usize,
+            len => len as usize,
+        };
+        Ok((header & 0x0f, len))
+    }
+
+    #[inline]
+    pub(crate) fn struct_begin(&mut self) {
+        self.stack.push(self.last);
+        self.last = 0;
+    }
+
+    #[inline]
+    pub(crate) fn struct_end(&mut self) {
+        self.last = self.stack.pop().unwrap_or(0);
+    }
+
+    pub(crate) fn skip(&mut self, field_type: u8) -> Result<()> {
+        match field_type {
+            BOOL_TRUE | BOOL_FALSE => {}
+            BYTE => {
+                self.byte()?;
+            }
+            I16 | I32 | I64 => {
+                self.varint()?;
+            }
+            DOUBLE => {
+                for _ in 0..8 {
+                    self.byte()?;
+                }
+            }
+            BINARY => {
+                self.binary()?;
+            }
+            LIST | SET => {
+                let (element_type, len) = self.list()?;
+                for _ in 0..len {
+                    if element_type == BOOL_TRUE || element_type == BOOL_FALSE {
+                        self.byte()?;
+                    } else {
+                        self.skip(element_type)?;
+                    }
+                }
+            }
+            MAP => {
+                let len = self.varint()? as usize;
+                if len > 0 {
+                    let types = self.byte()?;
+                    for _ in 0..len {
+                        self.skip(types >> 4)?;
+                        self.skip(types & 0x0f)?;
+                    }
+                }
+            }
+            STRUCT => {
+                self.struct_begin();
+                while let Some((_, field_type)) = self.field()? {
+                    self.skip(field_type)?;
+                }
+                self.struct_end();
+            }
+            _ => return Err(Error::Format(format!("unknown thrift type {field_type}"))),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompactReader, CompactWriter, BINARY, I32};
+
+    #[test]
+    fn round_trip() {
+        let mut out = Vec::new();
+        let mut writer = CompactWriter::new(&mut out);
+        writer.i32(1, -7);
+        writer.list(2, BINARY, 2);
+        writer.raw_binary(b"a");
+        writer.raw_binary(b"bc");
+        writer.struct_begin(Some(20));
+        writer.i64(1, 1 << 40);
+        writer.struct_end();
+        writer.list(21, I32, 20);
+        for i in 0..20 {
+            writer.raw_i32(i);
+        }
+        writer.struct_end();
+
+        let mut reader = CompactReader::new(&out);
+        assert_eq!(reader.field().unwrap(), Some((1, I32)));
+        assert_eq!(reader.i32().unwrap(), -7);
+        let (_, list_type) = reader.field().unwrap().unwrap();
+        reader.skip(list_type).unwrap();
+        assert_eq!(reader.field().unwrap().map(|(id, _)| id), Some(20));
+        reader.struct_begin();
+        reader.field().unwrap();
+        assert_eq!(reader.i64().unwrap(), 1 << 40);
+        assert_eq!(reader.field().unwrap(), None);
+        reader.struct_end();
+        assert_eq!(reader.field().unwrap().map(|(id, _)| id), Some(21));
+        assert_eq!(reader.list().unwrap(), (I32, 20));
+        for i in 0..20 {
+            assert_eq!(reader.i32().unwrap(), i);
+        }
+        assert_eq!(reader.field().unwrap(), None);
+        assert_eq!(reader.position(), out.len());
+    }
+}
@@ -0,0 +1,227 @@
+use std::io::Write;
+
+use super::{
+    column::{EncodedColumn, ParquetWrite},
+    encoding::{bit_width, encode_hybrid},
+    metadata::{
+        ColumnMetaData, FileMetaData, PageHeader, RowGroup, SchemaElement, CODEC_UNCOMPRESSED,
+        ENCODING_PLAIN, ENCODING_RLE, ENCODING_RLE_DICTIONARY, OPTIONAL, PAGE_DATA,
+        PAGE_DICTIONARY, REPEATED, REQUIRED,
+    },
+    ConvertedType, Leaf, Shape, MAGIC,
+};
+use crate::io::{Error, Result};
+
+/// Writes every call to [`Writer::write`] as one row group of uncompressed pages.
+pub struct Writer<W: Write> {
+    inner: W,
+    offset: usize,
+    schema: Option<Vec<(String, Leaf)>>,
+    row_groups: Vec<RowGroup>,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(mut inner: W) -> Result<Self> {
+        inner.write_all(MAGIC)?;
+        Ok(Self {
+            inner,
+            offset: MAGIC.len(),
+            schema: None,
+            row_groups: Vec::new(),
+        })
+    }
+
+    pub fn write(&mut self, columns: &[(&str, &dyn ParquetWrite)]) -> Result<()> {
+        let encoded = columns
+            .iter()
+            .map(|(_, column)| column.encode(None))
+            .collect::<Result<Vec<_>>>()?;
+        let num_rows = encoded.first().map_or(0, |column| column.num_rows);
+        if let Some(((name, _), column)) = columns
+            .iter()
+            .zip(&encoded)
+            .find(|(_, column)| column.num_rows != num_rows)
+        {
+            return Err(Error::Schema(format!(
+                "column {name} has {} rows, expected {num_rows}",
+                column.num_rows
+            )));
+        }
+
+        let schema = columns
+            .iter()
+            .zip(&encoded)
+            .map(|((name, _), column)| (name.to_string(), column.leaf))
+            .collect::<Vec<_>>();
+        match &self.schema {
+            Some(expected) if *expected != schema => {
+                return Err(Error::Schema(
+                    "row group schema differs from the first row group".into(),
+                ))
+            }
+            Some(_) => {}
+            None => self.schema = Some(schema),
+        }
+
+        let mut row_group = RowGroup {
+            num_rows: num_rows as i64,
+            ..Default::default()
+        };
+        for ((name, _), column) in columns.iter().zip(encoded) {
+            let metadata = self.write_chunk(name, column)?;
+            row_group.total_byte_size += metadata.total_uncompressed_size;
+            row_group.columns.push(metadata);
+        }
+        self.row_groups.push(row_group);
+        Ok(())
+    }
+
+    fn write_chunk(&mut self, name: &str, column: EncodedColumn) -> Result<ColumnMetaData> {
+        let (max_def, max_rep) = column.leaf.shape.levels();
+        let mut buffer = Vec::new();
+        let mut body = Vec::new();
+        if max_rep > 0 {
+            write_levels(&column.rep_levels, max_rep, &mut body);
+        }
+        if max_def > 0 {
+            write_levels(&column.def_levels, max_def, &mut body);
+        }
+
+        let mut encodings = vec![ENCODING_RLE];
+        let mut dictionary_page_offset = None;
+        let encoding = match &column.dictionary {
+            Some((len, dictionary)) => {
+                dictionary_page_offset = Some(self.offset as i64);
+                write_page(
+                    &mut buffer,
+                    PAGE_DICTIONARY,
+                    *len,
+                    ENCODING_PLAIN,
+                    &dictionary.buffer,
+                );
+                let width = bit_width(len.saturating_sub(1) as u32);
+                body.push(width);
+                encode_hybrid(&column.indices, width, &mut body);
+                encodings.extend([ENCODING_PLAIN, ENCODING_RLE_DICTIONARY]);
+                ENCODING_RLE_DICTIONARY
+            }
+            None => {
+                body.extend_from_slice(&column.values.buffer);
+                encodings.push(ENCODING_PLAIN);
+                ENCODING_PLAIN
+            }
+        };
+        let data_page_offset = self.offset + buffer.len();
+        let num_values = if max_def > 0 {
+            column.def_levels.len()
+        } else {
+            column.num_rows
+        };
+        write_page(&mut buffer, PAGE_DATA, num_values, encoding, &body);
+
+        self.inner.write_all(&buffer)?;
+        self.offset += buffer.len();
+        Ok(ColumnMetaData {
+            physical_type: column.leaf.physical_type as i32,
+            encodings,
+            path: column_path(name, column.leaf.shape),
+            codec: CODEC_UNCOMPRESSED,
+            num_values: num_values as i64,
+            total_uncompressed_size: buffer.len() as i64,
+            total_compressed_size: buffer.len() as i64,
+            key_value: column.key_value,
+            data_page_offset: data_page_offset as i64,
+            dictionary_page_offset,
+        })
+    }
+
+    /// Writes the footer and returns the inner writer.
+    pub fn finish(mut self) -> Result<W> {
+        let schema = self.schema.take().unwrap_or_default();
+        let mut elements = vec![SchemaElement {
+            name: "schema".into(),
+            num_children: Some(schema.len() as i32),
+            ..Default::default()
+        }];
+        for (name, leaf) in &schema {
+            schema_elements(name, leaf, &mut elements);
+        }
+        let metadata = FileMetaData {
+            version: 1,
+            schema: elements,
+            num_rows: self.row_groups.iter().map(|group| group.num_rows).sum(),
+            row_groups: std::mem::take(&mut self.row_groups),
+            created_by: Some(concat!("types version ", env!("CARGO_PKG_VERSION")).into()),
+        };
+        let mut footer = Vec::new();
+        metadata.write(&mut footer);
+        self.inner.write_all(&footer)?;
+        self.inner.write_all(&(footer.len() as u32).to_le_bytes())?;
+        self.inner.write_all(MAGIC)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+fn write_levels(levels: &[u32], max: u32, out: &mut Vec<u8>) {
+    let start = out.len();
+    out.extend_from_slice(&[0; 4]);
+    encode_hybrid(levels, bit_width(max), out);
+    let len = (out.len() - start - 4) as u32;
+    out[start..start + 4].copy_from_slice(&len.to_le_bytes());
+}
+
+fn write_page(out: &mut Vec<u8>, page_type: i32, num_values: usize, encoding: i32, body: &[u8]) {
+    PageHeader {
+        page_type,
+        uncompressed_page_size: body.len() as i32,
+        compressed_page_size: body.len() as i32,
+        num_values: num_values as i32,
+        encoding,
+    }
+    .write(out);
+    out.extend_from_slice(body);
+}
+
+fn column_path(name: &str, shape: Shape) -> Vec<String> {
+    match shape {
+        Shape::List { .. } => vec![name.into(), "list".into(), "element".into()],
+        _ => vec![name.into()],
+    }
+}
+
+fn schema_elements(name: &str, leaf: &Leaf, out: &mut Vec<SchemaElement>) {
+    let element = |name: &str, repetition| SchemaElement {
+        physical_type: Some(leaf.physical_type as i32),
+        repetition: Some(repetition),
+        name: name.into(),
+        num_children: None,
+        converted_type: leaf.converted_type.map(|converted| converted as i32),
+    };
+    match leaf.shape {
+        Shape::Required => out.push(element(name, REQUIRED)),
+        Shape::Optional => out.push(element(name, OPTIONAL)),
+        Shape::List {
+            optional,
+            optional_element,
+        } => {
+            out.push(SchemaElement {
+                repetition: Some(if optional { OPTIONAL } else { REQUIRED }),
+                name: name.into(),
+                num_children: Some(1),
+                converted_type: Some(ConvertedType::List as i32),
+                ..Default::default()
+            });
+            out.push(SchemaElement {
+                repetition: Some(REPEATED),
+                name: "list".into(),
+                num_children: Some(1),
+                ..Default::default()
+            });
+            out.push(element(
+                "element",
+                if optional_element { OPTIONAL } else { REQUIRED },
+            ));
+        }
+    }
+}
@@ -1,8 +1,16 @@
-#![feature(split_array)]
+#![feature(portable_simd)]
+
 pub mod array;
+pub mod batch;
 pub mod bitvec;
+pub mod buffer;
+pub mod compute;
+pub mod datatype;
+pub mod io;
 pub mod primitive;
+pub mod row;
 pub mod scalar;
+pub mod schema;
 
 #[cfg(test)]
 mod tests {
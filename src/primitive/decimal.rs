@@ -0,0 +1,400 @@
+//! Fixed-point decimals whose precision and scale are part of the type.
+//!
+//! A `Decimal64<P, S>` stores `value * 10^S` in an `i64` and holds at most `P` significant
+//! digits. Arithmetic between different scales aligns both sides first and rounds the result
+//! half away from zero into the scale of the output type.
+
+use std::{
+    cmp::Ordering,
+    error, fmt,
+    hash::{Hash, Hasher},
+    ops::{Add, Neg, Sub},
+    str::FromStr,
+};
+
+use super::Primitive;
+
+/// `exp` must be a single type's scale, which is at most 38.
+#[inline]
+pub(crate) fn pow10(exp: u8) -> i128 {
+    10i128.pow(exp as u32)
+}
+
+/// `None` past `10^38`, which combined scales such as those of a product can reach.
+#[inline]
+fn checked_pow10(exp: u8) -> Option<i128> {
+    10i128.checked_pow(exp as u32)
+}
+
+/// Divides rounding half away from zero.
+#[inline]
+fn div_round(value: i128, divisor: i128) -> i128 {
+    let (quotient, remainder) = (value / divisor, value % divisor);
+    if remainder.unsigned_abs() >= divisor.unsigned_abs() - remainder.unsigned_abs() {
+        quotient + value.signum() * divisor.signum()
+    } else {
+        quotient
+    }
+}
+
+/// Moves `raw` from scale `from` to scale `to`, rounding when digits are dropped.
+#[inline]
+pub(crate) fn rescale(raw: i128, from: u8, to: u8) -> Option<i128> {
+    match to.cmp(&from) {
+        Ordering::Equal => Some(raw),
+        Ordering::Greater => raw.checked_mul(checked_pow10(to - from)?),
+        // Dropping more digits than an i128 holds always rounds to zero.
+        Ordering::Less => {
+            Some(checked_pow10(from - to).map_or(0, |divisor| div_round(raw, divisor)))
+        }
+    }
+}
+
+/// Orders two raw values of different scales.
+fn cmp_scaled(lhs: i128, lhs_scale: u8, rhs: i128, rhs_scale: u8) -> Ordering {
+    let scale = lhs_scale.max(rhs_scale);
+    match (
+        lhs.checked_mul(pow10(scale - lhs_scale)),
+        rhs.checked_mul(pow10(scale - rhs_scale)),
+    ) {
+        (Some(lhs), Some(rhs)) => lhs.cmp(&rhs),
+        // Only the side that was scaled up can overflow, and it then dominates.
+        (None, _) => lhs.cmp(&0),
+        (_, None) => 0.cmp(&rhs),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseDecimalError {
+    /// Not a decimal number.
+    Invalid,
+    /// More fractional digits than the scale holds.
+    Scale,
+    /// More significant digits than the precision allows.
+    Precision,
+}
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid => write!(f, "invalid decimal"),
+            Self::Scale => write!(f, "too many fractional digits for the decimal scale"),
+            Self::Precision => write!(f, "too many digits for the decimal precision"),
+        }
+    }
+}
+
+impl error::Error for ParseDecimalError {}
+
+/// Parses `[+-]digits[.digits]` into a raw value at `scale`.
+fn parse(text: &str, scale: u8) -> Result<i128, ParseDecimalError> {
+    let (negative, digits) = match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+    let valid = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+    if int.is_empty() && frac.is_empty() || !valid(int) || !valid(frac) {
+        return Err(ParseDecimalError::Invalid);
+    }
+    let kept = frac.len().min(scale as usize);
+    if frac[kept..].bytes().any(|byte| byte != b'0') {
+        return Err(ParseDecimalError::Scale);
+    }
+    let mut raw = 0i128;
+    for byte in int.bytes().chain(frac[..kept].bytes()) {
+        raw = raw
+            .checked_mul(10)
+            .and_then(|raw| raw.checked_add((byte - b'0') as i128))
+            .ok_or(ParseDecimalError::Precision)?;
+    }
+    let raw = raw
+        .checked_mul(pow10(scale - kept as u8))
+        .ok_or(ParseDecimalError::Precision)?;
+    Ok(if negative { -raw } else { raw })
+}
+
+fn format(raw: i128, scale: u8, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let sign = if raw < 0 { "-" } else { "" };
+    let abs = raw.unsigned_abs();
+    if scale == 0 {
+        return write!(f, "{sign}{abs}");
+    }
+    let divisor = pow10(scale) as u128;
+    write!(
+        f,
+        "{sign}{}.{:0width$}",
+        abs / divisor,
+        abs % divisor,
+        width = scale as usize
+    )
+}
+
+macro_rules! decimal {
+    ($name:ident, $int:ty, $max_precision:literal) => {
+        #[derive(Clone, Copy, Default, Eq)]
+        pub struct $name<const PRECISION: u8, const SCALE: u8>($int);
+
+        impl<const PRECISION: u8, const SCALE: u8> $name<PRECISION, SCALE> {
+            pub const MAX_PRECISION: u8 = $max_precision;
+
+            const VALID: () = assert!(
+                PRECISION >= 1 && PRECISION <= $max_precision && SCALE <= PRECISION,
+                "decimal scale must not exceed its precision, which must be in range"
+            );
+
+            pub const ZERO: Self = Self(0);
+            /// Largest value that fits the precision.
+            pub const MAX: Self = Self((10 as $int).pow(PRECISION as u32) - 1);
+            pub const MIN: Self = Self(-Self::MAX.0);
+
+            /// Wraps a value already multiplied by `10^SCALE`; `None` if it exceeds the precision.
+            #[inline]
+            pub fn from_raw(raw: $int) -> Option<Self> {
+                #[allow(clippy::let_unit_value)]
+                let () = Self::VALID;
+                (Self::MIN.0 <= raw && raw <= Self::MAX.0).then_some(Self(raw))
+            }
+
+            #[inline]
+            pub(crate) fn from_wide(raw: i128) -> Option<Self> {
+                <$int>::try_from(raw).ok().and_then(Self::from_raw)
+            }
+
+            #[inline]
+            pub const fn raw(self) -> $int {
+                self.0
+            }
+
+            #[inline]
+            pub const fn precision(self) -> u8 {
+                PRECISION
+            }
+
+            #[inline]
+            pub const fn scale(self) -> u8 {
+                SCALE
+            }
+
+            /// Converts to another precision and scale, rounding dropped digits.
+            #[inline]
+            pub fn rescale<const P: u8, const S: u8>(self) -> Option<$name<P, S>> {
+                $name::from_wide(rescale(self.0 as i128, SCALE, S)?)
+            }
+
+            #[inline]
+            pub fn checked_add<const P2: u8, const S2: u8, const P: u8, const S: u8>(
+                self,
+                rhs: $name<P2, S2>,
+            ) -> Option<$name<P, S>> {
+                let scale = SCALE.max(S2);
+                let lhs = rescale(self.0 as i128, SCALE, scale)?;
+                let rhs = rescale(rhs.0 as i128, S2, scale)?;
+                $name::from_wide(rescale(lhs.checked_add(rhs)?, scale, S)?)
+            }
+
+            #[inline]
+            pub fn checked_sub<const P2: u8, const S2: u8, const P: u8, const S: u8>(
+                self,
+                rhs: $name<P2, S2>,
+            ) -> Option<$name<P, S>> {
+                self.checked_add(-rhs)
+            }
+
+            /// `None` if the result or the unrounded product does not fit.
+            #[inline]
+            pub fn checked_mul<const P2: u8, const S2: u8, const P: u8, const S: u8>(
+                self,
+                rhs: $name<P2, S2>,
+            ) -> Option<$name<P, S>> {
+                let product = (self.0 as i128).checked_mul(rhs.0 as i128)?;
+                $name::from_wide(rescale(product, SCALE + S2, S)?)
+            }
+
+            /// `None` on division by zero or overflow.
+            #[inline]
+            pub fn checked_div<const P2: u8, const S2: u8, const P: u8, const S: u8>(
+                self,
+                rhs: $name<P2, S2>,
+            ) -> Option<$name<P, S>> {
+                if rhs.0 == 0 {
+                    return None;
+                }
+                let (lhs, rhs) = (self.0 as i128, rhs.0 as i128);
+                // lhs / rhs carries scale SCALE - S2; shift it to S before dividing.
+                let shift = S as i32 + S2 as i32 - SCALE as i32;
+                let quotient = if shift >= 0 {
+                    div_round(lhs.checked_mul(checked_pow10(shift as u8)?)?, rhs)
+                } else {
+                    div_round(lhs, rhs.checked_mul(checked_pow10(-shift as u8)?)?)
+                };
+                $name::from_wide(quotient)
+            }
+
+            #[inline]
+            pub fn to_f64(self) -> f64 {
+                self.0 as f64 / pow10(SCALE) as f64
+            }
+        }
+
+        impl<const P: u8, const S: u8, const P2: u8, const S2: u8> PartialEq<$name<P2, S2>>
+            for $name<P, S>
+        {
+            #[inline]
+            fn eq(&self, other: &$name<P2, S2>) -> bool {
+                cmp_scaled(self.0 as i128, S, other.0 as i128, S2) == Ordering::Equal
+            }
+        }
+
+        impl<const P: u8, const S: u8, const P2: u8, const S2: u8> PartialOrd<$name<P2, S2>>
+            for $name<P, S>
+        {
+            #[inline]
+            fn partial_cmp(&self, other: &$name<P2, S2>) -> Option<Ordering> {
+                Some(cmp_scaled(self.0 as i128, S, other.0 as i128, S2))
+            }
+        }
+
+        impl<const P: u8, const S: u8> Ord for $name<P, S> {
+            #[inline]
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+
+        impl<const P: u8, const S: u8> Hash for $name<P, S> {
+            #[inline]
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.hash(state)
+            }
+        }
+
+        impl<const P: u8, const S: u8> Neg for $name<P, S> {
+            type Output = Self;
+
+            #[inline]
+            fn neg(self) -> Self {
+                Self(-self.0)
+            }
+        }
+
+        /// Panics if the sum exceeds the precision.
+        impl<const P: u8, const S: u8> Add for $name<P, S> {
+            type Output = Self;
+
+            #[inline]
+            fn add(self, rhs: Self) -> Self {
+                self.checked_add(rhs).expect("decimal overflow")
+            }
+        }
+
+        impl<const P: u8, const S: u8> Sub for $name<P, S> {
+            type Output = Self;
+
+            #[inline]
+            fn sub(self, rhs: Self) -> Self {
+                self.checked_sub(rhs).expect("decimal overflow")
+            }
+        }
+
+        impl<const P: u8, const S: u8> FromStr for $name<P, S> {
+            type Err = ParseDecimalError;
+
+            fn from_str(text: &str) -> Result<Self, Self::Err> {
+                Self::from_wide(parse(text, S)?).ok_or(ParseDecimalError::Precision)
+            }
+        }
+
+        impl<const P: u8, const S: u8> fmt::Display for $name<P, S> {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                format(self.0 as i128, S, f)
+            }
+        }
+
+        impl<const P: u8, const S: u8> fmt::Debug for $name<P, S> {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(self, f)
+            }
+        }
+
+        impl<const P: u8, const S: u8> Primitive for $name<P, S> {}
+    };
+}
+
+decimal!(Decimal64, i64, 18);
+decimal!(Decimal128, i128, 38);
+
+#[cfg(test)]
+mod tests {
+    use super::{Decimal128, Decimal64, ParseDecimalError};
+    use crate::array::{primitive::PrimitiveArray, Array};
+
+    type Money = Decimal64<12, 2>;
+    type Rate = Decimal64<8, 4>;
+
+    #[test]
+    fn parse_and_format() {
+        let price: Money = "1234.5".parse().unwrap();
+        assert_eq!(price.raw(), 123_450);
+        assert_eq!(price.to_string(), "1234.50");
+        assert_eq!("-0.07".parse::<Money>().unwrap().to_string(), "-0.07");
+        assert_eq!("12".parse::<Decimal64<4, 0>>().unwrap().to_string(), "12");
+        assert_eq!("1.230".parse::<Money>().unwrap().raw(), 123);
+        assert_eq!("1.234".parse::<Money>(), Err(ParseDecimalError::Scale));
+        assert_eq!("1e3".parse::<Money>(), Err(ParseDecimalError::Invalid));
+        assert_eq!(".".parse::<Money>(), Err(ParseDecimalError::Invalid));
+        assert_eq!(
+            "10000000000".parse::<Money>(),
+            Err(ParseDecimalError::Precision)
+        );
+        let big: Decimal128<38, 10> = "1234567890123456789012345678.0123456789".parse().unwrap();
+        assert_eq!(big.to_string(), "1234567890123456789012345678.0123456789");
+    }
+
+    #[test]
+    fn arithmetic_and_compare() {
+        let price: Money = "19.99".parse().unwrap();
+        let rate: Rate = "0.0825".parse().unwrap();
+        let tax: Money = price.checked_mul(rate).unwrap();
+        assert_eq!(tax.to_string(), "1.65");
+        let total: Money = price.checked_add(tax).unwrap();
+        assert_eq!(total.to_string(), "21.64");
+        let precise: Decimal64<12, 6> = price.checked_mul(rate).unwrap();
+        assert_eq!(precise.to_string(), "1.649175");
+        let share: Rate = tax.checked_div(price).unwrap();
+        assert_eq!(share.to_string(), "0.0825");
+        assert_eq!(price.checked_div::<12, 2, 12, 2>(Money::ZERO), None);
+        assert_eq!(Money::MAX.checked_add::<12, 2, 12, 2>(tax), None);
+
+        // Combined scales past 10^38 must not overflow the power of ten.
+        let tiny: Decimal128<38, 20> = "0.00000000000000000001".parse().unwrap();
+        assert_eq!(
+            tiny.checked_mul::<38, 20, 38, 0>(tiny),
+            Some(Decimal128::ZERO)
+        );
+        let one: Decimal128<38, 0> = "1".parse().unwrap();
+        let other: Decimal128<38, 38> = "0.1".parse().unwrap();
+        assert_eq!(one.checked_div::<38, 38, 38, 38>(other), None);
+        assert_eq!((price - tax).to_string(), "18.34");
+
+        assert!(rate < price);
+        assert_eq!(
+            "1.5".parse::<Money>().unwrap(),
+            "1.5000".parse::<Rate>().unwrap()
+        );
+        assert!(Decimal128::<38, 0>::MAX > Decimal128::<38, 37>::MAX);
+        assert_eq!(
+            price.rescale::<4, 1>(),
+            Some("20.0".parse::<Decimal64<4, 1>>().unwrap())
+        );
+        assert_eq!(price.rescale::<2, 1>(), None);
+
+        let mut array = PrimitiveArray::new();
+        array.push(price);
+        array.push(-tax);
+        assert_eq!(array.get(1).unwrap().to_string(), "-1.65");
+    }
+}
@@ -0,0 +1,329 @@
+//! Half-precision floats stored as their 16 raw bits.
+//!
+//! [`f16`] is IEEE 754 binary16, [`bf16`] keeps the exponent range of `f32` with an 8-bit
+//! significand. Arithmetic is carried out in `f32` and rounded back to nearest, ties to even.
+
+#![allow(non_camel_case_types)]
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    num::ParseFloatError,
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use super::Primitive;
+
+/// Shifts `value` right by `shift` bits, rounding to nearest with ties to even.
+#[inline]
+const fn round_shift(value: u32, shift: u32) -> u32 {
+    let quotient = value >> shift;
+    let rest = value & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+    if rest > half || (rest == half && quotient & 1 == 1) {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+const fn f16_from_f32(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent == 0xff {
+        // Infinity stays infinite, NaN keeps its payload and is made quiet.
+        let nan = if mantissa != 0 {
+            0x0200 | (mantissa >> 13) as u16
+        } else {
+            0
+        };
+        return sign | 0x7c00 | nan;
+    }
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        sign | 0x7c00
+    } else if exponent > 0 {
+        // A carry out of the mantissa bumps the exponent, up to infinity.
+        sign | round_shift(((exponent as u32) << 23) | mantissa, 13) as u16
+    } else if exponent >= -10 {
+        let shift = (14 - exponent) as u32;
+        sign | round_shift(mantissa | 0x80_0000, shift) as u16
+    } else {
+        sign
+    }
+}
+
+const fn f16_to_f32(bits: u16) -> f32 {
+    let sign = ((bits & 0x8000) as u32) << 16;
+    let exponent = ((bits >> 10) & 0x1f) as u32;
+    let mantissa = (bits & 0x3ff) as u32;
+    match exponent {
+        0 => {
+            // Subnormals are exact multiples of 2^-24.
+            let magnitude = mantissa as f32 * f32::from_bits(0x3380_0000);
+            f32::from_bits(magnitude.to_bits() | sign)
+        }
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+    }
+}
+
+const fn bf16_from_f32(value: f32) -> u16 {
+    let bits = value.to_bits();
+    if value.is_nan() {
+        ((bits >> 16) | 0x0040) as u16
+    } else {
+        round_shift(bits, 16) as u16
+    }
+}
+
+const fn bf16_to_f32(bits: u16) -> f32 {
+    f32::from_bits((bits as u32) << 16)
+}
+
+macro_rules! half {
+    ($name:ident, $from_f32:ident, $to_f32:ident, $bits:literal) => {
+        #[derive(Clone, Copy, Default)]
+        #[repr(transparent)]
+        pub struct $name(u16);
+
+        impl $name {
+            pub const ZERO: Self = Self(0);
+            pub const ONE: Self = Self::from_f32(1.0);
+            pub const INFINITY: Self = Self::from_f32(f32::INFINITY);
+            pub const NEG_INFINITY: Self = Self::from_f32(f32::NEG_INFINITY);
+            pub const NAN: Self = Self::from_f32(f32::NAN);
+            pub const MAX: Self = Self::from_bits($bits);
+            pub const MIN: Self = Self::from_bits($bits | 0x8000);
+
+            #[inline]
+            pub const fn from_bits(bits: u16) -> Self {
+                Self(bits)
+            }
+
+            #[inline]
+            pub const fn to_bits(self) -> u16 {
+                self.0
+            }
+
+            /// Rounds to the nearest representable value; out-of-range values become infinite.
+            #[inline]
+            pub const fn from_f32(value: f32) -> Self {
+                Self($from_f32(value))
+            }
+
+            /// Goes through `f32`, which can round twice for values between two halves.
+            #[inline]
+            pub const fn from_f64(value: f64) -> Self {
+                Self::from_f32(value as f32)
+            }
+
+            #[inline]
+            pub const fn to_f32(self) -> f32 {
+                $to_f32(self.0)
+            }
+
+            #[inline]
+            pub const fn to_f64(self) -> f64 {
+                self.to_f32() as f64
+            }
+
+            #[inline]
+            pub const fn is_nan(self) -> bool {
+                self.to_f32().is_nan()
+            }
+
+            #[inline]
+            pub const fn is_finite(self) -> bool {
+                self.to_f32().is_finite()
+            }
+
+            #[inline]
+            pub const fn abs(self) -> Self {
+                Self(self.0 & 0x7fff)
+            }
+
+            /// Like [`f32::min`], a NaN operand yields the other one.
+            #[inline]
+            pub fn min(self, other: Self) -> Self {
+                Self::from_f32(self.to_f32().min(other.to_f32()))
+            }
+
+            #[inline]
+            pub fn max(self, other: Self) -> Self {
+                Self::from_f32(self.to_f32().max(other.to_f32()))
+            }
+        }
+
+        impl Primitive for $name {}
+
+        impl From<$name> for f32 {
+            #[inline]
+            fn from(value: $name) -> Self {
+                value.to_f32()
+            }
+        }
+
+        impl From<$name> for f64 {
+            #[inline]
+            fn from(value: $name) -> Self {
+                value.to_f64()
+            }
+        }
+
+        impl PartialEq for $name {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.to_f32() == other.to_f32()
+            }
+        }
+
+        impl PartialOrd for $name {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                self.to_f32().partial_cmp(&other.to_f32())
+            }
+        }
+
+        /// Consistent with `==` for every value but NaN: both zeros hash alike, and so does
+        /// every NaN.
+        impl Hash for $name {
+            #[inline]
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                let bits = if self.is_nan() {
+                    Self::NAN.0
+                } else if self.0 & 0x7fff == 0 {
+                    0
+                } else {
+                    self.0
+                };
+                bits.hash(state)
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            #[inline]
+            fn neg(self) -> Self {
+                Self(self.0 ^ 0x8000)
+            }
+        }
+
+        half!(@op $name, Add, add, AddAssign, add_assign, +);
+        half!(@op $name, Sub, sub, SubAssign, sub_assign, -);
+        half!(@op $name, Mul, mul, MulAssign, mul_assign, *);
+
+        impl Div for $name {
+            type Output = Self;
+
+            #[inline]
+            fn div(self, rhs: Self) -> Self {
+                Self::from_f32(self.to_f32() / rhs.to_f32())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseFloatError;
+
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.parse().map(Self::from_f32)
+            }
+        }
+
+        impl fmt::Display for $name {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.to_f32(), f)
+            }
+        }
+
+        impl fmt::Debug for $name {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&self.to_f32(), f)
+            }
+        }
+    };
+    (@op $name:ident, $trait:ident, $method:ident, $assign:ident, $assign_method:ident, $op:tt) => {
+        impl $trait for $name {
+            type Output = Self;
+
+            #[inline]
+            fn $method(self, rhs: Self) -> Self {
+                Self::from_f32(self.to_f32() $op rhs.to_f32())
+            }
+        }
+
+        impl $assign for $name {
+            #[inline]
+            fn $assign_method(&mut self, rhs: Self) {
+                *self = *self $op rhs;
+            }
+        }
+    };
+}
+
+half!(f16, f16_from_f32, f16_to_f32, 0x7bff);
+half!(bf16, bf16_from_f32, bf16_to_f32, 0x7f7f);
+
+#[cfg(test)]
+mod tests {
+    use super::{bf16, f16};
+
+    #[test]
+    fn f16_round_trips() {
+        for bits in 0..=u16::MAX {
+            let value = f16::from_bits(bits);
+            let back = f16::from_f32(value.to_f32());
+            if value.is_nan() {
+                assert!(back.is_nan());
+            } else {
+                assert_eq!(back.to_bits(), bits);
+            }
+        }
+        assert_eq!(f16::MAX.to_f32(), 65504.0);
+        assert_eq!(f16::from_f32(65520.0), f16::INFINITY);
+        assert_eq!(f16::from_bits(1).to_f32(), 2f32.powi(-24));
+        assert_eq!(f16::from_f32(2f32.powi(-25)).to_bits(), 0);
+        assert_eq!(f16::from_f32(3.0 * 2f32.powi(-25)).to_bits(), 2);
+        // 1 + 2^-11 lies halfway between 1 and the next value and rounds to the even one.
+        assert_eq!(f16::from_f32(1.0 + 2f32.powi(-11)), f16::ONE);
+    }
+
+    #[test]
+    fn bf16_rounds() {
+        assert_eq!(bf16::from_f32(1.0).to_bits(), 0x3f80);
+        assert_eq!(bf16::from_f32(f32::MAX), bf16::INFINITY);
+        assert_eq!(bf16::MAX.to_f32(), f32::from_bits(0x7f7f_0000));
+        assert_eq!(
+            bf16::from_f32(f32::from_bits(0x3f80_8000)).to_bits(),
+            0x3f80
+        );
+        assert_eq!(
+            bf16::from_f32(f32::from_bits(0x3f81_8000)).to_bits(),
+            0x3f82
+        );
+        assert!(bf16::from_f32(f32::NAN).is_nan());
+    }
+
+    #[test]
+    fn arithmetic_and_hash() {
+        use std::hash::BuildHasher;
+
+        let state = std::collections::hash_map::RandomState::new();
+        let (a, b) = (bf16::from_f32(1.5), bf16::from_f32(2.25));
+        assert_eq!(a + b, bf16::from_f32(3.75));
+        assert_eq!(b - a, bf16::from_f32(0.75));
+        assert_eq!(-(a * b), bf16::from_f32(-3.375));
+        assert!(a < b && f16::NAN != f16::NAN);
+        assert_eq!(f16::ZERO, -f16::ZERO);
+        assert_eq!(state.hash_one(f16::ZERO), state.hash_one(-f16::ZERO));
+        assert_eq!("0.5".parse::<f16>().unwrap().to_string(), "0.5");
+    }
+}
@@ -0,0 +1,33 @@
+pub mod decimal;
+pub mod half;
+pub mod numeric;
+pub mod temporal;
+
+pub use decimal::{Decimal128, Decimal64, ParseDecimalError};
+pub use half::{bf16, f16};
+pub use numeric::{FloatPrimitive, IntegerPrimitive, NumericPrimitive, PrimitiveType, Total};
+pub use temporal::{Date32, Duration, TimeZone, Timestamp};
+
+pub trait Primitive: 'static + Default + Clone + Copy + PartialEq + Send + Sync {}
+
+macro_rules! native_type {
+    ($type:ty) => {
+        impl Primitive for $type {}
+    };
+}
+
+native_type!(bool);
+native_type!(u8);
+native_type!(u16);
+native_type!(u32);
+native_type!(u64);
+native_type!(u128);
+native_type!(usize);
+native_type!(i8);
+native_type!(i16);
+native_type!(i32);
+native_type!(i64);
+native_type!(i128);
+native_type!(isize);
+native_type!(f32);
+native_type!(f64);
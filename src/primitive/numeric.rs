@@ -0,0 +1,334 @@
+//! Numeric metadata on top of [`Primitive`].
+//!
+//! Floats have no `Eq`, `Ord` or `Hash`, so generic code goes through
+//! [`NumericPrimitive::total_cmp`] and [`NumericPrimitive::hash_total`] instead, or wraps values
+//! in [`Total`] where a type has to satisfy those traits, e.g. to dictionary-encode floats.
+//! Inherent float methods of the same name take precedence in method calls, so call the trait
+//! methods by path on concrete float types.
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use super::{bf16, f16, Primitive};
+
+/// Identifies a numeric primitive at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrimitiveType {
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+    F16,
+    BF16,
+    F32,
+    F64,
+}
+
+impl PrimitiveType {
+    #[inline]
+    pub const fn is_float(self) -> bool {
+        matches!(
+            self,
+            PrimitiveType::F16 | PrimitiveType::BF16 | PrimitiveType::F32 | PrimitiveType::F64
+        )
+    }
+}
+
+pub trait NumericPrimitive: Primitive + PartialOrd {
+    const TYPE: PrimitiveType;
+    const BITS: u32;
+    const MIN: Self;
+    const MAX: Self;
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// `[u8; BITS / 8]`.
+    type Bytes: Copy + Default + AsRef<[u8]> + AsMut<[u8]>;
+
+    fn to_le_bytes(self) -> Self::Bytes;
+
+    fn from_le_bytes(bytes: Self::Bytes) -> Self;
+
+    /// Orders negative zero before zero and every NaN after all other values.
+    fn total_cmp(&self, other: &Self) -> Ordering;
+
+    /// Hashes consistently with [`total_cmp`](Self::total_cmp): all NaNs hash alike.
+    fn hash_total<H: Hasher>(&self, state: &mut H);
+}
+
+pub trait IntegerPrimitive: NumericPrimitive + Eq + Ord + Hash {
+    const SIGNED: bool;
+}
+
+pub trait FloatPrimitive: NumericPrimitive {
+    type Bits: IntegerPrimitive;
+
+    const NAN: Self;
+    const INFINITY: Self;
+    const NEG_INFINITY: Self;
+
+    fn is_nan(self) -> bool;
+
+    fn to_bits(self) -> Self::Bits;
+
+    fn from_bits(bits: Self::Bits) -> Self;
+
+    /// The bits of the value with every NaN mapped to [`NAN`](Self::NAN).
+    #[inline]
+    fn canonical_bits(self) -> Self::Bits {
+        if self.is_nan() {
+            Self::NAN.to_bits()
+        } else {
+            self.to_bits()
+        }
+    }
+}
+
+macro_rules! integer_primitive {
+    ($type:ty, $variant:ident, $signed:literal) => {
+        impl NumericPrimitive for $type {
+            const TYPE: PrimitiveType = PrimitiveType::$variant;
+            const BITS: u32 = <$type>::BITS;
+            const MIN: Self = <$type>::MIN;
+            const MAX: Self = <$type>::MAX;
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            type Bytes = [u8; std::mem::size_of::<$type>()];
+
+            #[inline]
+            fn to_le_bytes(self) -> Self::Bytes {
+                <$type>::to_le_bytes(self)
+            }
+
+            #[inline]
+            fn from_le_bytes(bytes: Self::Bytes) -> Self {
+                <$type>::from_le_bytes(bytes)
+            }
+
+            #[inline]
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                self.cmp(other)
+            }
+
+            #[inline]
+            fn hash_total<H: Hasher>(&self, state: &mut H) {
+                self.hash(state)
+            }
+        }
+
+        impl IntegerPrimitive for $type {
+            const SIGNED: bool = $signed;
+        }
+    };
+}
+
+integer_primitive!(u8, U8, false);
+integer_primitive!(u16, U16, false);
+integer_primitive!(u32, U32, false);
+integer_primitive!(u64, U64, false);
+integer_primitive!(u128, U128, false);
+integer_primitive!(usize, Usize, false);
+integer_primitive!(i8, I8, true);
+integer_primitive!(i16, I16, true);
+integer_primitive!(i32, I32, true);
+integer_primitive!(i64, I64, true);
+integer_primitive!(i128, I128, true);
+integer_primitive!(isize, Isize, true);
+
+macro_rules! float_primitive {
+    ($type:ty, $variant:ident, $bits:ty, $zero:expr, $one:expr, $wide:expr) => {
+        impl NumericPrimitive for $type {
+            const TYPE: PrimitiveType = PrimitiveType::$variant;
+            const BITS: u32 = <$bits>::BITS;
+            const MIN: Self = <$type>::MIN;
+            const MAX: Self = <$type>::MAX;
+            const ZERO: Self = $zero;
+            const ONE: Self = $one;
+
+            type Bytes = [u8; std::mem::size_of::<$type>()];
+
+            #[inline]
+            fn to_le_bytes(self) -> Self::Bytes {
+                <$type>::to_bits(self).to_le_bytes()
+            }
+
+            #[inline]
+            fn from_le_bytes(bytes: Self::Bytes) -> Self {
+                <$type>::from_bits(<$bits>::from_le_bytes(bytes))
+            }
+
+            #[inline]
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                match (self.is_nan(), other.is_nan()) {
+                    (false, false) => $wide(*self).total_cmp(&$wide(*other)),
+                    (lhs, rhs) => lhs.cmp(&rhs),
+                }
+            }
+
+            #[inline]
+            fn hash_total<H: Hasher>(&self, state: &mut H) {
+                self.canonical_bits().hash(state)
+            }
+        }
+
+        impl FloatPrimitive for $type {
+            type Bits = $bits;
+
+            const NAN: Self = <$type>::NAN;
+            const INFINITY: Self = <$type>::INFINITY;
+            const NEG_INFINITY: Self = <$type>::NEG_INFINITY;
+
+            #[inline]
+            fn is_nan(self) -> bool {
+                <$type>::is_nan(self)
+            }
+
+            #[inline]
+            fn to_bits(self) -> $bits {
+                <$type>::to_bits(self)
+            }
+
+            #[inline]
+            fn from_bits(bits: $bits) -> Self {
+                <$type>::from_bits(bits)
+            }
+        }
+    };
+}
+
+float_primitive!(f16, F16, u16, f16::ZERO, f16::ONE, f16::to_f32);
+float_primitive!(bf16, BF16, u16, bf16::ZERO, bf16::ONE, bf16::to_f32);
+float_primitive!(f32, F32, u32, 0.0, 1.0, std::convert::identity);
+float_primitive!(f64, F64, u64, 0.0, 1.0, std::convert::identity);
+
+/// A numeric value compared, ordered and hashed by [`NumericPrimitive::total_cmp`], so that
+/// floats can be sorted and used as dictionary values.
+#[derive(Clone, Copy, Default)]
+#[repr(transparent)]
+pub struct Total<P>(pub P);
+
+impl<P: NumericPrimitive> Primitive for Total<P> {}
+
+impl<P: NumericPrimitive> PartialEq for Total<P> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.total_cmp(&other.0).is_eq()
+    }
+}
+
+impl<P: NumericPrimitive> Eq for Total<P> {}
+
+impl<P: NumericPrimitive> PartialOrd for Total<P> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: NumericPrimitive> Ord for Total<P> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl<P: NumericPrimitive> Hash for Total<P> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash_total(state)
+    }
+}
+
+impl<P: fmt::Display> fmt::Display for Total<P> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<P: fmt::Debug> fmt::Debug for Total<P> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cmp::Ordering, hash::BuildHasher};
+
+    use super::{FloatPrimitive, NumericPrimitive, PrimitiveType, Total};
+    use crate::{
+        array::{id::IdArray, primitive::PrimitiveArray, Array},
+        compute::sort,
+        primitive::bf16,
+    };
+
+    fn round_trip<P: NumericPrimitive + std::fmt::Debug>(values: &[P]) {
+        for &value in values {
+            let bytes = value.to_le_bytes();
+            assert_eq!(bytes.as_ref().len() as u32 * 8, P::BITS);
+            assert_eq!(P::from_le_bytes(bytes).total_cmp(&value), Ordering::Equal);
+        }
+    }
+
+    #[test]
+    fn metadata_and_bytes() {
+        assert_eq!(<i128 as NumericPrimitive>::TYPE, PrimitiveType::I128);
+        assert_eq!(<bf16 as NumericPrimitive>::BITS, 16);
+        assert!(<f32 as NumericPrimitive>::TYPE.is_float());
+        assert_eq!(<u16 as NumericPrimitive>::MAX, u16::MAX);
+        round_trip(&[u128::MAX, 0, 1 << 90]);
+        round_trip(&[-1isize, isize::MIN]);
+        round_trip(&[f64::NAN, -0.0, 1e300]);
+        round_trip(&[bf16::from_f32(-2.5), bf16::INFINITY]);
+        // NaN payloads survive the round trip.
+        let nan = f32::from_bits(0x7fc0_0001);
+        let bytes = NumericPrimitive::to_le_bytes(nan);
+        assert_eq!(
+            <f32 as NumericPrimitive>::from_le_bytes(bytes).to_bits(),
+            0x7fc0_0001
+        );
+    }
+
+    #[test]
+    fn total_order_and_hash() {
+        let state = std::collections::hash_map::RandomState::new();
+        let nan = f32::from_bits(0xffc0_1234);
+        assert!(nan.is_nan());
+        let cmp = NumericPrimitive::total_cmp;
+        assert_eq!(cmp(&nan, &f32::NAN), Ordering::Equal);
+        assert_eq!(cmp(&nan, &f32::INFINITY), Ordering::Greater);
+        assert_eq!(cmp(&-0.0f32, &0.0), Ordering::Less);
+        assert_eq!(nan.canonical_bits(), f32::NAN.to_bits());
+        assert_eq!(state.hash_one(Total(nan)), state.hash_one(Total(f32::NAN)));
+
+        let values = PrimitiveArray {
+            data: [2.5, f64::NAN, -1.0, 0.0, -0.0, f64::NAN, 2.5]
+                .map(Total)
+                .to_vec(),
+        };
+        let sorted = sort(&values);
+        assert_eq!(
+            format!("{:?}", sorted.as_slice()),
+            "[-1.0, -0.0, 0.0, 2.5, 2.5, NaN, NaN]"
+        );
+
+        let encoded = IdArray::encode(&values);
+        assert_eq!(encoded.values.data.len(), 5);
+        assert_eq!(encoded.get(5), encoded.get(1));
+        assert_ne!(encoded.get(3), encoded.get(4));
+    }
+}
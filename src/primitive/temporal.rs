@@ -0,0 +1,613 @@
+//! Dates, timestamps and durations.
+//!
+//! Timestamps count units since the Unix epoch in UTC. A [`TimeZone`] is column metadata: it
+//! is not stored per value but passed to the calendar kernels, which then work in local time.
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::{Add, Neg, Sub},
+    str::FromStr,
+};
+
+use super::Primitive;
+
+/// Identifies a [`TimeUnit`] at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeUnitType {
+    Second,
+    Millisecond,
+    Microsecond,
+    Nanosecond,
+}
+
+impl TimeUnitType {
+    #[inline]
+    pub const fn suffix(self) -> &'static str {
+        match self {
+            TimeUnitType::Second => Second::SUFFIX,
+            TimeUnitType::Millisecond => Millisecond::SUFFIX,
+            TimeUnitType::Microsecond => Microsecond::SUFFIX,
+            TimeUnitType::Nanosecond => Nanosecond::SUFFIX,
+        }
+    }
+}
+
+pub trait TimeUnit: 'static + Copy + Default + Eq + Hash + fmt::Debug + Send + Sync {
+    const TYPE: TimeUnitType;
+    const PER_SECOND: i64;
+    /// Fractional digits when formatting.
+    const DIGITS: usize;
+    const SUFFIX: &'static str;
+}
+
+macro_rules! time_unit {
+    ($name:ident, $per_second:literal, $digits:literal, $suffix:literal) => {
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+        pub struct $name;
+
+        impl TimeUnit for $name {
+            const TYPE: TimeUnitType = TimeUnitType::$name;
+            const PER_SECOND: i64 = $per_second;
+            const DIGITS: usize = $digits;
+            const SUFFIX: &'static str = $suffix;
+        }
+    };
+}
+
+time_unit!(Second, 1, 0, "s");
+time_unit!(Millisecond, 1_000, 3, "ms");
+time_unit!(Microsecond, 1_000_000, 6, "us");
+time_unit!(Nanosecond, 1_000_000_000, 9, "ns");
+
+/// Calendar and clock fields of a point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DatePart {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+/// A fixed offset from UTC.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TimeZone {
+    offset: i32,
+}
+
+impl TimeZone {
+    pub const UTC: Self = Self { offset: 0 };
+
+    /// `None` unless the offset is within ±24 hours.
+    #[inline]
+    pub fn from_offset_seconds(offset: i32) -> Option<Self> {
+        (offset.abs() < 86_400).then_some(Self { offset })
+    }
+
+    #[inline]
+    pub fn offset_seconds(self) -> i32 {
+        self.offset
+    }
+}
+
+/// Parses `Z`, `UTC` or `±HH:MM` with two digits each and an offset below 24 hours.
+impl FromStr for TimeZone {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Self, ()> {
+        if text == "Z" || text == "UTC" {
+            return Ok(Self::UTC);
+        }
+        let sign = match text.as_bytes().first() {
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            _ => return Err(()),
+        };
+        let digits = |part: &str| match part.as_bytes() {
+            &[tens @ b'0'..=b'9', ones @ b'0'..=b'9'] => {
+                Ok((tens - b'0') as i32 * 10 + (ones - b'0') as i32)
+            }
+            _ => Err(()),
+        };
+        let (hours, minutes) = text[1..].split_once(':').ok_or(())?;
+        let (hours, minutes) = (digits(hours)?, digits(minutes)?);
+        if hours >= 24 || minutes >= 60 {
+            return Err(());
+        }
+        Self::from_offset_seconds(sign * (hours * 3_600 + minutes * 60)).ok_or(())
+    }
+}
+
+impl fmt::Display for TimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.offset == 0 {
+            return write!(f, "Z");
+        }
+        let sign = if self.offset < 0 { '-' } else { '+' };
+        let minutes = self.offset.abs() / 60;
+        write!(f, "{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month + 2) / 5 + 1) as u32;
+    let month = if month < 10 { month + 3 } else { month - 9 } as u32;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since the Unix epoch.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date32(i32);
+
+impl Date32 {
+    #[inline]
+    pub const fn from_days(days: i32) -> Self {
+        Self(days)
+    }
+
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year as i64, month) {
+            return None;
+        }
+        i32::try_from(days_from_civil(year as i64, month, day))
+            .ok()
+            .map(Self)
+    }
+
+    #[inline]
+    pub const fn days(self) -> i32 {
+        self.0
+    }
+
+    /// Year, month and day.
+    #[inline]
+    pub fn ymd(self) -> (i32, u32, u32) {
+        let (year, month, day) = civil_from_days(self.0 as i64);
+        (year as i32, month, day)
+    }
+
+    #[inline]
+    pub fn checked_add_days(self, days: i32) -> Option<Self> {
+        self.0.checked_add(days).map(Self)
+    }
+
+    /// Midnight UTC of this day.
+    #[inline]
+    pub fn to_timestamp<U: TimeUnit>(self) -> Option<Timestamp<U>> {
+        (self.0 as i64)
+            .checked_mul(86_400 * U::PER_SECOND)
+            .map(Timestamp::from_raw)
+    }
+}
+
+impl fmt::Display for Date32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{year:04}-{month:02}-{day:02}")
+    }
+}
+
+impl fmt::Debug for Date32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Parses `YYYY-MM-DD`.
+impl FromStr for Date32 {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Self, ()> {
+        let mut parts = text.splitn(3, '-');
+        let mut next = || parts.next().ok_or(());
+        let year = next()?.parse().map_err(|_| ())?;
+        let month = next()?.parse().map_err(|_| ())?;
+        let day = next()?.parse().map_err(|_| ())?;
+        Self::from_ymd(year, month, day).ok_or(())
+    }
+}
+
+macro_rules! unit_value {
+    ($name:ident) => {
+        impl<U: TimeUnit> $name<U> {
+            #[inline]
+            pub const fn from_raw(raw: i64) -> Self {
+                Self(raw, PhantomData)
+            }
+
+            #[inline]
+            pub const fn raw(self) -> i64 {
+                self.0
+            }
+
+            /// Converts to another unit, rounding towards negative infinity; `None` on overflow.
+            #[inline]
+            pub fn to_unit<V: TimeUnit>(self) -> Option<$name<V>> {
+                let raw = if V::PER_SECOND >= U::PER_SECOND {
+                    self.0.checked_mul(V::PER_SECOND / U::PER_SECOND)?
+                } else {
+                    self.0.div_euclid(U::PER_SECOND / V::PER_SECOND)
+                };
+                Some($name::from_raw(raw))
+            }
+        }
+
+        impl<U> Clone for $name<U> {
+            #[inline]
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<U> Copy for $name<U> {}
+
+        impl<U> Default for $name<U> {
+            #[inline]
+            fn default() -> Self {
+                Self(0, PhantomData)
+            }
+        }
+
+        impl<U> PartialEq for $name<U> {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl<U> Eq for $name<U> {}
+
+        impl<U> PartialOrd for $name<U> {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<U> Ord for $name<U> {
+            #[inline]
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+
+        impl<U> Hash for $name<U> {
+            #[inline]
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.hash(state)
+            }
+        }
+
+        impl<U: TimeUnit> Primitive for $name<U> {}
+    };
+}
+
+/// A point in time as units since the Unix epoch, UTC.
+pub struct Timestamp<U>(i64, PhantomData<U>);
+
+/// A signed span of time.
+pub struct Duration<U>(i64, PhantomData<U>);
+
+unit_value!(Timestamp);
+unit_value!(Duration);
+
+impl<U: TimeUnit> Timestamp<U> {
+    /// `None` if any field is out of range or the result overflows.
+    pub fn from_ymd_hms(
+        year: i32,
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+    ) -> Option<Self> {
+        if hour >= 24 || minute >= 60 || second >= 60 {
+            return None;
+        }
+        let seconds = (hour * 3_600 + minute * 60 + second) as i64;
+        let day = Date32::from_ymd(year, month, day)?.to_timestamp::<U>()?;
+        day.0
+            .checked_add(seconds * U::PER_SECOND)
+            .map(Self::from_raw)
+    }
+
+    /// Units since the epoch in local time, saturating within a day of the `i64` range.
+    #[inline]
+    fn local(self, zone: TimeZone) -> i64 {
+        self.0.saturating_add(zone.offset as i64 * U::PER_SECOND)
+    }
+
+    #[inline]
+    fn from_local(local: i64, zone: TimeZone) -> Self {
+        Self::from_raw(local.saturating_sub(zone.offset as i64 * U::PER_SECOND))
+    }
+
+    /// Days since the epoch in local time, which may exceed the range of [`Date32`].
+    #[inline]
+    fn local_days(self, zone: TimeZone) -> i64 {
+        self.local(zone).div_euclid(86_400 * U::PER_SECOND)
+    }
+
+    /// Saturates at the range of [`Date32`] for timestamps further than about 5.8 million years
+    /// from the epoch.
+    #[inline]
+    pub fn date(self, zone: TimeZone) -> Date32 {
+        let days = self.local_days(zone);
+        Date32(days.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+
+    pub fn get(self, part: DatePart, zone: TimeZone) -> i64 {
+        let local = self.local(zone);
+        let seconds = local.div_euclid(U::PER_SECOND);
+        let ymd = || civil_from_days(self.local_days(zone));
+        match part {
+            DatePart::Year => ymd().0,
+            DatePart::Month => ymd().1 as i64,
+            DatePart::Day => ymd().2 as i64,
+            DatePart::Hour => seconds.rem_euclid(86_400) / 3_600,
+            DatePart::Minute => seconds.rem_euclid(3_600) / 60,
+            DatePart::Second => seconds.rem_euclid(60),
+        }
+    }
+
+    /// Rounds down to the start of the enclosing `part` in the given zone, saturating at the
+    /// earliest timestamp if that start is before it.
+    pub fn truncate(self, part: DatePart, zone: TimeZone) -> Self {
+        let local = self.local(zone);
+        let floor = |unit: i64| local - local.rem_euclid(unit * U::PER_SECOND);
+        let local = match part {
+            DatePart::Second => floor(1),
+            DatePart::Minute => floor(60),
+            DatePart::Hour => floor(3_600),
+            DatePart::Day => floor(86_400),
+            DatePart::Month | DatePart::Year => {
+                let (year, month, _) = civil_from_days(self.local_days(zone));
+                let month = if part == DatePart::Year { 1 } else { month };
+                days_from_civil(year, month, 1).saturating_mul(86_400 * U::PER_SECOND)
+            }
+        };
+        Self::from_local(local, zone)
+    }
+
+    #[inline]
+    pub fn checked_add(self, duration: Duration<U>) -> Option<Self> {
+        self.0.checked_add(duration.0).map(Self::from_raw)
+    }
+
+    #[inline]
+    pub fn checked_sub(self, other: Self) -> Option<Duration<U>> {
+        self.0.checked_sub(other.0).map(Duration::from_raw)
+    }
+}
+
+impl<U: TimeUnit> Add<Duration<U>> for Timestamp<U> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, duration: Duration<U>) -> Self {
+        Self::from_raw(self.0 + duration.0)
+    }
+}
+
+impl<U: TimeUnit> Sub<Duration<U>> for Timestamp<U> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, duration: Duration<U>) -> Self {
+        Self::from_raw(self.0 - duration.0)
+    }
+}
+
+impl<U: TimeUnit> Sub for Timestamp<U> {
+    type Output = Duration<U>;
+
+    #[inline]
+    fn sub(self, other: Self) -> Duration<U> {
+        Duration::from_raw(self.0 - other.0)
+    }
+}
+
+/// ISO 8601 in UTC with as many fractional digits as the unit has.
+impl<U: TimeUnit> fmt::Display for Timestamp<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.0.div_euclid(U::PER_SECOND);
+        let (year, month, day) = civil_from_days(self.local_days(TimeZone::UTC));
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+            seconds.rem_euclid(86_400) / 3_600,
+            seconds.rem_euclid(3_600) / 60,
+            seconds.rem_euclid(60)
+        )?;
+        if U::DIGITS > 0 {
+            write!(
+                f,
+                ".{:0width$}",
+                self.0.rem_euclid(U::PER_SECOND),
+                width = U::DIGITS
+            )?;
+        }
+        write!(f, "Z")
+    }
+}
+
+impl<U: TimeUnit> fmt::Debug for Timestamp<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl<U: TimeUnit> Duration<U> {
+    #[inline]
+    pub fn from_seconds(seconds: i64) -> Option<Self> {
+        seconds.checked_mul(U::PER_SECOND).map(Self::from_raw)
+    }
+
+    #[inline]
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self::from_raw)
+    }
+}
+
+impl<U: TimeUnit> Add for Duration<U> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self::from_raw(self.0 + other.0)
+    }
+}
+
+impl<U: TimeUnit> Sub for Duration<U> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self::from_raw(self.0 - other.0)
+    }
+}
+
+impl<U: TimeUnit> Neg for Duration<U> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self::from_raw(-self.0)
+    }
+}
+
+impl<U: TimeUnit> fmt::Display for Duration<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.0, U::SUFFIX)
+    }
+}
+
+impl<U: TimeUnit> fmt::Debug for Duration<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl Primitive for Date32 {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Date32, DatePart, Duration, Millisecond, Second, TimeZone, Timestamp};
+
+    #[test]
+    fn calendar() {
+        let date = Date32::from_ymd(2024, 2, 29).unwrap();
+        assert_eq!(date.days(), 19_782);
+        assert_eq!(date.to_string(), "2024-02-29");
+        assert_eq!(date.checked_add_days(1).unwrap().ymd(), (2024, 3, 1));
+        assert_eq!(Date32::from_ymd(2023, 2, 29), None);
+        assert_eq!("1969-12-31".parse::<Date32>().unwrap().days(), -1);
+        assert_eq!(Date32::from_days(-719_468).ymd(), (0, 3, 1));
+
+        let zone = "+05:30".parse::<TimeZone>().unwrap();
+        assert_eq!(zone.offset_seconds(), 19_800);
+        assert_eq!(zone.to_string(), "+05:30");
+        assert_eq!("-08:00".parse::<TimeZone>().unwrap().to_string(), "-08:00");
+        assert!("+25:00".parse::<TimeZone>().is_err());
+        for text in [
+            "+999999:00",
+            "+24:00",
+            "+1:00",
+            "++1:00",
+            "+-1:00",
+            "+01:+5",
+            "+:00",
+            "-",
+        ] {
+            assert!(text.parse::<TimeZone>().is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn timestamps() {
+        let ts = Timestamp::<Millisecond>::from_ymd_hms(2024, 12, 31, 22, 15, 30).unwrap()
+            + Duration::from_raw(250);
+        assert_eq!(ts.to_string(), "2024-12-31T22:15:30.250Z");
+        assert_eq!(ts.get(DatePart::Hour, TimeZone::UTC), 22);
+        let zone = "+02:00".parse().unwrap();
+        assert_eq!(ts.get(DatePart::Year, zone), 2025);
+        assert_eq!(ts.get(DatePart::Hour, zone), 0);
+        assert_eq!(ts.date(zone).to_string(), "2025-01-01");
+        assert_eq!(
+            ts.truncate(DatePart::Month, TimeZone::UTC).to_string(),
+            "2024-12-01T00:00:00.000Z"
+        );
+        assert_eq!(
+            ts.truncate(DatePart::Year, zone).to_string(),
+            "2024-12-31T22:00:00.000Z"
+        );
+        assert_eq!(
+            ts.truncate(DatePart::Minute, zone).to_string(),
+            "2024-12-31T22:15:00.000Z"
+        );
+
+        let earlier = Timestamp::<Millisecond>::from_ymd_hms(2024, 12, 31, 0, 0, 0).unwrap();
+        let elapsed = ts - earlier;
+        assert_eq!(elapsed.to_string(), "80130250ms");
+        assert_eq!(elapsed.to_unit::<Second>().unwrap().raw(), 80_130);
+        assert_eq!(earlier + elapsed, ts);
+
+        let zone = "+23:00".parse().unwrap();
+        let last = Timestamp::<Second>::from_raw(i64::MAX);
+        assert_eq!(last.get(DatePart::Year, zone), 292_277_026_596);
+        assert_eq!(last.date(zone), Date32::from_days(i32::MAX));
+        assert_eq!(last.to_string(), "292277026596-12-04T15:30:07Z");
+        let first = Timestamp::<Second>::from_raw(i64::MIN);
+        assert_eq!(
+            first.get(DatePart::Year, "-23:00".parse().unwrap()),
+            -292_277_022_657
+        );
+        assert_eq!(first.date(TimeZone::UTC), Date32::from_days(i32::MIN));
+        assert_eq!(first.truncate(DatePart::Year, TimeZone::UTC), first);
+        assert_eq!(
+            last.truncate(DatePart::Month, TimeZone::UTC)
+                .get(DatePart::Day, zone),
+            1
+        );
+
+        let before_epoch = Timestamp::<Second>::from_raw(-1);
+        assert_eq!(before_epoch.to_string(), "1969-12-31T23:59:59Z");
+        assert_eq!(
+            Timestamp::<Millisecond>::from_raw(-1)
+                .to_unit::<Second>()
+                .unwrap(),
+            before_epoch
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Rows over tuples of arrays.
+//!
+//! [`rows`] walks a tuple of array references in lockstep and yields tuples of their item
+//! references; [`RowBuilder`] pushes tuples of items into a tuple of arrays. Both are typed
+//! through the tuple, so no dynamic dispatch is involved. Tuples of up to eight columns are
+//! supported.
+
+use std::{iter::FusedIterator, ops::Range};
+
+use crate::{
+    array::{Array, DynArray, TypedArray},
+    batch::RecordBatch,
+    schema::SchemaError,
+};
+
+/// A tuple of array references of equal length.
+pub trait Columns: Copy {
+    type Row;
+
+    /// `None` if the columns differ in length.
+    fn row_count(&self) -> Option<usize>;
+
+    /// # Safety
+    ///
+    /// `row` must be less than the length of every column.
+    unsafe fn row_unchecked(&self, row: usize) -> Self::Row;
+}
+
+/// [`Columns`] that can be taken from a [`RecordBatch`] by downcasting.
+pub trait TypedColumns<'c>: Columns {
+    fn from_columns(columns: &'c [DynArray]) -> Option<Self>;
+}
+
+/// A tuple of owned arrays filled row by row.
+pub trait OwnedColumns {
+    type Row;
+
+    fn push_row(&mut self, row: Self::Row);
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn into_dyn(self) -> Vec<DynArray>
+    where
+        Self: Sized;
+}
+
+macro_rules! tuple_columns {
+    ($($A:ident $a:ident $i:tt),+) => {
+        impl<'c, $($A: Array),+> Columns for ($(&'c $A,)+) {
+            type Row = ($($A::ItemRef<'c>,)+);
+
+            #[inline]
+            fn row_count(&self) -> Option<usize> {
+                let len = self.0.len();
+                ($(self.$i.len() == len)&&+).then_some(len)
+            }
+
+            #[inline]
+            unsafe fn row_unchecked(&self, row: usize) -> Self::Row {
+                ($(self.$i.get_unchecked(row),)+)
+            }
+        }
+
+        impl<'c, $($A: TypedArray),+> TypedColumns<'c> for ($(&'c $A,)+) {
+            #[inline]
+            fn from_columns(columns: &'c [DynArray]) -> Option<Self> {
+                let [$($a),+] = columns else {
+                    return None;
+                };
+                Some(($($a.downcast_ref::<$A>()?,)+))
+            }
+        }
+
+        impl<$($A: TypedArray),+> OwnedColumns for ($($A,)+) {
+            type Row = ($($A::Item,)+);
+
+            #[inline]
+            fn push_row(&mut self, row: Self::Row) {
+                $(self.$i.push(row.$i);)+
+            }
+
+            #[inline]
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            #[inline]
+            fn into_dyn(self) -> Vec<DynArray> {
+                vec![$(DynArray::new(self.$i)),+]
+            }
+        }
+    };
+}
+
+tuple_columns!(A a 0);
+tuple_columns!(A a 0, B b 1);
+tuple_columns!(A a 0, B b 1, C c 2);
+tuple_columns!(A a 0, B b 1, C c 2, D d 3);
+tuple_columns!(A a 0, B b 1, C c 2, D d 3, E e 4);
+tuple_columns!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5);
+tuple_columns!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6);
+tuple_columns!(A a 0, B b 1, C c 2, D d 3, E e 4, F f 5, G g 6, H h 7);
+
+#[derive(Debug, Clone)]
+pub struct Rows<C> {
+    columns: C,
+    range: Range<usize>,
+}
+
+/// Iterates the rows of `columns`, which must all have the same length.
+///
+/// # Panics
+///
+/// If the lengths differ.
+#[inline]
+pub fn rows<C: Columns>(columns: C) -> Rows<C> {
+    let len = columns.row_count().expect("columns differ in length");
+    Rows {
+        columns,
+        range: 0..len,
+    }
+}
+
+impl<C: Columns> Iterator for Rows<C> {
+    type Item = C::Row;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.range.next()?;
+        Some(unsafe { self.columns.row_unchecked(row) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let row = self.range.nth(n)?;
+        Some(unsafe { self.columns.row_unchecked(row) })
+    }
+}
+
+impl<C: Columns> DoubleEndedIterator for Rows<C> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let row = self.range.next_back()?;
+        Some(unsafe { self.columns.row_unchecked(row) })
+    }
+}
+
+impl<C: Columns> ExactSizeIterator for Rows<C> {}
+
+impl<C: Columns> FusedIterator for Rows<C> {}
+
+impl RecordBatch {
+    /// The columns as a tuple of concrete arrays, `None` if the count or a type differs.
+    #[inline]
+    pub fn typed_columns<'c, C: TypedColumns<'c>>(&'c self) -> Option<C> {
+        C::from_columns(self.columns())
+    }
+
+    /// Typed rows of the batch, `None` if the columns are not `C`.
+    #[inline]
+    pub fn rows<'c, C: TypedColumns<'c>>(&'c self) -> Option<Rows<C>> {
+        self.typed_columns().map(rows)
+    }
+}
+
+/// Collects rows into a tuple of arrays.
+#[derive(Debug, Clone, Default)]
+pub struct RowBuilder<C> {
+    columns: C,
+}
+
+impl<C: OwnedColumns> RowBuilder<C> {
+    /// Rows are appended to `columns`, which must have equal lengths.
+    #[inline]
+    pub fn new(columns: C) -> Self {
+        Self { columns }
+    }
+
+    #[inline]
+    pub fn push(&mut self, row: C::Row) {
+        self.columns.push_row(row)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    #[inline]
+    pub fn finish(self) -> C {
+        self.columns
+    }
+
+    /// Names the columns in order, see [`RecordBatch::from_columns`].
+    pub fn into_batch<N: Into<String>>(
+        self,
+        names: impl IntoIterator<Item = N>,
+    ) -> Result<RecordBatch, SchemaError> {
+        let columns = self.columns.into_dyn();
+        let names = names.into_iter().map(Into::into).collect::<Vec<String>>();
+        if names.len() != columns.len() {
+            return Err(SchemaError::ColumnCount {
+                expected: columns.len(),
+                found: names.len(),
+            });
+        }
+        RecordBatch::from_columns(names.into_iter().zip(columns))
+    }
+}
+
+impl<C: OwnedColumns> Extend<C::Row> for RowBuilder<C> {
+    #[inline]
+    fn extend<T: IntoIterator<Item = C::Row>>(&mut self, rows: T) {
+        for row in rows {
+            self.push(row);
+        }
+    }
+}
+
+impl<C: OwnedColumns + Default> FromIterator<C::Row> for RowBuilder<C> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = C::Row>>(rows: T) -> Self {
+        let mut builder = Self::default();
+        builder.extend(rows);
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rows, RowBuilder};
+    use crate::array::{
+        id::IdArray, list::ListArray, primitive::PrimitiveArray, Array, OptionArray,
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Event {
+        id: u32,
+        payload: Vec<u8>,
+        tag: Option<Vec<u8>>,
+    }
+
+    type EventColumns = (PrimitiveArray<u32>, ListArray<u8>, IdArray<ListArray<u8>>);
+
+    fn events() -> Vec<Event> {
+        (0..5u32)
+            .map(|id| Event {
+                id,
+                payload: vec![id as u8; id as usize],
+                tag: (id % 2 == 0).then(|| format!("t{}", id % 4).into_bytes()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rows_round_trip() {
+        let builder = events()
+            .into_iter()
+            .map(|event| (event.id, event.payload, event.tag))
+            .collect::<RowBuilder<EventColumns>>();
+        assert_eq!(builder.len(), 5);
+        let (ids, payloads, tags) = builder.finish();
+        assert_eq!(tags.values.data.len(), 2);
+
+        let decoded = rows((&ids, &payloads, &tags))
+            .map(|(&id, payload, tag)| Event {
+                id,
+                payload: payload.to_vec(),
+                tag: tag.map(<[u8]>::to_vec),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(decoded, events());
+        let (id, payload, tag) = rows((&ids, &payloads, &tags)).next_back().unwrap();
+        assert_eq!((id, payload, tag), (&4, &[4u8; 4][..], Some(&b"t0"[..])));
+    }
+
+    #[test]
+    fn batch_rows() {
+        let mut builder = RowBuilder::new((
+            PrimitiveArray::<u32>::new(),
+            OptionArray::new(PrimitiveArray::<f64>::new()),
+        ));
+        builder.extend([(1, Some(0.5)), (2, None)]);
+        let batch = builder.into_batch(["id", "score"]).unwrap();
+        assert!(batch
+            .rows::<(&PrimitiveArray<u32>, &PrimitiveArray<f64>)>()
+            .is_none());
+        let typed = batch
+            .rows::<(&PrimitiveArray<u32>, &OptionArray<PrimitiveArray<f64>>)>()
+            .unwrap();
+        assert_eq!(typed.len(), 2);
+        assert_eq!(
+            typed.collect::<Vec<_>>(),
+            vec![(&1, Some(&0.5)), (&2, None)]
+        );
+
+        let ids = PrimitiveArray::<u32>::new();
+        let mut other = PrimitiveArray::<u32>::new();
+        other.push(1);
+        assert!(std::panic::catch_unwind(|| rows((&ids, &other)).count()).is_err());
+    }
+}
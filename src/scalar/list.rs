@@ -1,8 +1,14 @@
-use std::ops::{Range, Sub};
+use std::{
+    any::{Any, TypeId},
+    iter::FusedIterator,
+    ops::{Range, Sub},
+    slice,
+};
 
 use super::{Scalar, ScalarMut, ScalarRef};
 use crate::{
     bitvec::{BitSlice, BitSliceMut, BitVec},
+    compute::arithmetic,
     primitive::Primitive,
 };
 
@@ -45,6 +51,11 @@ impl<P: Primitive> OptionList<P> {
             None => None,
         }
     }
+
+    #[inline]
+    pub fn iter(&self) -> OptionSliceIter<'_, P> {
+        self.as_ref().into_iter()
+    }
 }
 
 impl<P: Primitive> Default for OptionList<P> {
@@ -121,10 +132,128 @@ impl<'slice, P: Primitive> OptionSlice<'slice, P> {
             None
         }
     }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> OptionSliceIter<'slice, P> {
+        self.clone().into_iter()
+    }
+}
+
+/// Yields `None` for null elements and `Some(&value)` otherwise.
+#[derive(Debug, Clone)]
+pub struct OptionSliceIter<'slice, P> {
+    slice: OptionSlice<'slice, P>,
+    range: Range<usize>,
+}
+
+impl<'slice, P: Primitive> OptionSliceIter<'slice, P> {
+    #[inline]
+    fn element(&self, n: usize) -> Option<&'slice P> {
+        self.slice
+            .validity
+            .get(n)
+            .unwrap_or(false)
+            .then(|| &self.slice.data[n])
+    }
+}
+
+impl<'slice, P: Primitive> Iterator for OptionSliceIter<'slice, P> {
+    type Item = Option<&'slice P>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.range.next()?;
+        Some(self.element(n))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<P: Primitive> DoubleEndedIterator for OptionSliceIter<'_, P> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let n = self.range.next_back()?;
+        Some(self.element(n))
+    }
+}
+
+impl<P: Primitive> ExactSizeIterator for OptionSliceIter<'_, P> {}
+
+impl<P: Primitive> FusedIterator for OptionSliceIter<'_, P> {}
+
+impl<'slice, P: Primitive> IntoIterator for OptionSlice<'slice, P> {
+    type Item = Option<&'slice P>;
+    type IntoIter = OptionSliceIter<'slice, P>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        OptionSliceIter {
+            range: 0..self.data.len(),
+            slice: self,
+        }
+    }
+}
+
+impl<'slice, P: Primitive> IntoIterator for &OptionSlice<'slice, P> {
+    type Item = Option<&'slice P>;
+    type IntoIter = OptionSliceIter<'slice, P>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
 
 impl<'slice, P: Primitive> ScalarRef<'slice> for OptionSlice<'slice, P> {
     type Owned = OptionList<P>;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        OptionList {
+            validity: self.validity.to_vec(),
+            data: self.data.to_vec(),
+        }
+    }
+}
+
+/// Subtracts through the SIMD kernel for floats, whose vector subtraction matches `Sub`. Other
+/// types keep their `Sub` impl on the `valid` rows, so integers still overflow as they would in
+/// scalar code instead of wrapping like the kernel does.
+fn sub_values<P: Primitive + Sub<Output = P>>(lhs: &[P], rhs: &[P], valid: BitSlice<'_>) -> Vec<P> {
+    macro_rules! simd {
+        ($($type:ty),*) => {$(
+            if TypeId::of::<P>() == TypeId::of::<$type>() {
+                // SAFETY: `P` is `$type`.
+                let (lhs, rhs) = unsafe {
+                    (
+                        slice::from_raw_parts(lhs.as_ptr().cast::<$type>(), lhs.len()),
+                        slice::from_raw_parts(rhs.as_ptr().cast::<$type>(), rhs.len()),
+                    )
+                };
+                let data: Box<dyn Any> = Box::new(arithmetic::sub(lhs, rhs));
+                return *data.downcast().unwrap();
+            }
+        )*};
+    }
+    simd!(f32, f64);
+    lhs.iter()
+        .zip(rhs)
+        .zip(valid)
+        .map(|((&lhs, &rhs), valid)| if valid { lhs - rhs } else { P::default() })
+        .collect()
 }
 
 impl<'slice, P: Primitive + Sub<Output = P>> Sub for OptionSlice<'slice, P> {
@@ -133,14 +262,10 @@ impl<'slice, P: Primitive + Sub<Output = P>> Sub for OptionSlice<'slice, P> {
     #[inline]
     fn sub(self, rhs: Self) -> Self::Output {
         #[allow(clippy::suspicious_arithmetic_impl)]
+        let validity = self.validity & rhs.validity;
         OptionList {
-            validity: self.validity & rhs.validity,
-            data: self
-                .data
-                .iter()
-                .zip(rhs.data)
-                .map(|(l, r)| *l - *r)
-                .collect(),
+            data: sub_values(self.data, rhs.data, validity.as_slice()),
+            validity,
         }
     }
 }
@@ -181,7 +306,7 @@ impl<'slice, P: Primitive> ScalarMut<'slice> for OptionSliceMut<'slice, P> {
     }
 }
 
-impl<S: Scalar, const SIZE: usize> Scalar for [S; SIZE] {
+impl<S: Scalar + Clone, const SIZE: usize> Scalar for [S; SIZE] {
     type Ref<'r> = &'r [S; SIZE]
     where
         Self: 'r;
@@ -204,7 +329,13 @@ impl<S: Scalar, const SIZE: usize> Scalar for [S; SIZE] {
 #[cfg(test)]
 mod tests {
     use super::OptionList;
-    use crate::scalar::Scalar;
+    use crate::{
+        primitive::{
+            bf16,
+            temporal::{Duration, Second},
+        },
+        scalar::Scalar,
+    };
 
     #[test]
     fn sub_option_slice() {
@@ -216,4 +347,65 @@ mod tests {
         assert_eq!(result.get(2), Some(&5));
         assert_eq!(result.get(3), None);
     }
+
+    #[test]
+    fn sub_shifted_slices() {
+        let list = OptionList::from(
+            (0..40)
+                .map(|i| (i % 9 != 4).then_some(i * i))
+                .collect::<Vec<_>>(),
+        );
+        let row = list.as_ref();
+        let deltas = row.slice(1..40) - row.slice(0..39);
+        assert_eq!(deltas.get(0), Some(&1));
+        assert_eq!(deltas.get(3), None);
+        assert_eq!(deltas.get(4), None);
+        assert_eq!(deltas.get(5), Some(&11));
+        assert_eq!(deltas.get(38), Some(&77));
+    }
+
+    #[test]
+    fn iterate_option_slice() {
+        let list = OptionList::from([Some(1u8), None, Some(3), None]);
+        assert_eq!(
+            list.iter().collect::<Vec<_>>(),
+            [Some(&1), None, Some(&3), None]
+        );
+        let row = list.as_ref();
+        let tail = row.slice(1..4);
+        assert_eq!(tail.len(), 3);
+        assert_eq!(tail.iter().rev().flatten().collect::<Vec<_>>(), [&3]);
+    }
+
+    #[test]
+    fn sub_wide_and_half() {
+        let ids = OptionList::from(vec![Some(1u128 << 100), Some(u128::MAX), None]);
+        let deltas = ids.as_ref().slice(1..3) - ids.as_ref().slice(0..2);
+        assert_eq!(deltas.get(0), Some(&(u128::MAX - (1 << 100))));
+        assert_eq!(deltas.get(1), None);
+
+        let values = [1.0, 2.5, -4.0].map(|v| Some(bf16::from_f32(v))).to_vec();
+        let values = OptionList::from(values);
+        let deltas = values.as_ref().slice(1..3) - values.as_ref().slice(0..2);
+        assert_eq!(deltas.get(0), Some(&bf16::from_f32(1.5)));
+        assert_eq!(deltas.get(1), Some(&bf16::from_f32(-6.5)));
+
+        let values = OptionList::from((0..20).map(|v| Some(v as f64 * 0.5)).collect::<Vec<_>>());
+        let deltas = values.as_ref().slice(1..20) - values.as_ref().slice(0..19);
+        assert!(deltas.iter().all(|delta| delta == Some(&0.5)));
+        let durations = [3, 10]
+            .map(|s| Some(Duration::<Second>::from_raw(s)))
+            .to_vec();
+        let durations = OptionList::from(durations);
+        let deltas = durations.as_ref().slice(1..2) - durations.as_ref().slice(0..1);
+        assert_eq!(deltas.get(0), Some(&Duration::from_raw(7)));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "overflow")]
+    fn sub_overflow_panics() {
+        let values = OptionList::from(vec![Some(i32::MIN), Some(1)]);
+        let _ = values.as_ref().slice(0..1) - values.as_ref().slice(1..2);
+    }
 }
@@ -17,6 +17,8 @@ pub trait Scalar: 'static + Sized {
 
 pub trait ScalarRef<'r> {
     type Owned: Scalar;
+
+    fn into_owned(self) -> Self::Owned;
 }
 
 pub trait ScalarMut<'r> {
@@ -30,9 +32,14 @@ pub trait ScalarMut<'r> {
 
 impl<'r, T> ScalarRef<'r> for &'r T
 where
-    T: Scalar,
+    T: Scalar + Clone,
 {
     type Owned = T;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        self.clone()
+    }
 }
 
 impl<'r, T> ScalarMut<'r> for &'r mut T
@@ -50,7 +57,7 @@ where
     }
 }
 
-impl<T: 'static> Scalar for Vec<T> {
+impl<T: 'static + Clone> Scalar for Vec<T> {
     type Ref<'r> = &'r [T]
     where
         Self: 'r;
@@ -70,11 +77,16 @@ impl<T: 'static> Scalar for Vec<T> {
     }
 }
 
-impl<'r, T: 'static> ScalarRef<'r> for &'r [T] {
+impl<'r, T: 'static + Clone> ScalarRef<'r> for &'r [T] {
     type Owned = Vec<T>;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        self.to_vec()
+    }
 }
 
-impl<'r, T: 'static> ScalarMut<'r> for &'r mut [T] {
+impl<'r, T: 'static + Clone> ScalarMut<'r> for &'r mut [T] {
     type Owned = Vec<T>;
 
     #[inline]
@@ -108,6 +120,11 @@ impl<S: Scalar> Scalar for Option<S> {
 
 impl<'r, S: ScalarRef<'r>> ScalarRef<'r> for Option<S> {
     type Owned = Option<S::Owned>;
+
+    #[inline]
+    fn into_owned(self) -> Self::Owned {
+        self.map(S::into_owned)
+    }
 }
 
 impl<'r, S: ScalarMut<'r>> ScalarMut<'r> for Option<S> {
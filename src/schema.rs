@@ -0,0 +1,234 @@
+//! Named, typed columns.
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::datatype::DataType;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    DuplicateField(String),
+    UnknownField(String),
+    ColumnCount {
+        expected: usize,
+        found: usize,
+    },
+    Length {
+        field: String,
+        expected: usize,
+        found: usize,
+    },
+    Type {
+        field: String,
+        expected: DataType,
+        found: DataType,
+    },
+    /// The column can hold nulls but the field is not nullable.
+    Nullability(String),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::DuplicateField(name) => write!(f, "duplicate field `{name}`"),
+            SchemaError::UnknownField(name) => write!(f, "unknown field `{name}`"),
+            SchemaError::ColumnCount { expected, found } => {
+                write!(f, "expected {expected} columns, found {found}")
+            }
+            SchemaError::Length {
+                field,
+                expected,
+                found,
+            } => write!(f, "field `{field}` has {found} rows, expected {expected}"),
+            SchemaError::Type {
+                field,
+                expected,
+                found,
+            } => write!(f, "field `{field}` is {found}, expected {expected}"),
+            SchemaError::Nullability(name) => write!(f, "field `{name}` is not nullable"),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub data_type: DataType,
+    /// Nullable fields accept `Option` and `Id` columns.
+    pub nullable: bool,
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Field {
+    /// The field is nullable if the data type can hold nulls.
+    #[inline]
+    pub fn new(name: impl Into<String>, data_type: DataType) -> Self {
+        Self {
+            name: name.into(),
+            nullable: data_type.is_nullable(),
+            data_type,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    #[inline]
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Checks that a column of `data_type` fits the field.
+    pub fn validate(&self, data_type: &DataType) -> Result<(), SchemaError> {
+        if *data_type != self.data_type {
+            return Err(SchemaError::Type {
+                field: self.name.clone(),
+                expected: self.data_type.clone(),
+                found: data_type.clone(),
+            });
+        }
+        if !self.nullable && data_type.is_nullable() {
+            return Err(SchemaError::Nullability(self.name.clone()));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.data_type)?;
+        if !self.nullable {
+            f.write_str(" not null")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Schema {
+    fields: Vec<Field>,
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Schema {
+    /// Field names must be unique.
+    pub fn new(fields: Vec<Field>) -> Result<Self, SchemaError> {
+        for (i, field) in fields.iter().enumerate() {
+            if fields[..i].iter().any(|other| other.name == field.name) {
+                return Err(SchemaError::DuplicateField(field.name.clone()));
+            }
+        }
+        Ok(Self {
+            fields,
+            metadata: BTreeMap::new(),
+        })
+    }
+
+    #[inline]
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    #[inline]
+    pub fn field(&self, index: usize) -> Option<&Field> {
+        self.fields.get(index)
+    }
+
+    #[inline]
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|field| field.name == name)
+    }
+
+    #[inline]
+    pub fn field_with_name(&self, name: &str) -> Option<&Field> {
+        self.index_of(name).map(|index| &self.fields[index])
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// The fields at `indices`, in that order, keeping the schema metadata.
+    pub fn project(&self, indices: &[usize]) -> Result<Self, SchemaError> {
+        let fields = indices
+            .iter()
+            .map(|&index| {
+                self.fields
+                    .get(index)
+                    .cloned()
+                    .ok_or_else(|| SchemaError::UnknownField(format!("#{index}")))
+            })
+            .collect::<Result<_, _>>()?;
+        let mut schema = Self::new(fields)?;
+        schema.metadata = self.metadata.clone();
+        Ok(schema)
+    }
+
+    /// Indices of the named fields.
+    pub fn indices_of(&self, names: &[&str]) -> Result<Vec<usize>, SchemaError> {
+        names
+            .iter()
+            .map(|&name| {
+                self.index_of(name)
+                    .ok_or_else(|| SchemaError::UnknownField(name.to_owned()))
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, field) in self.fields.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{field}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Field, Schema, SchemaError};
+    use crate::{datatype::DataType, primitive::PrimitiveType};
+
+    #[test]
+    fn fields_and_projection() {
+        let id = Field::new("id", DataType::Numeric(PrimitiveType::U128));
+        let score = Field::new(
+            "score",
+            DataType::Option(Box::new(DataType::Numeric(PrimitiveType::F32))),
+        )
+        .with_metadata("unit", "points");
+        assert!(!id.nullable && score.nullable);
+        assert_eq!(
+            id.validate(&score.data_type),
+            Err(SchemaError::Type {
+                field: "id".into(),
+                expected: id.data_type.clone(),
+                found: score.data_type.clone(),
+            })
+        );
+
+        let schema = Schema::new(vec![id.clone(), score]).unwrap();
+        assert_eq!(schema.to_string(), "id: u128 not null, score: option<f32>");
+        assert_eq!(schema.index_of("score"), Some(1));
+        let projected = schema.project(&[1]).unwrap();
+        assert_eq!(projected.fields()[0].metadata["unit"], "points");
+        assert_eq!(
+            schema.indices_of(&["score", "name"]),
+            Err(SchemaError::UnknownField("name".into()))
+        );
+        assert_eq!(
+            Schema::new(vec![id.clone(), id]),
+            Err(SchemaError::DuplicateField("id".into()))
+        );
+    }
+}